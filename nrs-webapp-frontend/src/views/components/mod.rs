@@ -1,4 +1,6 @@
 pub mod footer;
+pub(crate) mod form;
 pub mod link;
 pub mod navbar;
+pub mod pager;
 pub mod toast;
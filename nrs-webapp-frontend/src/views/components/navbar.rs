@@ -29,7 +29,7 @@ fn theme_controller() -> impl Renderable {
 /// The header includes primary route links, a brand link, a theme toggle, and authentication controls:
 /// - Desktop and mobile navigation for the routes "Home" and "Entries".
 /// - A theme toggle control.
-/// - When `logged_in` is `true`, a user avatar with a dropdown containing "Profile" and a "Logoff" action (POST to `/auth/logoff`).
+/// - When `logged_in` is `true`, a user avatar with a dropdown containing "Account" and a "Logoff" action (POST to `/auth/logoff`).
 /// - When `logged_in` is `false`, a "Log in" button that loads the login fragment via HTMX.
 ///
 /// # Parameters
@@ -88,7 +88,7 @@ pub fn navbar(logged_in: bool) -> impl Renderable {
                         </div>
 
                         <ul tabindex="-1" class="mt-3 z-50 p-2 shadow menu menu-sm dropdown-content bg-base-100 rounded-box w-32">
-                            <li><Link params=(LinkParams { href: "/profile", ..Default::default() })>Profile</Link></li>
+                            <li><Link params=(LinkParams { href: "/account", ..Default::default() })>Account</Link></li>
                             <li><Link params=(LinkParams { href: "/auth/logoff", hx_vals: "{\"logoff\":true}", method: Method::POST, ..Default::default() })>Logoff</Link></li>
                         </ul>
                     </div>
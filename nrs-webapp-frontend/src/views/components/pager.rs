@@ -0,0 +1,39 @@
+use hypertext::prelude::*;
+
+use crate::views::components::link::{Link, LinkParams};
+
+/// Renders the links to the previous and next pages of a list, each disabled when `None`.
+///
+/// # Examples
+///
+/// ```
+/// use hypertext::prelude::*;
+/// use nrs_webapp_frontend::views::components::pager::Pager;
+/// let _node = rsx! {
+///     <Pager prev_href=(None) next_href=(Some("/entry?cursor=abc")) total=(Some(42)) />
+/// };
+/// ```
+#[component]
+pub fn pager<'a>(
+    prev_href: Option<&'a str>,
+    next_href: Option<&'a str>,
+    total: Option<u64>,
+) -> impl Renderable {
+    rsx! {
+        <nav class="flex items-center gap-4">
+            @if let Some(href) = prev_href {
+                <Link params=(LinkParams { href, class: "btn btn-sm", ..Default::default() })>"Previous"</Link>
+            } @else {
+                <button class="btn btn-sm" disabled>"Previous"</button>
+            }
+            @if let Some(total) = total {
+                <span class="text-sm opacity-80">(total) " in total"</span>
+            }
+            @if let Some(href) = next_href {
+                <Link params=(LinkParams { href, class: "btn btn-sm", ..Default::default() })>"Next"</Link>
+            } @else {
+                <button class="btn btn-sm" disabled>"Next"</button>
+            }
+        </nav>
+    }
+}
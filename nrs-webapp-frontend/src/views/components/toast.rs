@@ -114,7 +114,7 @@ pub fn toast_component<'a>(toast: &'a Toast) -> impl Renderable {
                 </div>
 
 
-                <button class="btn btn-ghost close-button" onclick={"
+                <button class="btn btn-ghost close-button" hx-on:click={"
                     const a = this.closest('.alert');
                     a.classList.add('opacity-0');
                     setTimeout(() => a.remove(), "(fade_out_duration_ms)");
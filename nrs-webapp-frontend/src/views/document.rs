@@ -13,11 +13,17 @@ pub struct DocumentProps {
     pub error: bool,
     pub logged_in: bool,
     pub toasts: Vec<Toast>,
+    /// Sent back in the `X-CSRF-Token` header of every HTMX request made from the page.
+    pub csrf_token: Option<String>,
+    /// Nonce of the page's `Content-Security-Policy`, set on every `<script>` tag.
+    pub csp_nonce: Option<String>,
 }
 
 /// Renders the full HTML document shell for the application, including head assets, a toast container, navbar, page content, and footer.
 ///
-/// The output sets `lang="en"`, applies the "winter" theme, and exposes an `data-is-error` attribute matching `props.error`. Toasts from `props.toasts` are rendered into a fixed toast root. In debug builds an embedded live-reload script is included; it is omitted in non-debug builds.
+/// The output sets `lang="en"`, applies the "winter" theme, and exposes an `data-is-error` attribute matching `props.error`. When
+/// `props.csrf_token` is set, the body carries an inherited `hx-headers` attribute adding it to all HTMX requests. All
+/// `<script>` tags carry `props.csp_nonce`, so that they are allowed by the Content-Security-Policy. Toasts from `props.toasts` are rendered into a fixed toast root. In debug builds an embedded live-reload script is included; it is omitted in non-debug builds.
 ///
 /// # Examples
 ///
@@ -26,7 +32,7 @@ pub struct DocumentProps {
 /// use hypertext::prelude::*;
 ///
 /// let _html = rsx! {
-///    <Document props=(DocumentProps { error: false, logged_in: true, toasts: vec![] })>
+///    <Document props=(DocumentProps { error: false, logged_in: true, toasts: vec![], csrf_token: None, csp_nonce: None })>
 ///    // page content here
 ///    </Document>
 /// };
@@ -40,6 +46,12 @@ pub fn document<R: Renderable>(props: &DocumentProps, children: &R) -> impl Rend
         Raw::dangerously_create(include_str!("../inline_scripts/live-reload.js"));
     #[cfg(not(debug_assertions))]
     let live_reload_script = "";
+    // the token is URL-safe base64, so it needs no escaping inside the JSON string
+    let hx_headers = props.csrf_token.as_ref().map_or_else(
+        || "{}".into(),
+        |token| format!(r#"{{"X-CSRF-Token":"{token}"}}"#),
+    );
+    let nonce = props.csp_nonce.as_deref();
     rsx! {
         <!DOCTYPE html>
         <html lang="en" data-theme="winter" data-is-error=(props.error)>
@@ -50,18 +62,19 @@ pub fn document<R: Renderable>(props: &DocumentProps, children: &R) -> impl Rend
                     name="viewport"
                     content="width=device-width, initial-scale=1.0"
                 >
-                <script src="/static/htmx.min.js"></script>
-                <script src="/static/create-entry-form.js" type="module"></script>
-                <script src="/static/toast-on-load.js" type="module" defer></script>
-                <script src="/static/alpine.min.js" defer></script>
+                <script nonce=(nonce) src="/static/htmx.min.js"></script>
+                <script nonce=(nonce) src="/static/create-entry-form.js" type="module"></script>
+                <script nonce=(nonce) src="/static/toast-on-load.js" type="module" defer></script>
+                <script nonce=(nonce) src="/static/alpine.min.js" defer></script>
                 <link rel="stylesheet" href="/static/prism.css">
                 <link rel="stylesheet" href="/static/generated/output.css">
-                <script>
+                <script nonce=(nonce)>
                     (live_reload_script)
                 </script>
-                <script src="/static/theme-controller.js" type="module"></script>
+                <script nonce=(nonce) src="/static/theme-controller.js" type="module"></script>
             </head>
-            <body>
+            // htmx 4 only inherits attributes marked with `:inherited`
+            <body "hx-headers:inherited"=(hx_headers)>
                 <div
                     id="toast-root"
                     class={
@@ -80,8 +93,8 @@ pub fn document<R: Renderable>(props: &DocumentProps, children: &R) -> impl Rend
                     </main>
                     <Footer />
                 </div>
-                <script src="/static/prism.js"></script>
-                <script src="/static/prism-htmx.js"></script>
+                <script nonce=(nonce) src="/static/prism.js"></script>
+                <script nonce=(nonce) src="/static/prism-htmx.js"></script>
             </body>
         </html>
     }
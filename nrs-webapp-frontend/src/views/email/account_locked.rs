@@ -0,0 +1,57 @@
+use hypertext::prelude::*;
+
+use crate::views::email::{EmailTemplate, Locale};
+
+/// The email sent when an account gets locked after too many failed logins.
+///
+/// The email tells `username` that password logins are temporarily blocked, links to `href` to
+/// unlock the account right away, and suggests resetting the password if the attempts were not
+/// theirs.
+///
+/// # Examples
+///
+/// ```
+/// use nrs_webapp_frontend::views::email::{EmailTemplate, Locale, account_locked::AccountLocked};
+/// let template = AccountLocked { username: "alice", href: "https://example.com/auth/unlock?token=abc" };
+/// let email = template.render(Locale::Fr);
+/// assert!(email.text.starts_with("Bonjour, alice"));
+/// ```
+pub struct AccountLocked<'a> {
+    pub username: &'a str,
+    pub href: &'a str,
+}
+
+impl EmailTemplate for AccountLocked<'_> {
+    fn subject(&self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::En => "nrs-webapp - Your account has been locked",
+            Locale::Fr => "nrs-webapp - Votre compte a été verrouillé",
+        }
+    }
+
+    fn html(&self, locale: Locale) -> Rendered<String> {
+        let Self { username, href } = *self;
+        match locale {
+            Locale::En => rsx! {
+                <main>
+                    <p>"Hi, "(username)</p>
+                    <p>"There were too many failed login attempts on your account on nrs-"<em>webapp</em>", so logging in with a password has been temporarily blocked."</p>
+                    <p>"If these attempts were yours, you can unlock your account right away with the following link:"</p>
+                    <a href=(href) target="_blank" rel="noopener noreferrer">(href)</a>
+                    <p>"If they were not, someone may be trying to guess your password. Consider resetting it to a stronger one."</p>
+                </main>
+            }
+            .render(),
+            Locale::Fr => rsx! {
+                <main>
+                    <p>"Bonjour, "(username)</p>
+                    <p>"Trop de tentatives de connexion ont échoué sur votre compte nrs-"<em>webapp</em>", la connexion par mot de passe a donc été temporairement bloquée."</p>
+                    <p>"Si ces tentatives venaient de vous, vous pouvez déverrouiller votre compte immédiatement avec le lien suivant :"</p>
+                    <a href=(href) target="_blank" rel="noopener noreferrer">(href)</a>
+                    <p>"Sinon, quelqu'un essaie peut-être de deviner votre mot de passe. Pensez à le remplacer par un mot de passe plus robuste."</p>
+                </main>
+            }
+            .render(),
+        }
+    }
+}
@@ -1,30 +1,68 @@
 use hypertext::prelude::*;
 
-/// Render a verification email body containing a greeting, account notice, verification link, and dismissal note.
-///
-/// The returned renderable includes the provided `username` and `href` in the visible content and sets the link to open in a new tab with `rel="noopener noreferrer"`.
+use crate::views::email::{EmailTemplate, Locale};
+
+/// The email sent to verify the address of a new account, with a verification link to `href`.
 ///
 /// # Examples
 ///
 /// ```
-/// use nrs_webapp_frontend::views::email::email_verify::email_verify;
-/// let _view = email_verify("alice", "https://example.com/verify");
+/// use nrs_webapp_frontend::views::email::{EmailTemplate, Locale, email_verify::EmailVerify};
+/// let email = EmailVerify { username: "alice", href: "https://example.com/verify" }.render(Locale::En);
+/// assert!(email.text.contains("Verify your email address (https://example.com/verify)"));
 /// ```
-pub fn email_verify<'a>(username: &'a str, href: &'a str) -> impl Renderable {
-    rsx! {
-        <main>
-            <p>"Hi, "(username)</p>
-            <p>"An account on nrs-"<em>webapp</em>" has been registered using this email address."</p>
-            <p>"Please click the following link to verify your email address:"</p>
-            <a
-              href=(href)
-              target="_blank"
-              rel="noopener noreferrer"
-              aria-label="Verify your email address (opens in a new tab)"
-            >
-              "Verify your email address"
-            </a>
-            <p>"If you did not register an account, please ignore this email."</p>
-        </main>
+pub struct EmailVerify<'a> {
+    pub username: &'a str,
+    pub href: &'a str,
+}
+
+impl EmailTemplate for EmailVerify<'_> {
+    fn subject(&self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::En => "nrs-webapp - Please verify your email address",
+            Locale::Fr => "nrs-webapp - Veuillez vérifier votre adresse e-mail",
+        }
+    }
+
+    /// Renders a greeting, the account notice, the verification link (opening in a new tab with
+    /// `rel="noopener noreferrer"`) and a dismissal note.
+    fn html(&self, locale: Locale) -> Rendered<String> {
+        let Self { username, href } = *self;
+        match locale {
+            Locale::En => rsx! {
+                <main>
+                    <p>"Hi, "(username)</p>
+                    <p>"An account on nrs-"<em>webapp</em>" has been registered using this email address."</p>
+                    <p>"Please click the following link to verify your email address:"</p>
+                    <a
+                      href=(href)
+                      target="_blank"
+                      rel="noopener noreferrer"
+                      aria-label="Verify your email address (opens in a new tab)"
+                    >
+                      "Verify your email address"
+                    </a>
+                    <p>"If you did not register an account, please ignore this email."</p>
+                </main>
+            }
+            .render(),
+            Locale::Fr => rsx! {
+                <main>
+                    <p>"Bonjour, "(username)</p>
+                    <p>"Un compte sur nrs-"<em>webapp</em>" a été créé avec cette adresse e-mail."</p>
+                    <p>"Veuillez cliquer sur le lien suivant pour vérifier votre adresse e-mail :"</p>
+                    <a
+                      href=(href)
+                      target="_blank"
+                      rel="noopener noreferrer"
+                      aria-label="Vérifier votre adresse e-mail (s'ouvre dans un nouvel onglet)"
+                    >
+                      "Vérifier votre adresse e-mail"
+                    </a>
+                    <p>"Si vous n'avez pas créé de compte, vous pouvez ignorer cet e-mail."</p>
+                </main>
+            }
+            .render(),
+        }
     }
 }
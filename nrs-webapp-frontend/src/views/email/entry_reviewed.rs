@@ -0,0 +1,77 @@
+use hypertext::prelude::*;
+
+use crate::views::email::{EmailTemplate, Locale};
+
+/// The email sent to the submitter of an entry once a moderator reviewed it.
+///
+/// The email tells `username` whether `entry_title` was approved, in which case it links to the
+/// entry at `href`, or rejected for `rejection_reason`.
+///
+/// # Examples
+///
+/// ```
+/// use nrs_webapp_frontend::views::email::{EmailTemplate, Locale, entry_reviewed::EntryReviewed};
+/// let template = EntryReviewed {
+///     username: "alice",
+///     entry_title: "Cowboy Bebop",
+///     href: "https://example.com/entry/A-MAL-1",
+///     rejection_reason: Some("Duplicate of A-MAL-2"),
+/// };
+/// let email = template.render(Locale::En);
+/// assert!(email.text.contains("Duplicate of A-MAL-2"));
+/// ```
+pub struct EntryReviewed<'a> {
+    pub username: &'a str,
+    pub entry_title: &'a str,
+    pub href: &'a str,
+    /// `None` when the entry was approved.
+    pub rejection_reason: Option<&'a str>,
+}
+
+impl EmailTemplate for EntryReviewed<'_> {
+    fn subject(&self, locale: Locale) -> &'static str {
+        match (locale, self.rejection_reason) {
+            (Locale::En, None) => "nrs-webapp - Your entry has been approved",
+            (Locale::En, Some(_)) => "nrs-webapp - Your entry has been rejected",
+            (Locale::Fr, None) => "nrs-webapp - Votre entrée a été approuvée",
+            (Locale::Fr, Some(_)) => "nrs-webapp - Votre entrée a été refusée",
+        }
+    }
+
+    fn html(&self, locale: Locale) -> Rendered<String> {
+        let Self {
+            username,
+            entry_title,
+            href,
+            rejection_reason,
+        } = *self;
+        match locale {
+            Locale::En => rsx! {
+                <main>
+                    <p>"Hi, "(username)</p>
+                    @if let Some(reason) = rejection_reason {
+                        <p>"Your entry "<strong>(entry_title)</strong>" submitted on nrs-"<em>webapp</em>" has been rejected by a moderator, for the following reason:"</p>
+                        <p>(reason)</p>
+                    } @else {
+                        <p>"Your entry "<strong>(entry_title)</strong>" submitted on nrs-"<em>webapp</em>" has been approved by a moderator. It is now visible to everyone:"</p>
+                        <a href=(href) target="_blank" rel="noopener noreferrer">(href)</a>
+                    }
+                </main>
+            }
+            .render(),
+            Locale::Fr => rsx! {
+                <main>
+                    <p>"Bonjour, "(username)</p>
+                    @if let Some(reason) = rejection_reason {
+                        <p>"Votre entrée "<strong>(entry_title)</strong>" proposée sur nrs-"<em>webapp</em>" a été refusée par un modérateur, pour la raison suivante :"</p>
+                        <p>(reason)</p>
+                    } @else {
+                        <p>"Votre entrée "<strong>(entry_title)</strong>" proposée sur nrs-"<em>webapp</em>" a été approuvée par un modérateur. Elle est maintenant visible par tous :"</p>
+                        <a href=(href) target="_blank" rel="noopener noreferrer">(href)</a>
+                    }
+                </main>
+            }
+            .render(),
+        }
+    }
+}
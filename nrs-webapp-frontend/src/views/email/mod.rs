@@ -1,2 +1,151 @@
+use hypertext::Rendered;
+use strum::{EnumString, IntoStaticStr, VariantArray};
+
+use crate::views::email::{
+    account_locked::AccountLocked, email_verify::EmailVerify, entry_reviewed::EntryReviewed,
+    password_reset::PasswordReset, text::html_to_text,
+};
+
+pub mod account_locked;
 pub mod email_verify;
+pub mod entry_reviewed;
 pub mod password_reset;
+pub mod text;
+
+/// The languages the emails are available in.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, EnumString, IntoStaticStr, VariantArray)]
+#[strum(serialize_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    En,
+    Fr,
+}
+
+impl Locale {
+    /// Picks the supported locale the client prefers, from the value of an `Accept-Language`
+    /// header, falling back to English.
+    ///
+    /// Only the primary language subtag is considered, and the languages are ranked by their
+    /// `q` weight.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use nrs_webapp_frontend::views::email::Locale;
+    ///
+    /// assert_eq!(Locale::from_accept_language("fr-CA,fr;q=0.9,en;q=0.8"), Locale::Fr);
+    /// assert_eq!(Locale::from_accept_language("de, en;q=0.5, fr;q=0.7"), Locale::Fr);
+    /// assert_eq!(Locale::from_accept_language("de"), Locale::En);
+    /// assert_eq!(Locale::from_accept_language(""), Locale::En);
+    /// ```
+    pub fn from_accept_language(accept_language: &str) -> Self {
+        accept_language
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';');
+                let language = parts.next()?.trim().split('-').next()?;
+                let weight = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+                let locale = language.to_ascii_lowercase().parse::<Self>().ok()?;
+                Some((locale, weight))
+            })
+            .filter(|(_, weight)| *weight > 0.0)
+            // the first of the ranges with the highest weight wins
+            .fold(
+                None,
+                |best: Option<(Self, f32)>, (locale, weight)| match best {
+                    Some((_, best_weight)) if best_weight >= weight => best,
+                    _ => Some((locale, weight)),
+                },
+            )
+            .map(|(locale, _)| locale)
+            .unwrap_or_default()
+    }
+}
+
+/// An email ready to be sent, with an HTML body and its plain-text alternative.
+#[derive(Debug, Clone)]
+pub struct RenderedEmail {
+    pub subject: String,
+    pub html: Rendered<String>,
+    pub text: String,
+}
+
+/// An email sent by the application, available in every [`Locale`].
+pub trait EmailTemplate {
+    /// The subject line in `locale`.
+    fn subject(&self, locale: Locale) -> &'static str;
+
+    /// The HTML body in `locale`.
+    fn html(&self, locale: Locale) -> Rendered<String>;
+
+    /// Renders the subject and the HTML body in `locale`, deriving the plain-text body from the
+    /// HTML one with [`html_to_text`].
+    fn render(&self, locale: Locale) -> RenderedEmail {
+        let html = self.html(locale);
+        RenderedEmail {
+            subject: self.subject(locale).to_string(),
+            text: html_to_text(html.as_inner()),
+            html,
+        }
+    }
+}
+
+/// Every email template filled with sample data, by name, for previews.
+///
+/// # Examples
+///
+/// ```
+/// use nrs_webapp_frontend::views::email::{Locale, template_samples};
+///
+/// for (name, template) in template_samples() {
+///     let email = template.render(Locale::En);
+///     assert!(!email.subject.is_empty(), "{name}");
+///     assert!(email.text.contains("alice"), "{name}");
+/// }
+/// ```
+pub fn template_samples() -> [(&'static str, &'static dyn EmailTemplate); 5] {
+    const SAMPLE_USERNAME: &str = "alice";
+    [
+        (
+            "email_verify",
+            &EmailVerify {
+                username: SAMPLE_USERNAME,
+                href: "https://nrs.dev/auth/confirmmail/confirm?token=sample",
+            },
+        ),
+        (
+            "password_reset",
+            &PasswordReset {
+                username: SAMPLE_USERNAME,
+                href: "https://nrs.dev/auth/forgotpass/reset?token=sample",
+            },
+        ),
+        (
+            "account_locked",
+            &AccountLocked {
+                username: SAMPLE_USERNAME,
+                href: "https://nrs.dev/auth/unlock?token=sample",
+            },
+        ),
+        (
+            "entry_approved",
+            &EntryReviewed {
+                username: SAMPLE_USERNAME,
+                entry_title: "Cowboy Bebop",
+                href: "https://nrs.dev/entry/A-MAL-1",
+                rejection_reason: None,
+            },
+        ),
+        (
+            "entry_rejected",
+            &EntryReviewed {
+                username: SAMPLE_USERNAME,
+                entry_title: "Cowboy Bebop",
+                href: "https://nrs.dev/entry/A-MAL-1",
+                rejection_reason: Some("This entry already exists as A-MAL-2."),
+            },
+        ),
+    ]
+}
@@ -1,26 +1,54 @@
 use hypertext::prelude::*;
 
-/// Creates a renderable password-reset email fragment personalized for the given user.
-///
-/// The returned `Renderable` contains a <main> element with a greeting that includes `username`,
-/// a notice that a password reset was requested for the site, an anchor linking to `href`,
-/// and a final note instructing the recipient to ignore the message if they did not request it.
+use crate::views::email::{EmailTemplate, Locale};
+
+/// The email sent when a password reset is requested, with a reset link to `href`.
 ///
 /// # Examples
 ///
 /// ```
-/// use hypertext::prelude::*;
-/// use nrs_webapp_frontend::views::email::password_reset::password_reset;
-/// let _view = password_reset("alice", "https://example.com/reset");
+/// use nrs_webapp_frontend::views::email::{EmailTemplate, Locale, password_reset::PasswordReset};
+/// let email = PasswordReset { username: "alice", href: "https://example.com/reset" }.render(Locale::En);
+/// assert_eq!(email.subject, "nrs-webapp - Password Reset Request");
 /// ```
-pub fn password_reset<'a>(username: &'a str, href: &'a str) -> impl Renderable {
-    rsx! {
-        <main>
-            <p>"Hi, "(username)</p>
-            <p>"A password reset request for your account on nrs-"<em>webapp</em>" has been received."</p>
-            <p>"Please click the following link to reset your password:"</p>
-            <a href=(href) target="_blank" rel="noopener noreferrer">(href)</a>
-            <p>"If you did not request for a password reset, please ignore this email."</p>
-        </main>
+pub struct PasswordReset<'a> {
+    pub username: &'a str,
+    pub href: &'a str,
+}
+
+impl EmailTemplate for PasswordReset<'_> {
+    fn subject(&self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::En => "nrs-webapp - Password Reset Request",
+            Locale::Fr => "nrs-webapp - Demande de réinitialisation du mot de passe",
+        }
+    }
+
+    /// Renders a greeting with the username, a notice that a password reset was requested, the
+    /// reset link and a note to ignore the message if the reset was not requested.
+    fn html(&self, locale: Locale) -> Rendered<String> {
+        let Self { username, href } = *self;
+        match locale {
+            Locale::En => rsx! {
+                <main>
+                    <p>"Hi, "(username)</p>
+                    <p>"A password reset request for your account on nrs-"<em>webapp</em>" has been received."</p>
+                    <p>"Please click the following link to reset your password:"</p>
+                    <a href=(href) target="_blank" rel="noopener noreferrer">(href)</a>
+                    <p>"If you did not request for a password reset, please ignore this email."</p>
+                </main>
+            }
+            .render(),
+            Locale::Fr => rsx! {
+                <main>
+                    <p>"Bonjour, "(username)</p>
+                    <p>"Une demande de réinitialisation du mot de passe de votre compte sur nrs-"<em>webapp</em>" a été reçue."</p>
+                    <p>"Veuillez cliquer sur le lien suivant pour réinitialiser votre mot de passe :"</p>
+                    <a href=(href) target="_blank" rel="noopener noreferrer">(href)</a>
+                    <p>"Si vous n'êtes pas à l'origine de cette demande, vous pouvez ignorer cet e-mail."</p>
+                </main>
+            }
+            .render(),
+        }
     }
 }
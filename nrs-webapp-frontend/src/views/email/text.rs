@@ -0,0 +1,196 @@
+/// Converts the HTML body of an email into its plain-text alternative.
+///
+/// Tags are dropped, paragraphs and other block elements are separated by blank lines, line
+/// breaks and list items start new lines, and the target of a link is written after its text
+/// unless the text already is the target. Whitespace is collapsed like a browser would, and
+/// character references are decoded. The content of `head`, `style` and `script` elements is
+/// skipped.
+///
+/// This only aims at the simple markup of the email templates, not arbitrary HTML.
+///
+/// # Examples
+///
+/// ```
+/// use nrs_webapp_frontend::views::email::text::html_to_text;
+///
+/// let text = html_to_text(
+///     r#"<main><p>Hi,   <em>alice</em></p><p>Tom &amp; Jerry</p><a href="https://nrs.dev/a?b=1&amp;c=2">Open</a></main>"#,
+/// );
+/// assert_eq!(text, "Hi, alice\n\nTom & Jerry\n\nOpen (https://nrs.dev/a?b=1&c=2)");
+///
+/// let text = html_to_text(r#"<p>Go to <a href="https://nrs.dev">https://nrs.dev</a><br>Bye</p>"#);
+/// assert_eq!(text, "Go to https://nrs.dev\nBye");
+/// ```
+pub fn html_to_text(html: &str) -> String {
+    let mut text = TextBuilder::default();
+    let mut link: Option<(String, usize)> = None;
+    let mut skip_until: Option<String> = None;
+    let mut rest = html;
+
+    while let Some(tag_start) = rest.find('<') {
+        if skip_until.is_none() {
+            text.push_inline(&decode_entities(&rest[..tag_start]));
+        }
+        let Some(tag_len) = rest[tag_start..].find('>') else {
+            rest = "";
+            break;
+        };
+        let tag = &rest[tag_start + 1..tag_start + tag_len];
+        rest = &rest[tag_start + tag_len + 1..];
+
+        let (closing, tag) = match tag.strip_prefix('/') {
+            Some(tag) => (true, tag),
+            None => (false, tag),
+        };
+        let name = tag
+            .split(|c: char| c.is_whitespace() || c == '/')
+            .next()
+            .unwrap_or_default()
+            .to_ascii_lowercase();
+
+        if let Some(skipped) = &skip_until {
+            if closing && *skipped == name {
+                skip_until = None;
+            }
+            continue;
+        }
+
+        match (name.as_str(), closing) {
+            ("head" | "style" | "script", false) => skip_until = Some(name),
+            ("br", _) => text.line_break(),
+            ("li" | "tr", false) => text.line_break(),
+            (
+                "p" | "div" | "main" | "section" | "table" | "ul" | "ol" | "h1" | "h2" | "h3"
+                | "h4" | "h5" | "h6",
+                _,
+            ) => text.paragraph_break(),
+            ("a", false) => {
+                link = attribute(tag, "href").map(|href| (decode_entities(href), text.len()));
+            }
+            ("a", true) => {
+                if let Some((href, start)) = link.take()
+                    && text.since(start).trim() != href
+                {
+                    text.push_inline(&format!(" ({href})"));
+                }
+            }
+            _ => {}
+        }
+    }
+    if skip_until.is_none() {
+        text.push_inline(&decode_entities(rest));
+    }
+
+    text.finish()
+}
+
+#[derive(Default)]
+struct TextBuilder {
+    text: String,
+    // newlines owed before the next inline content
+    pending_newlines: usize,
+    pending_space: bool,
+}
+
+impl TextBuilder {
+    fn push_inline(&mut self, content: &str) {
+        for word_or_space in content.split_inclusive(char::is_whitespace) {
+            let word = word_or_space.trim_end_matches(char::is_whitespace);
+            if !word.is_empty() {
+                if !self.text.is_empty() {
+                    if self.pending_newlines > 0 {
+                        self.text.push_str(&"\n".repeat(self.pending_newlines));
+                    } else if self.pending_space {
+                        self.text.push(' ');
+                    }
+                }
+                self.pending_newlines = 0;
+                self.pending_space = false;
+                self.text.push_str(word);
+            }
+            if word.len() < word_or_space.len() {
+                self.pending_space = true;
+            }
+        }
+    }
+
+    fn line_break(&mut self) {
+        self.pending_newlines = self.pending_newlines.max(1);
+    }
+
+    fn paragraph_break(&mut self) {
+        self.pending_newlines = 2;
+    }
+
+    fn len(&self) -> usize {
+        self.text.len()
+    }
+
+    fn since(&self, start: usize) -> &str {
+        &self.text[start..]
+    }
+
+    fn finish(self) -> String {
+        self.text
+    }
+}
+
+/// The value of the attribute `name` in the inside of a start tag.
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = tag;
+    while let Some(pos) = rest.find(name) {
+        let preceded_by_space = rest[..pos].ends_with(char::is_whitespace);
+        rest = &rest[pos + name.len()..];
+        let Some(value) = rest.trim_start().strip_prefix('=') else {
+            continue;
+        };
+        if !preceded_by_space {
+            continue;
+        }
+        let value = value.trim_start();
+        return match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => value[1..].split(quote).next(),
+            _ => value.split(char::is_whitespace).next(),
+        };
+    }
+    None
+}
+
+fn decode_entities(text: &str) -> String {
+    let mut decoded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded_char = rest.find(';').and_then(|end| {
+            let char = match &rest[1..end] {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                "nbsp" => Some('\u{a0}'),
+                entity => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .map(|hex| u32::from_str_radix(hex, 16))
+                    .or_else(|| entity.strip_prefix('#').map(str::parse))
+                    .and_then(Result::ok)
+                    .and_then(char::from_u32),
+            }?;
+            Some((char, end))
+        });
+        match decoded_char {
+            Some((char, end)) => {
+                decoded.push(char);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    decoded.push_str(rest);
+    decoded
+}
@@ -72,7 +72,7 @@ pub fn error_page(error: &ClientError, props: &DocumentProps) -> impl Renderable
                 <Icon class="size-30 text-error mb-2" name=(ExclamationCircle) variant=(Solid) .. />
                 <h1 class="font-bold text-5xl mb-8">(error.title)</h1>
                 <p class="text-base-content/80">(error.description)</p>
-                <button onclick={"history.back()"} class="btn btn-primary mt-8">("Go Back")</button>
+                <button hx-on:click="history.back()" class="btn btn-primary mt-8">("Go Back")</button>
             </section>
         </Document>
     }
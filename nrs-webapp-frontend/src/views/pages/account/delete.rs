@@ -0,0 +1,34 @@
+use hypertext::prelude::*;
+
+use crate::views::components::{
+    form::Form,
+    link::{Link, LinkParams},
+};
+
+/// Render the account deletion form, which asks the user to re-enter their password.
+///
+/// The form has id `"deleteaccount-form"` and posts to `/account/delete`. It explains what happens
+/// to the account data and offers a link back to the account page.
+///
+/// # Examples
+///
+/// ```
+/// use nrs_webapp_frontend::views::pages::account::delete::delete_account;
+/// let _view = delete_account();
+/// ```
+pub fn delete_account() -> impl Renderable {
+    rsx! {
+        <Form form_id="deleteaccount-form" title="Delete account" hx_post="/account/delete">
+            <p>"This permanently deletes your account, your linked login providers and any pending email or password reset links."</p>
+            <p>"Entries you added or edited stay in the database, but are no longer attributed to you."</p>
+
+            <label class="label" for="deleteaccount-password">Password</label>
+            <input id="deleteaccount-password" name="password" type="password" class="input w-full validator" required placeholder="Password" autocomplete="current-password" />
+            <div class="validator-hint hidden">"Please re-enter your password to confirm"</div>
+
+            <button type="submit" class="btn btn-error mt-4">"Delete my account"</button>
+
+            <Link params=(LinkParams{href:"/account", class:"btn btn-secondary", ..Default::default()})>"Back to account page"</Link>
+        </Form>
+    }
+}
@@ -0,0 +1,118 @@
+use hypertext::prelude::*;
+
+use crate::views::components::link::{Link, LinkParams};
+
+pub struct AccountOverview {
+    pub username: String,
+    pub email: String,
+    pub email_verified: bool,
+    pub created_at: String,
+    pub providers: Vec<AccountProvider>,
+}
+
+pub struct AccountProvider {
+    pub name: String,
+    pub display_name: String,
+    /// Date the provider was linked, `None` if it is not linked.
+    pub linked_since: Option<String>,
+}
+
+/// Renders the account overview page with profile details and data management actions.
+///
+/// The page lists the username, email (with its verification state) and creation date, the
+/// configured login providers with a link (`/auth/oauth/link/<name>`) or unlink
+/// (`POST /account/oauth/unlink`) action each, offers a plain download link to `/account/export`
+/// for the JSON export of the user's personal data, and links to the account deletion page.
+///
+/// # Examples
+///
+/// ```
+/// use nrs_webapp_frontend::views::pages::account::overview::{
+///     AccountOverview, AccountProvider, account_page,
+/// };
+/// let account = AccountOverview {
+///     username: "alice".into(),
+///     email: "alice@example.com".into(),
+///     email_verified: true,
+///     created_at: "2025-01-01".into(),
+///     providers: vec![AccountProvider {
+///         name: "github".into(),
+///         display_name: "GitHub".into(),
+///         linked_since: None,
+///     }],
+/// };
+/// let _view = account_page(&account);
+/// ```
+pub fn account_page(account: &AccountOverview) -> impl Renderable {
+    rsx! {
+        <section class="flex flex-col items-center gap-6 p-4">
+            <h1 class="font-bold text-3xl">"Account"</h1>
+
+            <div class="card bg-base-200 w-full max-w-lg">
+                <div class="card-body">
+                    <h2 class="card-title">"Profile"</h2>
+                    <p>"Username: " <strong>(account.username)</strong></p>
+                    <p>
+                        "Email: " <strong>(account.email)</strong>
+                        @if account.email_verified {
+                            <span class="badge badge-success ml-2">"verified"</span>
+                        } @else {
+                            <span class="badge badge-warning ml-2">"unverified"</span>
+                        }
+                    </p>
+                    <p>"Member since: " (account.created_at)</p>
+                </div>
+            </div>
+
+            <div class="card bg-base-200 w-full max-w-lg">
+                <div class="card-body">
+                    <h2 class="card-title">"Login providers"</h2>
+                    <ul class="flex flex-col gap-2">
+                        @for provider in &account.providers {
+                            <li class="flex items-center justify-between gap-2">
+                                <span>
+                                    <strong>(provider.display_name)</strong>
+                                    @if let Some(linked_since) = &provider.linked_since {
+                                        <span class="text-xs opacity-80">" (linked since " (linked_since) ")"</span>
+                                    }
+                                </span>
+                                @if provider.linked_since.is_some() {
+                                    <button
+                                        class="btn btn-sm btn-outline btn-error"
+                                        hx-post="/account/oauth/unlink"
+                                        hx-vals=(format!(r#"{{"provider":"{}"}}"#, provider.name))
+                                        hx-confirm=(format!("Unlink your {} account?", provider.display_name))
+                                    >
+                                        "Unlink"
+                                    </button>
+                                } @else {
+                                    <a class="btn btn-sm btn-neutral" href=(format!("/auth/oauth/link/{}", provider.name))>"Link"</a>
+                                }
+                            </li>
+                        }
+                    </ul>
+                </div>
+            </div>
+
+            <div class="card bg-base-200 w-full max-w-lg">
+                <div class="card-body">
+                    <h2 class="card-title">"Your data"</h2>
+                    <p>"Download a copy of the personal data stored for your account as a JSON file."</p>
+                    <div class="card-actions justify-end">
+                        <a class="btn btn-neutral" href="/account/export" download>"Export data"</a>
+                    </div>
+                </div>
+            </div>
+
+            <div class="card bg-base-200 border border-error w-full max-w-lg">
+                <div class="card-body">
+                    <h2 class="card-title text-error">"Danger zone"</h2>
+                    <p>"Deleting your account is permanent. Entries you added are kept, but will no longer be attributed to you."</p>
+                    <div class="card-actions justify-end">
+                        <Link params=(LinkParams { href: "/account/delete", class: "btn btn-error", ..Default::default() })>"Delete account"</Link>
+                    </div>
+                </div>
+            </div>
+        </section>
+    }
+}
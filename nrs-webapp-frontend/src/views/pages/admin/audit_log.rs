@@ -0,0 +1,150 @@
+use hypertext::prelude::*;
+
+use crate::views::components::link::{Link, LinkParams};
+
+/// A field changed by an audit event, with its values as compact JSON.
+pub struct AuditFieldChange {
+    pub field: String,
+    /// `None` for a created row.
+    pub before: Option<String>,
+    /// `None` for a deleted row.
+    pub after: Option<String>,
+}
+
+pub struct AuditLogEntry {
+    pub id: i64,
+    pub created_at: String,
+    /// One of `"create"`, `"update"` or `"delete"`.
+    pub action: &'static str,
+    pub entity_table: String,
+    pub entity_id: String,
+    /// Link to the history of the changed entity.
+    pub entity_href: String,
+    /// The username of the actor, or its id when the account is gone; `None` for changes made by
+    /// the service itself.
+    pub actor: Option<String>,
+    /// Link to the changes made by the actor.
+    pub actor_href: Option<String>,
+    pub request_id: Option<String>,
+    pub ip: Option<String>,
+    pub changes: Vec<AuditFieldChange>,
+}
+
+fn action_badge_class(action: &str) -> &'static str {
+    match action {
+        "create" => "badge badge-success",
+        "delete" => "badge badge-error",
+        _ => "badge badge-info",
+    }
+}
+
+/// Renders the admin page listing the most recent changes recorded in the audit log.
+///
+/// `filter` describes the history being shown, such as the changes of an entity
+/// (`/admin/audit?entity_table=<table>&entity_id=<id>`) or made by a user
+/// (`/admin/audit?actor_id=<id>`), and is `None` for the whole log.
+///
+/// # Examples
+///
+/// ```
+/// use nrs_webapp_frontend::views::pages::admin::audit_log::{
+///     AuditFieldChange, AuditLogEntry, audit_log_page,
+/// };
+/// let events = vec![AuditLogEntry {
+///     id: 1,
+///     created_at: "2025-01-01 12:00:00".into(),
+///     action: "update",
+///     entity_table: "app_user".into(),
+///     entity_id: "0193c6a4-7d1e-7000-8000-000000000000".into(),
+///     entity_href: "/admin/audit?entity_table=app_user&entity_id=0193c6a4-7d1e-7000-8000-000000000000".into(),
+///     actor: Some("alice".into()),
+///     actor_href: Some("/admin/audit?actor_id=0193c6a4-7d1e-7000-8000-000000000000".into()),
+///     request_id: None,
+///     ip: Some("127.0.0.1".into()),
+///     changes: vec![AuditFieldChange {
+///         field: "locked_until".into(),
+///         before: Some("null".into()),
+///         after: Some("2025-01-01T12:15:00Z".into()),
+///     }],
+/// }];
+/// let _view = audit_log_page(Some("changes of app_user 0193c6a4"), &events);
+/// ```
+pub fn audit_log_page(filter: Option<&str>, events: &[AuditLogEntry]) -> impl Renderable {
+    rsx! {
+        <section class="flex flex-col items-center gap-6 p-4 w-full">
+            <h1 class="font-bold text-3xl">"Audit log"</h1>
+
+            @if let Some(filter) = filter {
+                <p class="flex items-center gap-2">
+                    "Showing the " (filter) "."
+                    <Link params=(LinkParams { href: "/admin/audit", class: "link", ..Default::default() })>"Show all"</Link>
+                </p>
+            }
+
+            @if events.is_empty() {
+                <p class="opacity-80">"No changes to show."</p>
+            } @else {
+                <div class="overflow-x-auto w-full max-w-6xl">
+                    <table class="table table-zebra">
+                        <thead>
+                            <tr>
+                                <th>"Time"</th>
+                                <th>"Actor"</th>
+                                <th>"Action"</th>
+                                <th>"Entity"</th>
+                                <th>"Changes"</th>
+                                <th>"Request"</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            @for event in events {
+                                <tr>
+                                    <td class="whitespace-nowrap">(event.created_at)</td>
+                                    <td>
+                                        @match (&event.actor, &event.actor_href) {
+                                            (Some(actor), Some(href)) => {
+                                                <Link params=(LinkParams { href: href.as_str(), class: "link link-hover", ..Default::default() })>(actor)</Link>
+                                            }
+                                            (Some(actor), None) => { (actor) }
+                                            (None, _) => { <span class="opacity-60">"system"</span> }
+                                        }
+                                    </td>
+                                    <td><span class=(action_badge_class(event.action))>(event.action)</span></td>
+                                    <td>
+                                        <Link params=(LinkParams { href: event.entity_href.as_str(), class: "link link-hover", ..Default::default() })>
+                                            (event.entity_table) " " <code>(event.entity_id)</code>
+                                        </Link>
+                                    </td>
+                                    <td class="text-xs">
+                                        <ul>
+                                            @for change in &event.changes {
+                                                <li>
+                                                    <span class="font-semibold">(change.field) ": "</span>
+                                                    @if let Some(before) = &change.before {
+                                                        <code class="text-error">(before)</code>
+                                                    }
+                                                    @if change.before.is_some() && change.after.is_some() {
+                                                        " → "
+                                                    }
+                                                    @if let Some(after) = &change.after {
+                                                        <code class="text-success">(after)</code>
+                                                    }
+                                                </li>
+                                            }
+                                        </ul>
+                                    </td>
+                                    <td class="text-xs">
+                                        (event.ip.as_deref().unwrap_or("-"))
+                                        @if let Some(request_id) = &event.request_id {
+                                            <br/><code>(request_id)</code>
+                                        }
+                                    </td>
+                                </tr>
+                            }
+                        </tbody>
+                    </table>
+                </div>
+            }
+        </section>
+    }
+}
@@ -0,0 +1,117 @@
+use hypertext::prelude::*;
+
+use crate::views::components::link::{Link, LinkParams};
+
+pub struct MailQueueEntry {
+    pub id: i64,
+    pub recipient: String,
+    pub subject: String,
+    /// One of `"pending"`, `"sent"` or `"dead"`.
+    pub status: &'static str,
+    pub attempts: i32,
+    pub created_at: String,
+    /// When the next delivery attempt is due, for pending mails.
+    pub next_attempt_at: Option<String>,
+    pub sent_at: Option<String>,
+    pub last_error: Option<String>,
+}
+
+const STATUS_FILTERS: [(&str, Option<&str>); 4] = [
+    ("All", None),
+    ("Pending", Some("pending")),
+    ("Sent", Some("sent")),
+    ("Dead", Some("dead")),
+];
+
+fn status_badge_class(status: &str) -> &'static str {
+    match status {
+        "sent" => "badge badge-success",
+        "dead" => "badge badge-error",
+        _ => "badge badge-info",
+    }
+}
+
+/// Renders the admin page listing the delivery status of the most recent outgoing emails.
+///
+/// The list can be narrowed to a status with the tabs (`/admin/mail?status=<status>`), and dead
+/// emails get a retry button posting to `/admin/mail/<id>/retry`.
+///
+/// # Examples
+///
+/// ```
+/// use nrs_webapp_frontend::views::pages::admin::mail_queue::{MailQueueEntry, mail_queue_page};
+/// let mails = vec![MailQueueEntry {
+///     id: 1,
+///     recipient: "alice@example.com".into(),
+///     subject: "Please verify your email address".into(),
+///     status: "dead",
+///     attempts: 8,
+///     created_at: "2025-01-01 12:00:00".into(),
+///     next_attempt_at: None,
+///     sent_at: None,
+///     last_error: Some("connection refused".into()),
+/// }];
+/// let _view = mail_queue_page(Some("dead"), &mails);
+/// ```
+pub fn mail_queue_page(status_filter: Option<&str>, mails: &[MailQueueEntry]) -> impl Renderable {
+    rsx! {
+        <section class="flex flex-col items-center gap-6 p-4 w-full">
+            <h1 class="font-bold text-3xl">"Outgoing emails"</h1>
+
+            <div role="tablist" class="tabs tabs-box">
+                @for (label, status) in STATUS_FILTERS {
+                    @let href = status.map_or_else(|| "/admin/mail".to_string(), |status| format!("/admin/mail?status={status}"));
+                    @let class = if status == status_filter { "tab tab-active" } else { "tab" };
+                    <Link params=(LinkParams { href: href.as_str(), class, ..Default::default() })>(label)</Link>
+                }
+            </div>
+
+            @if mails.is_empty() {
+                <p class="opacity-80">"No emails to show."</p>
+            } @else {
+                <div class="overflow-x-auto w-full max-w-6xl">
+                    <table class="table table-zebra">
+                        <thead>
+                            <tr>
+                                <th>"ID"</th>
+                                <th>"Recipient"</th>
+                                <th>"Subject"</th>
+                                <th>"Status"</th>
+                                <th>"Attempts"</th>
+                                <th>"Queued at"</th>
+                                <th>"Sent at / next attempt"</th>
+                                <th>"Last error"</th>
+                                <th></th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            @for mail in mails {
+                                <tr>
+                                    <td>(mail.id)</td>
+                                    <td>(mail.recipient)</td>
+                                    <td>(mail.subject)</td>
+                                    <td><span class=(status_badge_class(mail.status))>(mail.status)</span></td>
+                                    <td>(mail.attempts)</td>
+                                    <td>(mail.created_at)</td>
+                                    <td>(mail.sent_at.as_deref().or(mail.next_attempt_at.as_deref()).unwrap_or("-"))</td>
+                                    <td class="text-xs">(mail.last_error.as_deref().unwrap_or("-"))</td>
+                                    <td>
+                                        @if mail.status == "dead" {
+                                            <button
+                                                class="btn btn-sm btn-outline"
+                                                hx-post=(format!("/admin/mail/{}/retry", mail.id))
+                                                hx-confirm="Queue this email again?"
+                                            >
+                                                "Retry"
+                                            </button>
+                                        }
+                                    </td>
+                                </tr>
+                            }
+                        </tbody>
+                    </table>
+                </div>
+            }
+        </section>
+    }
+}
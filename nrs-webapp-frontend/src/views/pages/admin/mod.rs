@@ -0,0 +1,3 @@
+pub mod audit_log;
+pub mod mail_queue;
+pub mod users;
@@ -0,0 +1,309 @@
+use hypertext::prelude::*;
+
+use crate::views::components::link::{Link, LinkParams};
+
+pub struct AdminUserRow {
+    pub id: String,
+    pub username: String,
+    pub email: String,
+    pub email_verified: bool,
+    /// One of `"active"`, `"locked"` or `"disabled"`.
+    pub status: &'static str,
+    /// Names of the OAuth providers linked to the account.
+    pub providers: Vec<String>,
+    pub created_at: String,
+}
+
+pub struct AdminUserDetails {
+    pub id: String,
+    pub username: String,
+    pub email: String,
+    pub email_verified_at: Option<String>,
+    pub created_at: String,
+    pub locked_until: Option<String>,
+    pub disabled_at: Option<String>,
+    /// Lowercase names of the granted roles.
+    pub roles: Vec<&'static str>,
+    /// Lowercase names of every role, from the least to the most privileged.
+    pub all_roles: Vec<&'static str>,
+    pub oauth_links: Vec<AdminOAuthLink>,
+    pub logins: Vec<AdminLogin>,
+    pub failed_logins: Vec<AdminFailedLogin>,
+}
+
+pub struct AdminOAuthLink {
+    pub provider: String,
+    pub linked_since: String,
+}
+
+pub struct AdminLogin {
+    pub created_at: String,
+    /// `"password"`, or the name of the OAuth provider.
+    pub method: String,
+    pub ip: String,
+    pub user_agent: String,
+}
+
+pub struct AdminFailedLogin {
+    pub created_at: String,
+    pub ip: String,
+}
+
+fn status_badge_class(status: &str) -> &'static str {
+    match status {
+        "disabled" => "badge badge-error",
+        "locked" => "badge badge-warning",
+        _ => "badge badge-success",
+    }
+}
+
+/// Renders the admin page listing the users, the most recently registered first.
+///
+/// The search form submits `q` to `/admin/users`, narrowing the list to the users whose username
+/// or email contains it; `search` is the current one. Every username links to the details of the
+/// user at `/admin/users/<id>`.
+///
+/// # Examples
+///
+/// ```
+/// use nrs_webapp_frontend::views::pages::admin::users::{AdminUserRow, user_list_page};
+/// let users = vec![AdminUserRow {
+///     id: "0193c6a4-7d1e-7000-8000-000000000000".into(),
+///     username: "alice".into(),
+///     email: "alice@example.com".into(),
+///     email_verified: true,
+///     status: "active",
+///     providers: vec!["github".into()],
+///     created_at: "2025-01-01 12:00:00".into(),
+/// }];
+/// let _view = user_list_page(Some("ali"), &users);
+/// ```
+pub fn user_list_page(search: Option<&str>, users: &[AdminUserRow]) -> impl Renderable {
+    rsx! {
+        <section class="flex flex-col items-center gap-6 p-4 w-full">
+            <h1 class="font-bold text-3xl">"Users"</h1>
+
+            <form method="get" action="/admin/users" class="join">
+                <input type="search" name="q" class="input join-item" placeholder="Username or email" value=[search] />
+                <button type="submit" class="btn btn-neutral join-item">"Search"</button>
+            </form>
+
+            @if users.is_empty() {
+                <p class="opacity-80">"No users to show."</p>
+            } @else {
+                <div class="overflow-x-auto w-full max-w-6xl">
+                    <table class="table table-zebra">
+                        <thead>
+                            <tr>
+                                <th>"Username"</th>
+                                <th>"Email"</th>
+                                <th>"Status"</th>
+                                <th>"Linked accounts"</th>
+                                <th>"Registered at"</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            @for user in users {
+                                @let href = format!("/admin/users/{}", user.id);
+                                <tr>
+                                    <td><Link params=(LinkParams { href: href.as_str(), class: "link", ..Default::default() })>(user.username)</Link></td>
+                                    <td>
+                                        (user.email)
+                                        @if !user.email_verified {
+                                            <span class="badge badge-warning badge-sm ml-2">"unverified"</span>
+                                        }
+                                    </td>
+                                    <td><span class=(status_badge_class(user.status))>(user.status)</span></td>
+                                    <td>
+                                        @if user.providers.is_empty() {
+                                            "-"
+                                        } @else {
+                                            (user.providers.join(", "))
+                                        }
+                                    </td>
+                                    <td>(user.created_at)</td>
+                                </tr>
+                            }
+                        </tbody>
+                    </table>
+                </div>
+            }
+        </section>
+    }
+}
+
+/// Renders the admin page of a user: their account, linked accounts, roles and recent logins.
+///
+/// The actions post to `/admin/users/<id>/<action>`: `verify-email` while the email is not
+/// verified, `password-reset` to email a password reset link, `disable` or `enable`, and
+/// `grant/<role>` or `revoke/<role>` for each of `all_roles`. The changes made to the account are
+/// linked in the audit log.
+///
+/// # Examples
+///
+/// ```
+/// use nrs_webapp_frontend::views::pages::admin::users::{
+///     AdminFailedLogin, AdminLogin, AdminOAuthLink, AdminUserDetails, user_details_page,
+/// };
+/// let user = AdminUserDetails {
+///     id: "0193c6a4-7d1e-7000-8000-000000000000".into(),
+///     username: "alice".into(),
+///     email: "alice@example.com".into(),
+///     email_verified_at: None,
+///     created_at: "2025-01-01 12:00:00".into(),
+///     locked_until: None,
+///     disabled_at: None,
+///     roles: vec!["editor"],
+///     all_roles: vec!["editor", "moderator", "admin"],
+///     oauth_links: vec![AdminOAuthLink {
+///         provider: "github".into(),
+///         linked_since: "2025-01-02 12:00:00".into(),
+///     }],
+///     logins: vec![AdminLogin {
+///         created_at: "2025-01-03 12:00:00".into(),
+///         method: "password".into(),
+///         ip: "127.0.0.1".into(),
+///         user_agent: "curl/8.0".into(),
+///     }],
+///     failed_logins: vec![AdminFailedLogin {
+///         created_at: "2025-01-03 11:59:00".into(),
+///         ip: "127.0.0.1".into(),
+///     }],
+/// };
+/// let _view = user_details_page(&user);
+/// ```
+pub fn user_details_page(user: &AdminUserDetails) -> impl Renderable {
+    let action = |name: &str| format!("/admin/users/{}/{name}", user.id);
+    let audit_href = format!("/admin/audit?entity_table=app_user&entity_id={}", user.id);
+    rsx! {
+        <section class="flex flex-col items-center gap-6 p-4 w-full">
+            <h1 class="font-bold text-3xl">(user.username)</h1>
+
+            <div class="card bg-base-200 w-full max-w-3xl">
+                <div class="card-body">
+                    <h2 class="card-title">"Account"</h2>
+                    <p>"ID: " <code>(user.id)</code></p>
+                    <p>
+                        "Email: " <strong>(user.email)</strong>
+                        @if let Some(verified_at) = &user.email_verified_at {
+                            <span class="badge badge-success ml-2">"verified " (verified_at)</span>
+                        } @else {
+                            <span class="badge badge-warning ml-2">"unverified"</span>
+                        }
+                    </p>
+                    <p>"Registered at: " (user.created_at)</p>
+                    <div class="flex flex-wrap items-center gap-2">
+                        "Roles: "
+                        @for role in &user.all_roles {
+                            @if user.roles.contains(role) {
+                                <span class="join">
+                                    <span class="badge badge-primary join-item">(role)</span>
+                                    <button class="btn btn-xs btn-ghost join-item" hx-post=(action(&format!("revoke/{role}"))) hx-confirm=(format!("Revoke the {role} role of this user?"))>
+                                        "Revoke"
+                                    </button>
+                                </span>
+                            } @else {
+                                <button class="btn btn-xs btn-outline" hx-post=(action(&format!("grant/{role}"))) hx-confirm=(format!("Grant the {role} role to this user?"))>
+                                    "Grant " (role)
+                                </button>
+                            }
+                        }
+                    </div>
+                    @if let Some(locked_until) = &user.locked_until {
+                        <p class="text-warning">"Locked after failed logins until " (locked_until)</p>
+                    }
+                    @if let Some(disabled_at) = &user.disabled_at {
+                        <p class="text-error">"Disabled since " (disabled_at)</p>
+                    }
+                    <div class="card-actions justify-end">
+                        <Link params=(LinkParams { href: audit_href.as_str(), class: "btn btn-sm btn-ghost", ..Default::default() })>"Audit log"</Link>
+                        @if user.email_verified_at.is_none() {
+                            <button class="btn btn-sm btn-outline" hx-post=(action("verify-email")) hx-confirm="Mark the email of this user as verified?">
+                                "Mark email verified"
+                            </button>
+                        }
+                        <button class="btn btn-sm btn-outline" hx-post=(action("password-reset")) hx-confirm="Email a password reset link to this user?">
+                            "Send password reset"
+                        </button>
+                        @if user.disabled_at.is_some() {
+                            <button class="btn btn-sm btn-success" hx-post=(action("enable")) hx-confirm="Enable this account?">
+                                "Enable account"
+                            </button>
+                        } @else {
+                            <button class="btn btn-sm btn-error" hx-post=(action("disable")) hx-confirm="Disable this account? The user is logged out and can no longer log in.">
+                                "Disable account"
+                            </button>
+                        }
+                    </div>
+                </div>
+            </div>
+
+            <div class="card bg-base-200 w-full max-w-3xl">
+                <div class="card-body">
+                    <h2 class="card-title">"Linked accounts"</h2>
+                    @if user.oauth_links.is_empty() {
+                        <p class="opacity-80">"No linked accounts."</p>
+                    } @else {
+                        <ul>
+                            @for link in &user.oauth_links {
+                                <li><strong>(link.provider)</strong> <span class="text-xs opacity-80">" (linked since " (link.linked_since) ")"</span></li>
+                            }
+                        </ul>
+                    }
+                </div>
+            </div>
+
+            <div class="card bg-base-200 w-full max-w-3xl">
+                <div class="card-body">
+                    <h2 class="card-title">"Recent logins"</h2>
+                    @if user.logins.is_empty() {
+                        <p class="opacity-80">"No recent logins."</p>
+                    } @else {
+                        <table class="table table-sm">
+                            <thead>
+                                <tr>
+                                    <th>"Date"</th>
+                                    <th>"Method"</th>
+                                    <th>"IP"</th>
+                                    <th>"User agent"</th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                @for login in &user.logins {
+                                    <tr>
+                                        <td>(login.created_at)</td>
+                                        <td>(login.method)</td>
+                                        <td>(login.ip)</td>
+                                        <td class="text-xs">(login.user_agent)</td>
+                                    </tr>
+                                }
+                            </tbody>
+                        </table>
+                    }
+
+                    <h3 class="font-bold mt-2">"Failed logins"</h3>
+                    @if user.failed_logins.is_empty() {
+                        <p class="opacity-80">"No recent failed logins."</p>
+                    } @else {
+                        <table class="table table-sm">
+                            <thead>
+                                <tr>
+                                    <th>"Date"</th>
+                                    <th>"IP"</th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                @for failure in &user.failed_logins {
+                                    <tr>
+                                        <td>(failure.created_at)</td>
+                                        <td>(failure.ip)</td>
+                                    </tr>
+                                }
+                            </tbody>
+                        </table>
+                    }
+                </div>
+            </div>
+        </section>
+    }
+}
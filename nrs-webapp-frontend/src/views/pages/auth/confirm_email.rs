@@ -1,6 +1,6 @@
 use hypertext::prelude::*;
 
-use super::Form;
+use crate::views::components::form::Form;
 
 /// Render a form prompting the user to confirm their email and allowing resending the confirmation message.
 ///
@@ -1,6 +1,6 @@
 use hypertext::prelude::*;
 
-use super::Form;
+use crate::views::components::form::Form;
 use crate::views::components::link::{Link, LinkParams};
 
 /// Render the "Recover password" form for submitting an email to request a password reset.
@@ -76,7 +76,7 @@ pub fn reset_pass(token: String) -> impl Renderable {
                 id="reset-password" name="password" type="password" class="input validator w-full" required placeholder="New password"
                 minlength="8" pattern="(?=.*\\d)(?=.*[a-z])(?=.*[A-Z]).{8,}"
                 title="Must be more than 8 characters, including number, lowercase letter, uppercase letter"
-                oninput="document.getElementById('reset-password-confirm').dispatchEvent(new Event('input'))"
+                hx-on:input="document.getElementById('reset-password-confirm').dispatchEvent(new Event('input'))"
                 autocomplete="new-password"
             />
             <p class="validator-hint hidden">
@@ -89,7 +89,7 @@ pub fn reset_pass(token: String) -> impl Renderable {
             <label class="label" for="reset-password-confirm">Confirm new password</label>
             <input
                 id="reset-password-confirm" name="password_confirm" type="password" class="input validator w-full" required placeholder="Confirm new password"
-                oninput="this.setCustomValidity(this.value != document.getElementById('reset-password').value ? 'Passwords do not match' : '')"
+                hx-on:input="this.setCustomValidity(this.value != document.getElementById('reset-password').value ? 'Passwords do not match' : '')"
                 autocomplete="new-password"
             />
             <p class="validator-hint hidden">Passwords do not match</p>
@@ -5,19 +5,31 @@ use crate::views::{
     icons::{Github, Google},
 };
 
-use super::Form;
+use crate::views::components::form::Form;
+
+/// A configured external login provider, shown as a "Login with ..." button.
+pub struct LoginProvider {
+    pub name: String,
+    pub display_name: String,
+}
 
 /// Renders the sign-in page UI with username/password fields, links, and social login buttons.
 ///
 /// The returned component renders a form posting to `/auth/login` with client-side validators,
-/// navigation links for password reset and registration, and buttons for GitHub and Google sign-in.
+/// navigation links for password reset and registration, and one button per login provider,
+/// linking to `/auth/oauth/authorize/<name>`. GitHub and Google get their branded buttons.
 ///
 /// # Examples
 ///
 /// ```
-/// let _login = nrs_webapp_frontend::views::pages::auth::login::login();
+/// use nrs_webapp_frontend::views::pages::auth::login::{LoginProvider, login};
+/// let providers = [LoginProvider {
+///     name: "keycloak".into(),
+///     display_name: "Keycloak".into(),
+/// }];
+/// let _login = login(&providers);
 /// ```
-pub fn login() -> impl Renderable {
+pub fn login(providers: &[LoginProvider]) -> impl Renderable {
     rsx! {
         <Form form_id="signin-form" title="Sign in" hx_post="/auth/login">
             <label class="label" for="signin-username">Username</label>
@@ -35,17 +47,32 @@ pub fn login() -> impl Renderable {
                 <Link params=(LinkParams{href:"/auth/register", class:"link", ..Default::default()})>"Create new account"</Link>
             </div>
 
-            <div class="divider"></div>
-
-            <a href="/auth/oauth/authorize/github" class="btn bg-black text-white border-black">
-                <Github />
-                Login with GitHub
-            </a>
+            @if !providers.is_empty() {
+                <div class="divider"></div>
+            }
 
-            <a href="/auth/oauth/authorize/google" class="btn bg-white text-black border-[#e5e5e5]">
-                <Google />
-                Login with Google
-            </a>
+            @for provider in providers {
+                @let href = format!("/auth/oauth/authorize/{}", provider.name);
+                @match provider.name.as_str() {
+                    "github" => {
+                        <a href=(href) class="btn bg-black text-white border-black">
+                            <Github />
+                            "Login with " (provider.display_name)
+                        </a>
+                    }
+                    "google" => {
+                        <a href=(href) class="btn bg-white text-black border-[#e5e5e5]">
+                            <Google />
+                            "Login with " (provider.display_name)
+                        </a>
+                    }
+                    _ => {
+                        <a href=(href) class="btn btn-outline">
+                            "Login with " (provider.display_name)
+                        </a>
+                    }
+                }
+            }
         </Form>
     }
 }
@@ -1,13 +1,17 @@
 use hypertext::prelude::*;
 
-use super::Form;
+use crate::views::components::form::Form;
 use crate::views::components::link::{Link, LinkParams};
 
 pub enum RegisterScreen {
     Regular,
     OAuth {
+        provider_display_name: String,
         username: Option<String>,
+        /// Prefills the email field when the provider returned no verified address.
         email: Option<String>,
+        /// Addresses verified by the provider; when not empty, the user has to pick one of them.
+        verified_emails: Vec<String>,
     },
 }
 
@@ -17,28 +21,40 @@ pub enum RegisterScreen {
 /// client-side validation attributes and hints, a "Register" submit button, and a link
 /// back to the login page.
 ///
+/// On the OAuth screen, the email input is replaced by a select of the addresses verified by the
+/// provider. Without any, a free email input is shown and the address has to be confirmed by mail.
+///
 /// # Examples
 ///
 /// ```
 /// use nrs_webapp_frontend::views::pages::auth::register::register;
 /// use nrs_webapp_frontend::views::pages::auth::register::RegisterScreen;
 /// let _fragment = register(RegisterScreen::Regular);
+/// let _fragment = register(RegisterScreen::OAuth {
+///     provider_display_name: "GitHub".into(),
+///     username: Some("alice".into()),
+///     email: None,
+///     verified_emails: vec!["alice@example.com".into(), "alice@work.example.com".into()],
+/// });
 /// ```
 pub fn register(screen: RegisterScreen) -> impl Renderable {
     let hx_post = match &screen {
         RegisterScreen::Regular => "/auth/register",
         RegisterScreen::OAuth { .. } => "/auth/oauth/register",
     };
-    let (username, email, email_readonly) = match screen {
+    let (username, email, verified_emails, provider_display_name) = match screen {
         RegisterScreen::Regular => Default::default(),
-        RegisterScreen::OAuth { username, email } => {
-            let username = username.unwrap_or_default();
-            let (email, email_readonly) = match email {
-                Some(email) => (email, true),
-                None => (String::new(), false),
-            };
-            (username, email, email_readonly)
-        }
+        RegisterScreen::OAuth {
+            provider_display_name,
+            username,
+            email,
+            verified_emails,
+        } => (
+            username.unwrap_or_default(),
+            email.unwrap_or_default(),
+            verified_emails,
+            Some(provider_display_name),
+        ),
     };
     rsx! {
         <Form form_id="signup-form" title="Sign up" hx_post=(hx_post)>
@@ -54,17 +70,35 @@ pub fn register(screen: RegisterScreen) -> impl Renderable {
             </p>
 
             <label class="label" for="signup-email">Email</label>
-            <input id="signup-email" name="email" type="email" class="input validator w-full" required placeholder="Email"
-                autocomplete = "email" value=(email) readonly=(email_readonly)
-            />
-            <div class="validator-hint hidden">Please enter a valid email</div>
+            @if verified_emails.is_empty() {
+                <input id="signup-email" name="email" type="email" class="input validator w-full" required placeholder="Email"
+                    autocomplete = "email" value=(email)
+                />
+                <div class="validator-hint hidden">Please enter a valid email</div>
+                @if let Some(provider_display_name) = &provider_display_name {
+                    <p class="text-xs opacity-80">
+                        (provider_display_name) " did not provide a verified email address, you will need to confirm this one by email."
+                    </p>
+                }
+            } @else {
+                <select id="signup-email" name="email" class="select w-full" required>
+                    @for verified_email in &verified_emails {
+                        <option value=(verified_email)>(verified_email)</option>
+                    }
+                </select>
+                @if let Some(provider_display_name) = &provider_display_name {
+                    <p class="text-xs opacity-80">
+                        "Only the verified addresses of your " (provider_display_name) " account are listed."
+                    </p>
+                }
+            }
 
             <label class="label" for="signup-password">Password</label>
             <input
                 id="signup-password" name="password" type="password" class="input validator w-full" required placeholder="Password"
                 minlength="8" maxlength="50" pattern="(?=.*\\d)(?=.*[a-z])(?=.*[A-Z]).{8,}"
                 title="Must be 8-50 characters, including number, lowercase letter, uppercase letter"
-                oninput="document.getElementById('signup-password-confirm').dispatchEvent(new Event('input'))"
+                hx-on:input="document.getElementById('signup-password-confirm').dispatchEvent(new Event('input'))"
                 autocomplete="new-password"
             />
             <p class="validator-hint hidden">
@@ -77,7 +111,7 @@ pub fn register(screen: RegisterScreen) -> impl Renderable {
             <label class="label" for="signup-password-confirm">Confirm Password</label>
             <input
                 id="signup-password-confirm" name="password_confirm" type="password" class="input validator w-full" required placeholder="Confirm Password"
-                oninput="this.setCustomValidity(this.value != document.getElementById('signup-password').value ? 'Passwords do not match' : '')"
+                hx-on:input="this.setCustomValidity(this.value != document.getElementById('signup-password').value ? 'Passwords do not match' : '')"
                 autocomplete="new-password"
             />
             <p class="validator-hint hidden">Passwords do not match</p>
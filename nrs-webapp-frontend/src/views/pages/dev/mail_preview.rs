@@ -0,0 +1,56 @@
+use hypertext::prelude::*;
+use strum::VariantArray;
+
+use crate::views::email::Locale;
+
+/// Renders the dev-only index of the email templates, linking to the HTML and plain-text
+/// previews of each template in every [`Locale`] at
+/// `/__dev_only/mail-preview/<template>?locale=<locale>&format=<html|text>`.
+///
+/// The previews are plain links opening in a new tab, as they are not app pages.
+///
+/// # Examples
+///
+/// ```
+/// use hypertext::Renderable;
+/// use nrs_webapp_frontend::views::pages::dev::mail_preview::mail_preview_page;
+///
+/// let html = mail_preview_page(&["email_verify"]).render().into_inner();
+/// assert!(html.contains("/__dev_only/mail-preview/email_verify?locale=fr&amp;format=text"));
+/// ```
+pub fn mail_preview_page(templates: &[&str]) -> impl Renderable {
+    rsx! {
+        <section class="flex flex-col items-center gap-6 p-4 w-full">
+            <h1 class="font-bold text-3xl">"Email previews"</h1>
+
+            <div class="overflow-x-auto w-full max-w-3xl">
+                <table class="table table-zebra">
+                    <thead>
+                        <tr>
+                            <th>"Template"</th>
+                            @for locale in Locale::VARIANTS {
+                                <th>(<&'static str>::from(locale))</th>
+                            }
+                        </tr>
+                    </thead>
+                    <tbody>
+                        @for template in templates {
+                            <tr>
+                                <td class="font-mono">(template)</td>
+                                @for locale in Locale::VARIANTS {
+                                    @let locale: &'static str = locale.into();
+                                    <td class="flex gap-2">
+                                        @for format in ["html", "text"] {
+                                            @let href = format!("/__dev_only/mail-preview/{template}?locale={locale}&format={format}");
+                                            <a class="link" href=(href) target="_blank">(format)</a>
+                                        }
+                                    </td>
+                                }
+                            </tr>
+                        }
+                    </tbody>
+                </table>
+            </div>
+        </section>
+    }
+}
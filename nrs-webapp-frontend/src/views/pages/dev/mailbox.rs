@@ -0,0 +1,133 @@
+use hypertext::prelude::*;
+
+use crate::views::components::link::{Link, LinkParams};
+
+pub struct MailboxEntry {
+    pub id: u64,
+    pub to: String,
+    pub subject: String,
+    pub captured_at: String,
+}
+
+pub struct MailboxMessage<'a> {
+    pub id: u64,
+    pub from: &'a str,
+    pub to: &'a str,
+    pub subject: &'a str,
+    pub captured_at: String,
+    pub html: &'a str,
+    pub text: &'a str,
+}
+
+/// Renders the dev-only mailbox, listing the emails captured instead of being sent, newest
+/// first, each linking to `/__dev_only/mailbox/<id>`.
+///
+/// The clear button sends `DELETE /__dev_only/mailbox`.
+///
+/// # Examples
+///
+/// ```
+/// use hypertext::Renderable;
+/// use nrs_webapp_frontend::views::pages::dev::mailbox::{MailboxEntry, mailbox_page};
+///
+/// let mails = vec![MailboxEntry {
+///     id: 3,
+///     to: "alice@example.com".into(),
+///     subject: "Please verify your email address".into(),
+///     captured_at: "2025-01-01 12:00:00".into(),
+/// }];
+/// let html = mailbox_page(&mails).render().into_inner();
+/// assert!(html.contains("/__dev_only/mailbox/3"));
+/// ```
+pub fn mailbox_page(mails: &[MailboxEntry]) -> impl Renderable {
+    rsx! {
+        <section class="flex flex-col items-center gap-6 p-4 w-full">
+            <h1 class="font-bold text-3xl">"Mailbox"</h1>
+
+            @if mails.is_empty() {
+                <p class="opacity-80">"No emails captured yet."</p>
+            } @else {
+                <button
+                    class="btn btn-sm btn-outline"
+                    hx-delete="/__dev_only/mailbox"
+                    hx-confirm="Clear the mailbox?"
+                >
+                    "Clear"
+                </button>
+                <div class="overflow-x-auto w-full max-w-4xl">
+                    <table class="table table-zebra">
+                        <thead>
+                            <tr>
+                                <th>"ID"</th>
+                                <th>"Recipient"</th>
+                                <th>"Subject"</th>
+                                <th>"Captured at"</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            @for mail in mails {
+                                @let href = format!("/__dev_only/mailbox/{}", mail.id);
+                                <tr>
+                                    <td>(mail.id)</td>
+                                    <td>(mail.to)</td>
+                                    <td>
+                                        <Link params=(LinkParams { href: href.as_str(), class: "link", ..Default::default() })>(mail.subject)</Link>
+                                    </td>
+                                    <td>(mail.captured_at)</td>
+                                </tr>
+                            }
+                        </tbody>
+                    </table>
+                </div>
+            }
+        </section>
+    }
+}
+
+/// Renders a captured email: its headers, its HTML body in a sandboxed frame where links open in
+/// a new tab, and its plain-text body.
+///
+/// # Examples
+///
+/// ```
+/// use hypertext::Renderable;
+/// use nrs_webapp_frontend::views::pages::dev::mailbox::{MailboxMessage, mailbox_message_page};
+///
+/// let html = mailbox_message_page(&MailboxMessage {
+///     id: 1,
+///     from: "accounts@nrs.dev",
+///     to: "alice@example.com",
+///     subject: "Hi",
+///     captured_at: "2025-01-01 12:00:00".into(),
+///     html: r#"<a href="https://nrs.dev">Open</a>"#,
+///     text: "Open (https://nrs.dev)",
+/// })
+/// .render()
+/// .into_inner();
+/// assert!(html.contains("srcdoc=\"&lt;a href=&quot;https://nrs.dev&quot;&gt;Open&lt;/a&gt;\""));
+/// ```
+pub fn mailbox_message_page(mail: &MailboxMessage) -> impl Renderable {
+    rsx! {
+        <section class="flex flex-col gap-4 p-4 w-full max-w-4xl mx-auto">
+            <Link params=(LinkParams { href: "/__dev_only/mailbox", class: "link", ..Default::default() })>"Back to the mailbox"</Link>
+            <h1 class="font-bold text-2xl">(mail.subject)</h1>
+            <dl class="grid grid-cols-[auto_1fr] gap-x-4 text-sm">
+                <dt class="font-semibold">"ID"</dt><dd>(mail.id)</dd>
+                <dt class="font-semibold">"From"</dt><dd>(mail.from)</dd>
+                <dt class="font-semibold">"To"</dt><dd>(mail.to)</dd>
+                <dt class="font-semibold">"Captured at"</dt><dd>(mail.captured_at)</dd>
+            </dl>
+
+            <h2 class="font-bold text-xl">"HTML"</h2>
+            <iframe
+                class="w-full h-96 bg-white rounded-box border"
+                title="HTML body"
+                sandbox="allow-popups allow-popups-to-escape-sandbox"
+                srcdoc=(mail.html)
+            ></iframe>
+
+            <h2 class="font-bold text-xl">"Plain text"</h2>
+            <pre class="whitespace-pre-wrap bg-base-200 rounded-box p-4">(mail.text)</pre>
+        </section>
+    }
+}
@@ -0,0 +1,2 @@
+pub mod mail_preview;
+pub mod mailbox;
@@ -1,29 +1,60 @@
 use hypertext::prelude::*;
 use nrs_webapp_core::data::entry::types::idtype::EntryType;
 
+use crate::views::{
+    components::link::{Link, LinkParams},
+    pages::entry::{DELETED_USER, EntryTab, entry_tabs},
+};
+
 pub struct EntryDetails {
     pub id: String,
     pub title: String,
     pub entry_type: EntryType,
-    pub added_by_id: String,
-    pub added_by_username: String,
+    pub added_by_id: Option<String>,
+    pub added_by_username: Option<String>,
     pub info_json: String,
+    /// `"pending"`, `"approved"` or `"rejected"`.
+    pub status: &'static str,
+    pub rejection_reason: Option<String>,
+    /// Whether the user may edit the entry, which shows a link to `/entry/<id>/edit`.
+    pub can_edit: bool,
 }
 
 pub fn entry_details_page(entry: &EntryDetails) -> impl Renderable {
+    let edit_href = format!("/entry/{}/edit", entry.id);
     rsx! {
         <section class="flex flex-col items-center gap-10">
             <h1 class="font-bold text-3xl">("Entry Details Page (UNDER CONSTRUCTION)")</h1>
+            (entry_tabs(&entry.id, EntryTab::Details))
             <div>
                 <h2 class="font-semibold text-2xl">(entry.title)</h2>
                 <p>"Type: " (entry.entry_type.to_display_string())</p>
                 <p>"ID: " (entry.id)</p>
-                <p>"Added by: " (entry.added_by_username) " (ID: " (entry.added_by_id) ")"</p>
+                @match entry.status {
+                    "pending" => {
+                        <p class="badge badge-warning">"Waiting for a moderator"</p>
+                    }
+                    "rejected" => {
+                        <p class="badge badge-error">"Rejected"</p>
+                        @if let Some(reason) = &entry.rejection_reason {
+                            <p>"Reason: " (reason)</p>
+                        }
+                    }
+                    _ => {}
+                }
+                @if let (Some(username), Some(id)) = (&entry.added_by_username, &entry.added_by_id) {
+                    <p>"Added by: " (username) " (ID: " (id) ")"</p>
+                } @else {
+                    <p>"Added by: " (DELETED_USER)</p>
+                }
                 <pre>
                     <code class="language-json">
                         (entry.info_json)
                     </code>
                 </pre>
+                @if entry.can_edit {
+                    <Link params=(LinkParams { href: edit_href.as_str(), class: "btn btn-neutral mt-4", ..Default::default() })>"Edit"</Link>
+                }
             </div>
         </section>
     }
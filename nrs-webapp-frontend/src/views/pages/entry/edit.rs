@@ -0,0 +1,63 @@
+use hypertext::prelude::*;
+use nrs_webapp_core::data::entry::types::idtype::EntryType;
+
+use crate::views::components::{
+    form::Form,
+    link::{Link, LinkParams},
+};
+
+pub struct EntryEditForm {
+    pub id: String,
+    pub title: String,
+    pub entry_type: EntryType,
+    /// The entry info as pretty-printed JSON.
+    pub info_json: String,
+}
+
+/// Render the form editing the content of an entry, prefilled with its current content.
+///
+/// The form has id `"editentry-form"` and posts `title`, `entry_type`, `entry_info` (a JSON
+/// object) and an optional `comment` describing the change to `/entry/<id>/edit`.
+///
+/// # Examples
+///
+/// ```
+/// use nrs_webapp_core::data::entry::types::idtype::EntryType;
+/// use nrs_webapp_frontend::views::pages::entry::edit::{EntryEditForm, entry_edit_page};
+/// let form = EntryEditForm {
+///     id: "A-MAL-1".into(),
+///     title: "Cowboy Bebop".into(),
+///     entry_type: EntryType::Anime,
+///     info_json: "{}".into(),
+/// };
+/// let _view = entry_edit_page(&form);
+/// ```
+pub fn entry_edit_page(form: &EntryEditForm) -> impl Renderable {
+    let hx_post = format!("/entry/{}/edit", form.id);
+    let back_href = format!("/entry/{}", form.id);
+    rsx! {
+        <Form form_id="editentry-form" title="Edit entry" hx_post=(&hx_post)>
+            <label class="label" for="editentry-title">"Title"</label>
+            <input id="editentry-title" name="title" type="text" class="input validator w-full" required maxlength="512" value=(form.title) />
+
+            <label class="label" for="editentry-type">"Type"</label>
+            <select id="editentry-type" name="entry_type" class="select w-full" required>
+                @for entry_type in EntryType::all() {
+                    <option value=(entry_type.to_enum_string()) selected[entry_type == form.entry_type]>
+                        (entry_type.to_display_string())
+                    </option>
+                }
+            </select>
+
+            <label class="label" for="editentry-info">"Info (JSON)"</label>
+            <textarea id="editentry-info" name="entry_info" class="textarea w-full font-mono" rows="12" required>(form.info_json)</textarea>
+
+            <label class="label" for="editentry-comment">"Comment"</label>
+            <input id="editentry-comment" name="comment" type="text" class="input w-full" maxlength="500" placeholder="Describe your change" />
+
+            <button type="submit" class="btn btn-neutral mt-4">"Save"</button>
+
+            <Link params=(LinkParams { href: back_href.as_str(), class: "btn btn-secondary", ..Default::default() })>"Cancel"</Link>
+        </Form>
+    }
+}
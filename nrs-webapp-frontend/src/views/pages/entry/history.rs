@@ -0,0 +1,120 @@
+use hypertext::prelude::*;
+
+use crate::views::pages::entry::{DELETED_USER, EntryTab, entry_tabs};
+
+/// A value changed by a revision, as compact JSON.
+pub struct RevisionChangeView {
+    /// Dotted path of the value, such as `entry_info.links.mal`.
+    pub path: String,
+    /// `None` when the value was added.
+    pub before: Option<String>,
+    /// `None` when the value was removed.
+    pub after: Option<String>,
+}
+
+pub struct EntryRevisionView {
+    pub revision: i32,
+    pub created_at: String,
+    /// `None` when the author deleted their account.
+    pub author: Option<String>,
+    pub comment: String,
+    /// The changes since the previous revision, empty for the first one.
+    pub changes: Vec<RevisionChangeView>,
+}
+
+/// Renders the revision history of an entry, newest first, with the changes of every revision
+/// since the previous one.
+///
+/// When `can_rollback` is set, every revision but the current one gets a button posting its
+/// number as `revision` to `/entry/<id>/rollback`.
+///
+/// # Examples
+///
+/// ```
+/// use nrs_webapp_frontend::views::pages::entry::history::{
+///     EntryRevisionView, RevisionChangeView, entry_history_page,
+/// };
+/// let revisions = vec![
+///     EntryRevisionView {
+///         revision: 2,
+///         created_at: "2025-01-02 12:00:00".into(),
+///         author: Some("alice".into()),
+///         comment: "Fix the title".into(),
+///         changes: vec![RevisionChangeView {
+///             path: "title".into(),
+///             before: Some(r#""Cowboy Bebo""#.into()),
+///             after: Some(r#""Cowboy Bebop""#.into()),
+///         }],
+///     },
+///     EntryRevisionView {
+///         revision: 1,
+///         created_at: "2025-01-01 12:00:00".into(),
+///         author: None,
+///         comment: "Initial revision".into(),
+///         changes: vec![],
+///     },
+/// ];
+/// let _view = entry_history_page("A-MAL-1", "Cowboy Bebop", &revisions, true);
+/// ```
+pub fn entry_history_page(
+    id: &str,
+    title: &str,
+    revisions: &[EntryRevisionView],
+    can_rollback: bool,
+) -> impl Renderable {
+    let rollback_href = format!("/entry/{id}/rollback");
+    rsx! {
+        <section class="flex flex-col items-center gap-6 p-4 w-full">
+            <h1 class="font-bold text-3xl">"History of " (title)</h1>
+            (entry_tabs(id, EntryTab::History))
+
+            <ul class="flex flex-col gap-4 w-full max-w-4xl">
+                @for (index, revision) in revisions.iter().enumerate() {
+                    <li class="card bg-base-200">
+                        <div class="card-body">
+                            <div class="flex items-center justify-between gap-2">
+                                <h2 class="card-title">
+                                    "Revision " (revision.revision)
+                                    @if index == 0 {
+                                        <span class="badge badge-success">"current"</span>
+                                    }
+                                </h2>
+                                @if can_rollback && index > 0 {
+                                    <button
+                                        class="btn btn-sm btn-outline btn-warning"
+                                        hx-post=(&rollback_href)
+                                        hx-vals=(format!(r#"{{"revision":{}}}"#, revision.revision))
+                                        hx-confirm=(format!("Roll back the entry to revision {}?", revision.revision))
+                                    >
+                                        "Roll back to this revision"
+                                    </button>
+                                }
+                            </div>
+                            <p class="text-sm opacity-80">
+                                (revision.created_at) " by " (revision.author.as_deref().unwrap_or(DELETED_USER))
+                            </p>
+                            @if !revision.comment.is_empty() {
+                                <p>(revision.comment)</p>
+                            }
+                            @if !revision.changes.is_empty() {
+                                <div class="overflow-x-auto">
+                                    <table class="table table-sm">
+                                        <tbody>
+                                            @for change in &revision.changes {
+                                                <tr>
+                                                    <td class="font-semibold">(change.path)</td>
+                                                    <td><code class="text-error">(change.before.as_deref().unwrap_or("-"))</code></td>
+                                                    <td><code class="text-success">(change.after.as_deref().unwrap_or("-"))</code></td>
+                                                </tr>
+                                            }
+                                        </tbody>
+                                    </table>
+                                </div>
+                            }
+                        </div>
+                    </li>
+                }
+            </ul>
+        </section>
+    }
+}
@@ -1,29 +1,101 @@
 use hypertext::prelude::*;
 use nrs_webapp_core::data::entry::types::idtype::EntryType;
 
-use crate::views::components::link::{Link, LinkParams};
+use crate::views::{
+    components::{
+        link::{Link, LinkParams},
+        pager::Pager,
+    },
+    pages::entry::DELETED_USER,
+};
 
 pub struct EntryListEntry {
     pub id: String,
     pub title: String,
     pub entry_type: EntryType,
-    pub added_by: String,
+    pub added_by: Option<String>,
 }
 
-pub fn entry_list_page(entries: &[EntryListEntry]) -> impl Renderable {
+/// Links to the neighbouring pages of the entry list, `None` at either end.
+#[derive(Default)]
+pub struct EntryListPager {
+    pub prev_href: Option<String>,
+    pub next_href: Option<String>,
+    pub total: Option<u64>,
+}
+
+/// The current filter of the entry list, to prefill its form with.
+#[derive(Default)]
+pub struct EntryListFilter {
+    pub title_contains: Option<String>,
+    /// The selected type, `None` for all of them.
+    pub entry_type: Option<EntryType>,
+}
+
+/// Renders a page of the approved entries, with a link to `/entry/new` when `can_submit`.
+///
+/// The filter form submits `title_contains` and `entry_type_in` to `/entry`, which starts over
+/// from the first page.
+///
+/// # Examples
+///
+/// ```
+/// use nrs_webapp_core::data::entry::types::idtype::EntryType;
+/// use nrs_webapp_frontend::views::pages::entry::list::{
+///     EntryListEntry, EntryListFilter, EntryListPager, entry_list_page,
+/// };
+/// let entries = vec![EntryListEntry {
+///     id: "A-MAL-1".into(),
+///     title: "Cowboy Bebop".into(),
+///     entry_type: EntryType::Anime,
+///     added_by: Some("alice".into()),
+/// }];
+/// let pager = EntryListPager {
+///     next_href: Some("/entry?cursor=abc".into()),
+///     ..Default::default()
+/// };
+/// let filter = EntryListFilter {
+///     title_contains: Some("bebop".into()),
+///     entry_type: Some(EntryType::Anime),
+/// };
+/// let _view = entry_list_page(&entries, &filter, &pager, true);
+/// ```
+pub fn entry_list_page(
+    entries: &[EntryListEntry],
+    filter: &EntryListFilter,
+    pager: &EntryListPager,
+    can_submit: bool,
+) -> impl Renderable {
     rsx! {
         <div class="flex flex-col items-center gap-10 w-full max-w-4xl">
             <h1 class="font-bold text-3xl">("Entry List Page (UNDER CONSTRUCTION)")</h1>
+            @if can_submit {
+                <Link params=(LinkParams { href: "/entry/new", class: "btn btn-neutral", ..Default::default() })>"Submit an entry"</Link>
+            }
+            <form method="get" action="/entry" class="join">
+                <input type="search" name="title_contains" class="input join-item" placeholder="Title" value=[filter.title_contains.as_deref()] />
+                <select name="entry_type_in" class="select join-item">
+                    <option value="">"All types"</option>
+                    @for entry_type in EntryType::all() {
+                        <option value=(entry_type.to_enum_string()) selected[filter.entry_type == Some(entry_type)]>(entry_type.to_display_string())</option>
+                    }
+                </select>
+                <button type="submit" class="btn btn-neutral join-item">"Filter"</button>
+            </form>
+            @if entries.is_empty() {
+                <p class="opacity-80">"No entries to show."</p>
+            }
             <ul>
                 @for EntryListEntry { id, title, entry_type, added_by } in entries {
                     @let href = format!("/entry/{}", id);
                     <li>
                         <Link params=(LinkParams {href: href.as_str(), class: "link link-hover", ..Default::default()})>
-                            (title)" (" (entry_type.to_display_string()) ", added by " (added_by) ")"
+                            (title)" (" (entry_type.to_display_string()) ", added by " (added_by.as_deref().unwrap_or(DELETED_USER)) ")"
                         </Link>
                     </li>
                 }
             </ul>
+            <Pager prev_href=(pager.prev_href.as_deref()) next_href=(pager.next_href.as_deref()) total=(pager.total) />
         </div>
     }
 }
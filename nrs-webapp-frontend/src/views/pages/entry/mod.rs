@@ -1,2 +1,34 @@
+use hypertext::prelude::*;
+
+use crate::views::components::link::{Link, LinkParams};
+
 pub mod details;
+pub mod edit;
+pub mod history;
 pub mod list;
+pub mod submit;
+
+/// Placeholder shown in place of the submitter of entries whose account has been deleted.
+pub(crate) const DELETED_USER: &str = "[deleted user]";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EntryTab {
+    Details,
+    History,
+}
+
+/// The tabs switching between the pages of the entry `id`.
+fn entry_tabs(id: &str, active: EntryTab) -> impl Renderable {
+    let tabs = [
+        ("Details", format!("/entry/{id}"), EntryTab::Details),
+        ("History", format!("/entry/{id}/history"), EntryTab::History),
+    ];
+    rsx! {
+        <div role="tablist" class="tabs tabs-box">
+            @for (label, href, tab) in &tabs {
+                @let class = if *tab == active { "tab tab-active" } else { "tab" };
+                <Link params=(LinkParams { href: href.as_str(), class, ..Default::default() })>(label)</Link>
+            }
+        </div>
+    }
+}
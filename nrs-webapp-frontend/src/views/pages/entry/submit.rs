@@ -0,0 +1,46 @@
+use hypertext::prelude::*;
+use nrs_webapp_core::data::entry::types::idtype::EntryType;
+
+use crate::views::components::{
+    form::Form,
+    link::{Link, LinkParams},
+};
+
+/// Render the form submitting a new entry.
+///
+/// The form has id `"submitentry-form"` and posts `id`, `title`, `entry_type` and `entry_info`
+/// (a JSON object) to `/entry/new`.
+///
+/// # Examples
+///
+/// ```
+/// use nrs_webapp_frontend::views::pages::entry::submit::entry_submit_page;
+/// let _view = entry_submit_page();
+/// ```
+pub fn entry_submit_page() -> impl Renderable {
+    rsx! {
+        <Form form_id="submitentry-form" title="Submit entry" hx_post="/entry/new">
+            <p class="text-sm opacity-80">"Submitted entries are listed once a moderator approves them."</p>
+
+            <label class="label" for="submitentry-id">"ID"</label>
+            <input id="submitentry-id" name="id" type="text" class="input validator w-full" required maxlength="50" pattern="[A-Za-z0-9_-]+" placeholder="A-MAL-1" />
+
+            <label class="label" for="submitentry-title">"Title"</label>
+            <input id="submitentry-title" name="title" type="text" class="input validator w-full" required maxlength="512" />
+
+            <label class="label" for="submitentry-type">"Type"</label>
+            <select id="submitentry-type" name="entry_type" class="select w-full" required>
+                @for entry_type in EntryType::all() {
+                    <option value=(entry_type.to_enum_string())>(entry_type.to_display_string())</option>
+                }
+            </select>
+
+            <label class="label" for="submitentry-info">"Info (JSON)"</label>
+            <textarea id="submitentry-info" name="entry_info" class="textarea w-full font-mono" rows="12" required>"{}"</textarea>
+
+            <button type="submit" class="btn btn-neutral mt-4">"Submit"</button>
+
+            <Link params=(LinkParams { href: "/entry", class: "btn btn-secondary", ..Default::default() })>"Cancel"</Link>
+        </Form>
+    }
+}
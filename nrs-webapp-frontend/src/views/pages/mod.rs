@@ -1,3 +1,7 @@
+pub mod account;
+pub mod admin;
 pub mod auth;
+pub mod dev;
 pub mod entry;
 pub mod home;
+pub mod moderation;
@@ -0,0 +1,87 @@
+use hypertext::prelude::*;
+use nrs_webapp_core::data::entry::types::idtype::EntryType;
+
+use crate::views::{
+    components::link::{Link, LinkParams},
+    pages::entry::DELETED_USER,
+};
+
+pub struct PendingEntry {
+    pub id: String,
+    pub title: String,
+    pub entry_type: EntryType,
+    pub submitted_by: Option<String>,
+    pub submitted_at: String,
+}
+
+/// Renders the queue of the entries waiting for a moderator, the oldest submission first.
+///
+/// Each entry links to its page and to its edit form, and can be approved with a post to
+/// `/moderation/<id>/approve`, or rejected by posting a `reason` to `/moderation/<id>/reject`.
+///
+/// # Examples
+///
+/// ```
+/// use nrs_webapp_core::data::entry::types::idtype::EntryType;
+/// use nrs_webapp_frontend::views::pages::moderation::{PendingEntry, moderation_queue_page};
+/// let entries = vec![PendingEntry {
+///     id: "A-MAL-1".into(),
+///     title: "Cowboy Bebop".into(),
+///     entry_type: EntryType::Anime,
+///     submitted_by: Some("alice".into()),
+///     submitted_at: "2025-01-01 12:00:00".into(),
+/// }];
+/// let _view = moderation_queue_page(&entries);
+/// ```
+pub fn moderation_queue_page(entries: &[PendingEntry]) -> impl Renderable {
+    rsx! {
+        <section class="flex flex-col items-center gap-6 p-4 w-full">
+            <h1 class="font-bold text-3xl">"Moderation queue"</h1>
+
+            @if entries.is_empty() {
+                <p class="opacity-80">"No entries are waiting for review."</p>
+            } @else {
+                <div class="overflow-x-auto w-full max-w-6xl">
+                    <table class="table table-zebra">
+                        <thead>
+                            <tr>
+                                <th>"Entry"</th>
+                                <th>"Type"</th>
+                                <th>"Submitted by"</th>
+                                <th>"Submitted at"</th>
+                                <th>"Review"</th>
+                            </tr>
+                        </thead>
+                        <tbody>
+                            @for entry in entries {
+                                @let href = format!("/entry/{}", entry.id);
+                                @let edit_href = format!("/entry/{}/edit", entry.id);
+                                @let approve = format!("/moderation/{}/approve", entry.id);
+                                @let reject = format!("/moderation/{}/reject", entry.id);
+                                <tr>
+                                    <td>
+                                        <Link params=(LinkParams { href: href.as_str(), class: "link", ..Default::default() })>(entry.title)</Link>
+                                        <div class="text-xs opacity-80">(entry.id)</div>
+                                    </td>
+                                    <td>(entry.entry_type.to_display_string())</td>
+                                    <td>(entry.submitted_by.as_deref().unwrap_or(DELETED_USER))</td>
+                                    <td>(entry.submitted_at)</td>
+                                    <td class="flex flex-col gap-2">
+                                        <div class="flex gap-2">
+                                            <Link params=(LinkParams { href: edit_href.as_str(), class: "btn btn-sm btn-ghost", ..Default::default() })>"Edit"</Link>
+                                            <button class="btn btn-sm btn-success" hx-post=(approve) hx-confirm="Approve this entry?">"Approve"</button>
+                                        </div>
+                                        <form class="join" hx-post=(reject)>
+                                            <input type="text" name="reason" class="input input-sm join-item" required maxlength="500" placeholder="Reason" />
+                                            <button type="submit" class="btn btn-sm btn-error join-item">"Reject"</button>
+                                        </form>
+                                    </td>
+                                </tr>
+                            }
+                        </tbody>
+                    </table>
+                </div>
+            }
+        </section>
+    }
+}
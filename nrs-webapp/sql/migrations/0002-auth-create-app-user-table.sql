@@ -6,6 +6,7 @@ CREATE TABLE IF NOT EXISTS app_user (
   email TEXT COLLATE case_insensitive NOT NULL,
   email_verified_at TIMESTAMPTZ,
   password_hash TEXT NOT NULL,
+  locked_until TIMESTAMPTZ,
   created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
   updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
   UNIQUE(username),
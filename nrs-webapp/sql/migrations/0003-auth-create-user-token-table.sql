@@ -1,6 +1,7 @@
 CREATE TYPE USER_ONE_TIME_TOKEN_PURPOSE AS ENUM (
   'EMAIL_VERIFICATION',
-  'PASSWORD_RESET'
+  'PASSWORD_RESET',
+  'ACCOUNT_UNLOCK'
 );
 
 CREATE TABLE IF NOT EXISTS user_one_time_token (
@@ -18,7 +18,7 @@ CREATE TABLE entry (
     id VARCHAR(50) NOT NULL PRIMARY KEY,
     title VARCHAR(512) NOT NULL,
     entry_type ENTRYTYPE NOT NULL,
-    added_by UUID REFERENCES app_user(id),
+    added_by UUID REFERENCES app_user(id) ON DELETE SET NULL,
     entry_info JSONB NOT NULL DEFAULT '{}'::JSONB
 );
 
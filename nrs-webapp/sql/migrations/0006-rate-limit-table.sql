@@ -0,0 +1,10 @@
+-- fixed-window counters for the Postgres rate-limit backend, shared by every instance
+-- counters are cheap to lose, so the table skips the WAL
+CREATE UNLOGGED TABLE IF NOT EXISTS rate_limit_bucket (
+  key TEXT NOT NULL PRIMARY KEY,
+  window_start TIMESTAMPTZ NOT NULL,
+  hits INTEGER NOT NULL
+);
+
+CREATE INDEX rate_limit_bucket_window_start_idx
+ON rate_limit_bucket (window_start);
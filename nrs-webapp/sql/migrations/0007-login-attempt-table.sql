@@ -0,0 +1,14 @@
+-- failed password logins, used for the progressive delays and account lockout
+CREATE TABLE login_attempt (
+  id BIGSERIAL PRIMARY KEY,
+  user_id UUID REFERENCES app_user(id) ON DELETE CASCADE,
+  ip TEXT NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX login_attempt_user_idx
+ON login_attempt (user_id, created_at)
+WHERE user_id IS NOT NULL;
+
+CREATE INDEX login_attempt_ip_idx
+ON login_attempt (ip, created_at);
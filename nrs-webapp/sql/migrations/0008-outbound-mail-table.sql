@@ -0,0 +1,30 @@
+CREATE TYPE OUTBOUND_MAIL_STATUS AS ENUM (
+  'PENDING',
+  'SENT',
+  'DEAD'
+);
+
+-- outbox of the emails to send, written in the same transaction as the data they refer to and
+-- delivered by a background worker
+CREATE TABLE outbound_mail (
+  id BIGSERIAL PRIMARY KEY,
+  recipient TEXT NOT NULL,
+  sender TEXT NOT NULL,
+  subject TEXT NOT NULL,
+  -- encrypted since the bodies contain one-time tokens, cleared once the mail is sent
+  html_body BYTEA,
+  text_body BYTEA,
+  status OUTBOUND_MAIL_STATUS NOT NULL DEFAULT 'PENDING',
+  attempts INTEGER NOT NULL DEFAULT 0,
+  next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  last_error TEXT,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  sent_at TIMESTAMPTZ
+);
+
+CREATE INDEX outbound_mail_due_idx
+ON outbound_mail (next_attempt_at)
+WHERE status = 'PENDING';
+
+CREATE INDEX outbound_mail_created_at_idx
+ON outbound_mail (created_at);
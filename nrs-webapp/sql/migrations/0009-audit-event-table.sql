@@ -0,0 +1,30 @@
+CREATE TYPE AUDIT_ACTION AS ENUM (
+  'CREATE',
+  'UPDATE',
+  'DELETE'
+);
+
+-- history of the changes to the audited tables, with the fields that changed
+-- no foreign key on the actor, so that the history outlives deleted accounts
+CREATE TABLE audit_event (
+  id BIGSERIAL PRIMARY KEY,
+  actor_id UUID,
+  request_id UUID,
+  ip TEXT,
+  action AUDIT_ACTION NOT NULL,
+  entity_table TEXT NOT NULL,
+  entity_id TEXT NOT NULL,
+  before JSONB,
+  after JSONB,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX audit_event_entity_idx
+ON audit_event (entity_table, entity_id, created_at);
+
+CREATE INDEX audit_event_actor_idx
+ON audit_event (actor_id, created_at)
+WHERE actor_id IS NOT NULL;
+
+CREATE INDEX audit_event_created_at_idx
+ON audit_event (created_at);
@@ -0,0 +1,19 @@
+CREATE TABLE entry_revision (
+    entry_id VARCHAR(50) NOT NULL REFERENCES entry(id) ON DELETE CASCADE,
+    revision INTEGER NOT NULL,
+    title VARCHAR(512) NOT NULL,
+    entry_type ENTRYTYPE NOT NULL,
+    entry_info JSONB NOT NULL,
+    -- NULL when the author deleted their account
+    author_id UUID REFERENCES app_user(id) ON DELETE SET NULL,
+    comment VARCHAR(500) NOT NULL DEFAULT '',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (entry_id, revision)
+);
+
+CREATE INDEX entry_revision_author_idx ON entry_revision (author_id) WHERE author_id IS NOT NULL;
+
+-- the entries added before revisions were kept start their history from their current content
+INSERT INTO entry_revision (entry_id, revision, title, entry_type, entry_info, author_id, comment)
+SELECT id, 1, title, entry_type, entry_info, added_by, 'Initial revision'
+FROM entry;
@@ -0,0 +1,16 @@
+CREATE TYPE USER_ROLE AS ENUM (
+  'ADMIN',
+  'MODERATOR',
+  'EDITOR'
+);
+
+CREATE TABLE app_user_role (
+  user_id UUID NOT NULL REFERENCES app_user(id) ON DELETE CASCADE,
+  role USER_ROLE NOT NULL,
+  -- NULL when granted on startup from the config, or when the granting user deleted their account
+  granted_by UUID REFERENCES app_user(id) ON DELETE SET NULL,
+  granted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  PRIMARY KEY (user_id, role)
+);
+
+CREATE INDEX app_user_role_role_idx ON app_user_role (role);
@@ -0,0 +1,17 @@
+-- disabled accounts cannot log in, and their sessions are no longer accepted
+ALTER TABLE app_user ADD COLUMN disabled_at TIMESTAMPTZ;
+
+-- successful logins, shown to the admins; failed ones are in login_attempt
+CREATE TABLE login_event (
+  id BIGSERIAL PRIMARY KEY,
+  user_id UUID NOT NULL REFERENCES app_user(id) ON DELETE CASCADE,
+  -- "password", or the name of the OAuth provider
+  method TEXT NOT NULL,
+  ip TEXT NOT NULL,
+  user_agent TEXT NOT NULL,
+  created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX login_event_user_idx ON login_event (user_id, created_at);
+
+CREATE INDEX login_event_created_at_idx ON login_event (created_at);
@@ -0,0 +1,19 @@
+CREATE TYPE ENTRY_STATUS AS ENUM (
+  'PENDING',
+  'APPROVED',
+  'REJECTED'
+);
+
+-- the existing entries were added before submissions were moderated
+ALTER TABLE entry
+  ADD COLUMN status ENTRY_STATUS NOT NULL DEFAULT 'APPROVED',
+  ADD COLUMN submitted_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+  ADD COLUMN reviewed_by UUID REFERENCES app_user(id) ON DELETE SET NULL,
+  ADD COLUMN reviewed_at TIMESTAMPTZ,
+  ADD COLUMN rejection_reason VARCHAR(500);
+
+ALTER TABLE entry ALTER COLUMN status SET DEFAULT 'PENDING';
+
+CREATE INDEX entry_pending_idx
+ON entry (submitted_at)
+WHERE status = 'PENDING';
@@ -1,49 +1,24 @@
 use std::time::Duration;
 
-use include_dir::{File, include_dir};
 use sqlx::postgres::PgPoolOptions;
 
-use crate::_dev_utils::{Db, PG_DEV_APP_URL, PG_DEV_POSTGRES_URL};
+use crate::_dev_utils::{Db, PG_DEV_POSTGRES_URL};
 
-fn is_init_file(file: &File) -> bool {
-    file.path().file_name().unwrap_or_default() == "00-recreate-db.sql"
-}
+const RECREATE_DB_FILE: &str = "sql/dev_initial/00-recreate-db.sql";
 
-pub async fn init_dev_db() -> Db {
+/// Drops and recreates the empty dev database and its user.
+///
+/// The schema is created afterwards by the migrations, like in production.
+pub async fn init_dev_db() {
     tracing::info!("{:<12} -- init_dev_db()", "FOR-DEV-ONLY");
 
-    let migration_dir = include_dir!("$CARGO_MANIFEST_DIR/sql/dev_initial");
-    let sql_files = migration_dir.files();
-
-    let (mut init_sqls, mut app_sqls) = sql_files.partition::<Vec<_>, _>(|f| is_init_file(f));
-
-    {
-        let initial_db = new_db_pool(PG_DEV_POSTGRES_URL).await;
-        init_sqls.sort_by_key(|f| f.path());
-        for init_file in init_sqls {
-            execute_sql(
-                &initial_db,
-                init_file
-                    .contents_utf8()
-                    .expect("Invalid UTF-8 in SQL file"),
-                init_file.path().to_str().unwrap_or_default(),
-            )
-            .await;
-        }
-    }
-
-    let app_db = new_db_pool(PG_DEV_APP_URL).await;
-    app_sqls.sort_by_key(|f| f.path());
-    for app_file in app_sqls {
-        execute_sql(
-            &app_db,
-            app_file.contents_utf8().expect("Invalid UTF-8 in SQL file"),
-            app_file.path().to_str().unwrap_or_default(),
-        )
-        .await;
-    }
-
-    app_db
+    let initial_db = new_db_pool(PG_DEV_POSTGRES_URL).await;
+    execute_sql(
+        &initial_db,
+        include_str!("../../sql/dev_initial/00-recreate-db.sql"),
+        RECREATE_DB_FILE,
+    )
+    .await;
 }
 
 async fn new_db_pool(url: &str) -> Db {
@@ -154,7 +129,7 @@ fn split_sql_with_blocks(sql: &str) -> Vec<String> {
 /// # async fn doc() -> Result<(), Box<dyn std::error::Error>> {
 /// let pool = /* obtain a sqlx::Pool<Postgres> */ unimplemented!();
 /// let sql = "CREATE TABLE t(id SERIAL PRIMARY KEY); INSERT INTO t DEFAULT VALUES;";
-/// execute_sql(&pool, sql, "sql/dev_initial/00-init.sql").await;
+/// execute_sql(&pool, sql, "sql/dev_initial/00-recreate-db.sql").await;
 /// # Ok(()) }
 /// ```
 async fn execute_sql(pool: &Db, sql: &str, file_path: &str) {
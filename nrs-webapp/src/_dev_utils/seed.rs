@@ -1,26 +1,24 @@
 use std::sync::Mutex;
 
 use nrs_webapp_core::{data::entry::types::idtype::EntryType, legacy_json::Bulk};
+use sqlx::types::Json;
 use uuid::Uuid;
 
 use crate::{
     crypt::password_hash::PasswordHasher,
     model::{
         ModelManager,
-        entry::{EntryBmc, EntryForCreate},
+        audit_event::AuditContext,
+        entry::{EntryBmc, EntryForCreate, EntryStatus},
         user::{UserBmc, UserForCreate},
     },
 };
 
-pub async fn seed_dev_db() {
+pub async fn seed_dev_db(mm: &mut ModelManager) {
     tracing::info!("{:<12} -- seed_dev_db()", "FOR-DEV-ONLY");
 
-    let mut mm = ModelManager::new()
-        .await
-        .expect("Failed to create ModelManager");
-
-    let _ = create_test_user(&mut mm).await;
-    seed_entries(&mut mm).await;
+    let _ = create_test_user(mm).await;
+    seed_entries(mm).await;
 }
 
 static TEST_USER_ID: Mutex<Option<Uuid>> = Mutex::new(None);
@@ -60,6 +58,7 @@ async fn create_test_user(mm: &mut ModelManager) -> Uuid {
 
     let id = UserBmc::create_user(
         mm,
+        &AuditContext::default(),
         UserForCreate {
             username,
             email,
@@ -69,7 +68,7 @@ async fn create_test_user(mm: &mut ModelManager) -> Uuid {
     .await
     .expect("Unable to create test user");
 
-    UserBmc::mark_email_verified(mm, id)
+    UserBmc::mark_email_verified(mm, &AuditContext::default(), id)
         .await
         .expect("Unable to verify test user email");
 
@@ -109,17 +108,18 @@ async fn seed_entries(mm: &mut ModelManager) {
             .and_then(|v| v.as_str())
             .and_then(EntryType::from_enum_string)
             .unwrap_or_default(),
+        entry_info: Json(serde_json::Value::Object(Default::default())),
         added_by: test_user_id(),
+        status: EntryStatus::Approved,
         id,
     });
 
-    // inserting via transaction for performance (on my setup: 5-6s to sub-1)
+    // unlike the entries submitted through the app, the seeded ones have no audit event: only
+    // their initial revision is recorded
     let mut tx = mm.tx().await.expect("Unable to start transaction");
-    for create_req in create_reqs {
-        EntryBmc::create_entry(&mut tx, create_req)
-            .await
-            .expect("Unable to create entry");
-    }
+    EntryBmc::create_entries(&mut tx, create_reqs.collect())
+        .await
+        .expect("Unable to create entries");
     tx.commit().await.expect("Unable to commit transaction");
 
     tracing::info!(
@@ -0,0 +1,63 @@
+//! Bootstrapping of the first administrator.
+//!
+//! Roles can only be granted by an admin, so the first one is taken from
+//! `SERVICE_BOOTSTRAP_ADMIN_USERNAME` when the service starts without any.
+
+use sqlbindable::{FieldNames, Fields};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::{
+    Result,
+    config::AppConfig,
+    model::{
+        ModelManager,
+        audit_event::AuditContext,
+        user::UserBmc,
+        user_role::{Role, UserRoleBmc},
+    },
+};
+
+#[derive(FromRow, FieldNames, Fields)]
+struct UserId {
+    id: Uuid,
+}
+
+/// Grant the admin role to the user named by `SERVICE_BOOTSTRAP_ADMIN_USERNAME`, unless it is
+/// unset or some user already has the role.
+///
+/// A user that has not registered yet is only reported, so that the role is granted on a later
+/// startup.
+pub async fn bootstrap_admin(mm: &ModelManager) -> Result<()> {
+    let Some(username) = AppConfig::get().SERVICE_BOOTSTRAP_ADMIN_USERNAME.as_deref() else {
+        return Ok(());
+    };
+
+    let mut tx = mm.tx().await?;
+    if UserRoleBmc::any_with_role(&mut tx, Role::Admin).await? {
+        return Ok(());
+    }
+
+    match UserBmc::get_by_username::<UserId>(&mut tx, username).await? {
+        Some(UserId { id }) => {
+            // another instance starting at the same time may have granted it first
+            let granted =
+                UserRoleBmc::grant(&mut tx, &AuditContext::default(), id, Role::Admin).await?;
+            tx.commit().await?;
+            if !granted {
+                return Ok(());
+            }
+            tracing::info!(
+                "{:<12} -- Granted the admin role to {}",
+                "BOOTSTRAP",
+                username
+            );
+        }
+        None => tracing::warn!(
+            "{:<12} -- No user {} to grant the admin role to",
+            "BOOTSTRAP",
+            username
+        ),
+    }
+    Ok(())
+}
@@ -1,12 +1,24 @@
 use thiserror::Error;
 
-use crate::auth::external;
+use crate::{auth::external, model::user_role::Role};
 
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("Login error: {0}")]
     Login(LoginError),
 
+    #[error("This action requires a logged in user")]
+    NotLoggedIn,
+
+    #[error("This action requires the {0:?} role")]
+    MissingRole(Role),
+
+    #[error("The logged in user is not allowed to perform this action")]
+    NotAllowed,
+
+    #[error("Cannot remove the last login method of an account")]
+    LastLoginMethod,
+
     #[error("Error from external authentication provider: {0}")]
     ExternalAuth(#[from] external::Error),
 }
@@ -15,6 +27,12 @@ pub enum Error {
 pub enum LoginError {
     #[error("Invalid credentials provided")]
     InvalidCredentials,
+
+    #[error("Account is locked after too many failed logins")]
+    AccountLocked,
+
+    #[error("Account is disabled")]
+    AccountDisabled,
 }
 
 pub type Result<T> = core::result::Result<T, Error>;
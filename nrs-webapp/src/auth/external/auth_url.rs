@@ -9,12 +9,16 @@ use openidconnect::{
 };
 use serde::{Deserialize, Serialize};
 use url::Url;
+use uuid::Uuid;
 
 #[derive(Serialize, Deserialize)]
 pub struct AuthFlowState {
     pub csrf_state: Option<CsrfToken>,
     pub nonce: Option<Nonce>,
     pub pkce_verifier: Option<PkceCodeVerifier>,
+    // set when a logged in user links a new provider to their account, instead of logging in
+    #[serde(default)]
+    pub link_user_id: Option<Uuid>,
 }
 
 pub struct AuthorizeUrl {
@@ -31,8 +35,8 @@ pub trait BaseAuthorizeUrlGenerator {
 
     fn as_client(&self) -> &Self::Client;
 
-    fn scopes(&self) -> &'static [&'static str] {
-        &[]
+    fn scopes(&self) -> Vec<&str> {
+        Vec::new()
     }
 
     fn create_csrf_token() -> CsrfToken {
@@ -139,7 +143,7 @@ where
             };
 
         for scope in inner.scopes() {
-            req = req.add_scope(Scope::new(scope.to_string()));
+            req = req.add_scope(Scope::new(scope.to_owned()));
         }
 
         let (authorize_url, csrf_state) = req.url();
@@ -150,6 +154,7 @@ where
                 csrf_state: Some(csrf_state),
                 nonce: None,
                 pkce_verifier,
+                link_user_id: None,
             },
         })
     }
@@ -235,7 +240,7 @@ where
             };
 
         for scope in inner.scopes() {
-            req = req.add_scope(Scope::new(scope.to_string()));
+            req = req.add_scope(Scope::new(scope.to_owned()));
         }
 
         let (authorize_url, csrf_state, nonce) = req.url();
@@ -246,6 +251,7 @@ where
                 csrf_state: Some(csrf_state),
                 nonce: Some(nonce),
                 pkce_verifier,
+                link_user_id: None,
             },
         })
     }
@@ -1,4 +1,7 @@
-use oauth2::{ConfigurationError, basic::BasicRequestTokenError};
+use oauth2::{
+    ConfigurationError, RequestTokenError, RevocationErrorResponseType, StandardErrorResponse,
+    basic::BasicRequestTokenError,
+};
 use openidconnect::{ClaimsVerificationError, DiscoveryError};
 use thiserror::Error;
 
@@ -28,14 +31,34 @@ pub enum Error {
     #[error("Mismatched CSRF state in OAuth2 flow")]
     CsrfStateMismatch,
 
+    #[error("Account link flow was started by a different user")]
+    LinkSessionMismatch,
+
+    // 409 - conflicts
+    #[error("This provider account is already linked to another user")]
+    IdentityAlreadyLinked,
+
+    #[error("A {0} account is already linked to this user")]
+    ProviderAlreadyLinked(String),
+
+    // 401 - the user has to link the account again
+    #[error("The {0} access token expired and cannot be refreshed")]
+    AccessTokenExpired(String),
+
     // 404 - not found
     #[error("OAuth2/OIDC provider not found: {0}")]
     ProviderNotFound(String),
 
+    #[error("No active {0} account is linked to this user")]
+    ProviderNotLinked(String),
+
     // 500 - configuration/internal errors
     #[error("OAuth2 configuration error: {0}")]
     OAuth2InvalidConfiguration(#[from] ConfigurationError),
 
+    #[error("Provider {0} does not support refreshing access tokens")]
+    TokenRefreshUnsupported(String),
+
     #[error("JSON serialization/deserialization error: {0}")]
     SerdeJson(#[from] serde_json::Error),
 
@@ -51,6 +74,15 @@ pub enum Error {
 
     #[error("OAuth2 token exchange error: {0}")]
     TokenExchange(#[from] BasicRequestTokenError<OAuth2HttpClientError>),
+
+    #[error("OAuth2 token revocation error: {0}")]
+    TokenRevocation(
+        #[from]
+        RequestTokenError<
+            OAuth2HttpClientError,
+            StandardErrorResponse<RevocationErrorResponseType>,
+        >,
+    ),
 }
 
 pub type Result<T> = core::result::Result<T, Error>;
@@ -46,11 +46,29 @@ pub trait CodeExchanger {
         pkce_verifier: Option<oauth2::PkceCodeVerifier>,
     ) -> Result<(TokenResponse, IdToken)>;
 
+    async fn refresh_token(
+        &self,
+        http_client: &HttpClientWrapper,
+        refresh_token: &RefreshToken,
+    ) -> Result<TokenResponse>;
+
     fn get_id_token(&self, _token_resp: Self::TR) -> Result<IdToken> {
         Ok(IdToken::default())
     }
 }
 
+impl TokenResponse {
+    fn from_oauth2(token_response: &impl oauth2::TokenResponse) -> Self {
+        Self {
+            access_token: token_response.access_token().clone(),
+            refresh_token: token_response.refresh_token().cloned(),
+            expires_at: token_response
+                .expires_in()
+                .map(|dur| OffsetDateTime::now_utc() + dur),
+        }
+    }
+}
+
 pub trait BaseCodeExchanger {
     type Client;
 
@@ -82,6 +100,18 @@ pub(super) trait OAuthCodeExchangerTrait {
             .exchange_code(http_client, code, pkce_verifier)
             .await
     }
+
+    async fn refresh_token_oauth<'a>(
+        &'a self,
+        http_client: &HttpClientWrapper,
+        refresh_token: &RefreshToken,
+    ) -> Result<TokenResponse>
+    where
+        OAuthBaseCodeExchanger<'a, Self>: CodeExchanger,
+        Self: Sized,
+    {
+        self.oauth().refresh_token(http_client, refresh_token).await
+    }
 }
 pub(super) trait OidcCodeExchangerTrait {
     fn oidc(&self) -> OidcBaseCodeExchanger<'_, Self>
@@ -105,6 +135,18 @@ pub(super) trait OidcCodeExchangerTrait {
             .exchange_code(http_client, code, pkce_verifier)
             .await
     }
+
+    async fn refresh_token_oidc<'a>(
+        &'a self,
+        http_client: &HttpClientWrapper,
+        refresh_token: &RefreshToken,
+    ) -> Result<TokenResponse>
+    where
+        OidcBaseCodeExchanger<'a, Self>: CodeExchanger,
+        Self: Sized,
+    {
+        self.oidc().refresh_token(http_client, refresh_token).await
+    }
 }
 impl<G> OAuthCodeExchangerTrait for G {}
 impl<G> OidcCodeExchangerTrait for G {}
@@ -166,17 +208,24 @@ where
         }
 
         let token_response = req.request_async(http_client).await?;
-
-        let tokens = TokenResponse {
-            access_token: token_response.access_token().clone(),
-            refresh_token: token_response.refresh_token().cloned(),
-            expires_at: token_response
-                .expires_in()
-                .map(|dur| OffsetDateTime::now_utc() + dur),
-        };
+        let tokens = TokenResponse::from_oauth2(&token_response);
 
         Ok((tokens, self.get_id_token(token_response)?))
     }
+
+    async fn refresh_token(
+        &self,
+        http_client: &HttpClientWrapper,
+        refresh_token: &RefreshToken,
+    ) -> Result<TokenResponse> {
+        let token_response = self
+            .0
+            .as_client()
+            .exchange_refresh_token(refresh_token)?
+            .request_async(http_client)
+            .await?;
+        Ok(TokenResponse::from_oauth2(&token_response))
+    }
 }
 
 #[async_trait]
@@ -262,18 +311,25 @@ where
         }
 
         let token_response = req.request_async(http_client).await?;
-
-        let tokens = TokenResponse {
-            access_token: token_response.access_token().clone(),
-            refresh_token: token_response.refresh_token().cloned(),
-            expires_at: token_response
-                .expires_in()
-                .map(|dur| OffsetDateTime::now_utc() + dur),
-        };
+        let tokens = TokenResponse::from_oauth2(&token_response);
 
         Ok((tokens, self.get_id_token(token_response)?))
     }
 
+    async fn refresh_token(
+        &self,
+        http_client: &HttpClientWrapper,
+        refresh_token: &RefreshToken,
+    ) -> Result<TokenResponse> {
+        let token_response = self
+            .0
+            .as_client()
+            .exchange_refresh_token(refresh_token)?
+            .request_async(http_client)
+            .await?;
+        Ok(TokenResponse::from_oauth2(&token_response))
+    }
+
     fn get_id_token(&self, token_resp: Self::TR) -> Result<IdToken> {
         Ok(token_resp
             .id_token()
@@ -282,3 +338,27 @@ where
             .unwrap_or_default())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use oauth2::{
+        EmptyExtraTokenFields, StandardTokenResponse,
+        basic::{BasicTokenResponse, BasicTokenType},
+    };
+
+    use super::*;
+
+    #[test]
+    fn refresh_response_without_expires_in_has_no_expiry() {
+        let resp: BasicTokenResponse = StandardTokenResponse::new(
+            AccessToken::new("refreshed".into()),
+            BasicTokenType::Bearer,
+            EmptyExtraTokenFields {},
+        );
+        let tokens = TokenResponse::from_oauth2(&resp);
+
+        assert_eq!(tokens.access_token.secret(), "refreshed");
+        assert!(tokens.refresh_token.is_none());
+        assert!(tokens.expires_at.is_none());
+    }
+}
@@ -1,7 +1,7 @@
 use std::{collections::HashMap, sync::Arc};
 
 use async_trait::async_trait;
-use oauth2::{AccessToken, PkceCodeVerifier};
+use oauth2::{AccessToken, PkceCodeVerifier, RefreshToken, StandardRevocableToken};
 use openidconnect::Nonce;
 use url::Url;
 
@@ -10,7 +10,7 @@ use crate::{
         auth_url::AuthorizeUrl,
         exch_code::{IdToken, TokenResponse},
     },
-    model::ModelManager,
+    model::{ModelManager, oauth_links::OAuthLink},
 };
 
 pub mod auth_url;
@@ -19,6 +19,7 @@ pub mod exch_code;
 pub mod oidc_discover;
 pub mod oidc_fetch_identity;
 mod providers;
+pub mod token_manager;
 
 pub use error::{Error, Result};
 
@@ -26,14 +27,19 @@ pub use error::{Error, Result};
 pub struct UserIdentity {
     pub id: String,
     pub username: Option<String>,
+    /// The address the provider considers the user's main one.
     pub email: Option<String>,
     pub email_verified: bool,
+    /// Every verified address of the user, the preferred one first.
+    pub verified_emails: Vec<String>,
     pub profile_picture: Option<Url>,
 }
 
 #[async_trait]
 pub trait AuthProvider: Send + Sync {
-    fn name(&self) -> &'static str;
+    fn name(&self) -> &str;
+
+    fn display_name(&self) -> &str;
 
     async fn authorize_url(&self, mm: &ModelManager, redirect_uri: Url) -> Result<AuthorizeUrl>;
 
@@ -53,10 +59,31 @@ pub trait AuthProvider: Send + Sync {
         access_token: &AccessToken,
         redirect_uri: Url,
     ) -> Result<UserIdentity>;
+
+    /// Exchanges `refresh_token` for a new access token.
+    ///
+    /// The returned `TokenResponse` has no refresh token when the provider keeps using the one
+    /// it issued before. The default implementation fails, for providers whose access tokens
+    /// never expire.
+    async fn refresh_token(
+        &self,
+        _mm: &ModelManager,
+        _refresh_token: &RefreshToken,
+    ) -> Result<TokenResponse> {
+        Err(Error::TokenRefreshUnsupported(self.name().to_string()))
+    }
+
+    /// Revokes `token` at the provider, for providers that expose a revocation endpoint.
+    ///
+    /// The default implementation does nothing, since unlinking an account must work even if
+    /// the provider offers no way to revoke the tokens we hold.
+    async fn revoke_token(&self, _mm: &ModelManager, _token: StandardRevocableToken) -> Result<()> {
+        Ok(())
+    }
 }
 
 #[derive(Default, Clone)]
-pub struct AuthProviderRegistry(Arc<HashMap<&'static str, Box<dyn AuthProvider>>>);
+pub struct AuthProviderRegistry(Arc<HashMap<String, Box<dyn AuthProvider>>>);
 
 impl AuthProviderRegistry {
     pub fn new() -> Self {
@@ -64,13 +91,21 @@ impl AuthProviderRegistry {
     }
 
     pub fn from_config() -> Self {
-        let mut registry: HashMap<&'static str, Box<dyn AuthProvider>> = HashMap::new();
+        let mut registry: HashMap<String, Box<dyn AuthProvider>> = HashMap::new();
 
-        for p in [providers::google(), providers::github()]
+        let builtin = [providers::google(), providers::github()]
             .into_iter()
-            .flatten()
-        {
-            registry.insert(p.name(), p);
+            .flatten();
+        for p in builtin.chain(providers::oidc()) {
+            if registry.contains_key(p.name()) {
+                tracing::warn!(
+                    "{:<12} -- Skipping duplicate auth provider {}",
+                    "OAUTH",
+                    p.name()
+                );
+                continue;
+            }
+            registry.insert(p.name().to_string(), p);
         }
 
         Self(Arc::new(registry))
@@ -83,4 +118,51 @@ impl AuthProviderRegistry {
     pub fn has_provider(&self, name: &str) -> bool {
         self.0.contains_key(name)
     }
+
+    /// Iterates over the registered providers, sorted by name so that pages listing them are
+    /// rendered in a stable order.
+    pub fn providers(&self) -> impl Iterator<Item = &dyn AuthProvider> {
+        let mut providers: Vec<_> = self.0.values().map(|b| b.as_ref()).collect();
+        providers.sort_by_key(|p| p.name());
+        providers.into_iter()
+    }
+}
+
+/// Revokes the tokens stored for `link` at its provider, on a best-effort basis.
+///
+/// The refresh token is preferred since revoking it also invalidates the access tokens issued
+/// from it. Failures are only logged: by the time this runs the link is already gone on our side,
+/// and the provider may have revoked the grant on its own.
+pub async fn revoke_link_tokens(mm: &ModelManager, link: &OAuthLink) {
+    let Some(provider) = mm.auth_providers().get(&link.provider) else {
+        tracing::warn!(
+            "{:<12} -- Cannot revoke tokens of unknown provider {}",
+            "OAUTH",
+            link.provider
+        );
+        return;
+    };
+
+    let decrypt = |token: &[u8]| token_manager::decrypt_token(token).ok();
+    let token = match (&link.refresh_token, &link.access_token) {
+        (Some(refresh_token), _) => decrypt(refresh_token)
+            .map(|t| StandardRevocableToken::RefreshToken(RefreshToken::new(t))),
+        (None, Some(access_token)) => {
+            decrypt(access_token).map(|t| StandardRevocableToken::AccessToken(AccessToken::new(t)))
+        }
+        (None, None) => None,
+    };
+
+    let Some(token) = token else {
+        return;
+    };
+
+    if let Err(err) = provider.revoke_token(mm, token).await {
+        tracing::warn!(
+            "{:<12} -- Failed to revoke {} tokens: {}",
+            "OAUTH",
+            link.provider,
+            err
+        );
+    }
 }
@@ -9,7 +9,7 @@ use crate::model::ModelManager;
 
 pub async fn oidc_discover<A, AD, CA, CN, CT, G, JE, JK, K, RM, RT, S>(
     mm: &ModelManager,
-    issuer_url: &'static str,
+    issuer_url: &str,
 ) -> Result<ProviderMetadata<A, AD, CA, CN, CT, G, JE, JK, K, RM, RT, S>>
 where
     A: AdditionalProviderMetadata,
@@ -27,7 +27,7 @@ where
     RT: ResponseType,
     S: SubjectIdentifierType,
 {
-    let issuer_url = IssuerUrl::new(issuer_url.into()).expect("valid issuer URL");
+    let issuer_url = IssuerUrl::new(issuer_url.into())?;
     let provider_metadata =
         ProviderMetadata::discover_async(issuer_url, mm.http_client_wrapper()).await?;
     Ok(provider_metadata)
@@ -142,11 +142,14 @@ where
             .ok_or_else(|| Error::InvalidIdTokenType)?;
 
         let claims = id_token.claims(&verifier, &nonce.ok_or(Error::NonceMissing)?)?;
+        let email = claims.email().map(|e| e.to_string());
+        let email_verified = claims.email_verified().unwrap_or(false);
         Ok(UserIdentity {
             id: claims.subject().to_string(),
             username: claims.preferred_username().map(|u| u.to_string()),
-            email: claims.email().map(|e| e.to_string()),
-            email_verified: claims.email_verified().unwrap_or(false),
+            verified_emails: email.iter().filter(|_| email_verified).cloned().collect(),
+            email,
+            email_verified,
             profile_picture: claims.picture().and_then(|urls| {
                 urls.iter()
                     .find_map(|(_, url)| Url::parse(url.as_str()).ok())
@@ -10,8 +10,8 @@ use async_trait::async_trait;
 use oauth2::basic::{BasicClient, BasicErrorResponseType, BasicTokenType};
 use oauth2::{
     AccessToken, AuthUrl, Client, EmptyExtraTokenFields, EndpointMaybeSet, EndpointNotSet,
-    PkceCodeVerifier, RevocationErrorResponseType, StandardErrorResponse, StandardRevocableToken,
-    StandardTokenIntrospectionResponse, StandardTokenResponse, TokenUrl,
+    PkceCodeVerifier, RefreshToken, RevocationErrorResponseType, StandardErrorResponse,
+    StandardRevocableToken, StandardTokenIntrospectionResponse, StandardTokenResponse, TokenUrl,
 };
 use openidconnect::{ClientId, ClientSecret, Nonce, RedirectUrl};
 use reqwest_middleware::ClientWithMiddleware;
@@ -41,8 +41,8 @@ impl BaseAuthorizeUrlGenerator for GithubCoreClientWrapper {
         &self.0
     }
 
-    fn scopes(&self) -> &'static [&'static str] {
-        &["user:email", "read:user"]
+    fn scopes(&self) -> Vec<&str> {
+        vec!["user:email", "read:user"]
     }
 }
 
@@ -103,10 +103,14 @@ impl IdentityFetcher for GithubCoreClientWrapper {
         )
         .await?;
 
-        // TODO: better email selection logic?
-        // TODO: allow the user to select which email?
+        // unverified addresses are never offered to the user: anyone can add any address to
+        // their GitHub account
+        let mut verified_emails: Vec<_> = emails.iter().filter(|e| e.verified).collect();
+        // the primary address first, then in the order GitHub returned them
+        verified_emails.sort_by_key(|e| !e.primary);
+
         let email = emails
-            .into_iter()
+            .iter()
             .enumerate()
             .min_by_key(|(idx, e)| {
                 (
@@ -115,13 +119,17 @@ impl IdentityFetcher for GithubCoreClientWrapper {
                     *idx,        // then first in list
                 )
             })
-            .map(|(_, e)| (e.email, e.verified));
+            .map(|(_, e)| (e.email.clone(), e.verified));
 
         Ok(UserIdentity {
             id: user.id.to_string(),
             username: Some(user.login),
             email_verified: email.as_ref().map(|(_, v)| *v).unwrap_or_default(),
             email: email.map(|(e, _)| e),
+            verified_emails: verified_emails
+                .into_iter()
+                .map(|e| e.email.clone())
+                .collect(),
             profile_picture: Some(Url::parse(&user.avatar_url)?),
         })
     }
@@ -150,28 +158,34 @@ impl GithubAuthProvider {
         ))
     }
 
-    fn create_client(&self, redirect_uri: Url) -> Result<GithubCoreClientWrapper> {
-        let client = BasicClient::new(ClientId::new(self.client_id.clone()))
+    fn create_client(&self, redirect_uri: Option<Url>) -> Result<GithubCoreClientWrapper> {
+        let mut client = BasicClient::new(ClientId::new(self.client_id.clone()))
             .set_client_secret(ClientSecret::new(self.client_secret.clone()))
             .set_auth_uri_option(Some(AuthUrl::new(
                 "https://github.com/login/oauth/authorize".into(),
             )?))
             .set_token_uri_option(Some(TokenUrl::new(
                 "https://github.com/login/oauth/access_token".into(),
-            )?))
-            .set_redirect_uri(RedirectUrl::from_url(redirect_uri));
+            )?));
+        if let Some(redirect_uri) = redirect_uri {
+            client = client.set_redirect_uri(RedirectUrl::from_url(redirect_uri));
+        }
         Ok(GithubCoreClientWrapper(client))
     }
 }
 
 #[async_trait]
 impl AuthProvider for GithubAuthProvider {
-    fn name(&self) -> &'static str {
+    fn name(&self) -> &str {
         "github"
     }
 
+    fn display_name(&self) -> &str {
+        "GitHub"
+    }
+
     async fn authorize_url(&self, _mm: &ModelManager, redirect_uri: Url) -> Result<AuthorizeUrl> {
-        self.create_client(redirect_uri)?
+        self.create_client(Some(redirect_uri))?
             .create_authorize_url_oauth()
     }
 
@@ -182,7 +196,7 @@ impl AuthProvider for GithubAuthProvider {
         redirect_uri: Url,
         pkce_verifier: Option<PkceCodeVerifier>,
     ) -> Result<(TokenResponse, IdToken)> {
-        self.create_client(redirect_uri)?
+        self.create_client(Some(redirect_uri))?
             .exchange_code_oauth(mm.http_client_wrapper(), code, pkce_verifier)
             .await
     }
@@ -195,8 +209,44 @@ impl AuthProvider for GithubAuthProvider {
         access_token: &AccessToken,
         redirect_uri: Url,
     ) -> Result<UserIdentity> {
-        self.create_client(redirect_uri)?
+        self.create_client(Some(redirect_uri))?
             .fetch_identity(mm.http_client_wrapper(), &id_token, access_token, nonce)
             .await
     }
+
+    // only GitHub Apps with expiring user tokens issue refresh tokens, OAuth app tokens never
+    // expire and are never refreshed
+    async fn refresh_token(
+        &self,
+        mm: &ModelManager,
+        refresh_token: &RefreshToken,
+    ) -> Result<TokenResponse> {
+        self.create_client(None)?
+            .refresh_token_oauth(mm.http_client_wrapper(), refresh_token)
+            .await
+    }
+
+    // GitHub has no RFC 7009 revocation endpoint, but deleting the app authorization revokes
+    // every token it issued to us for this user
+    // see: https://docs.github.com/en/rest/apps/oauth-applications#delete-an-app-authorization
+    async fn revoke_token(&self, mm: &ModelManager, token: StandardRevocableToken) -> Result<()> {
+        let StandardRevocableToken::AccessToken(access_token) = token else {
+            // GitHub OAuth apps do not issue refresh tokens
+            return Ok(());
+        };
+
+        mm.http_client()
+            .delete(format!(
+                "https://api.github.com/applications/{}/grant",
+                self.client_id
+            ))
+            .header("Accept", "application/vnd.github+json")
+            .header("User-Agent", "nrs-webapp")
+            .basic_auth(&self.client_id, Some(&self.client_secret))
+            .json(&serde_json::json!({ "access_token": access_token.secret() }))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
 }
@@ -11,8 +11,8 @@ use async_trait::async_trait;
 use oauth2::basic::{BasicErrorResponseType, BasicTokenType};
 use oauth2::{
     AccessToken, EmptyExtraTokenFields, EndpointMaybeSet, EndpointNotSet, EndpointSet,
-    PkceCodeVerifier, RevocationErrorResponseType, StandardErrorResponse, StandardRevocableToken,
-    StandardTokenIntrospectionResponse, StandardTokenResponse,
+    PkceCodeVerifier, RefreshToken, RevocationErrorResponseType, StandardErrorResponse,
+    StandardRevocableToken, StandardTokenIntrospectionResponse, StandardTokenResponse,
 };
 use openidconnect::DiscoveryError;
 use openidconnect::core::{
@@ -87,8 +87,8 @@ impl BaseAuthorizeUrlGenerator for GoogleCoreClientWrapper {
         &self.0
     }
 
-    fn scopes(&self) -> &'static [&'static str] {
-        &["email", "profile"]
+    fn scopes(&self) -> Vec<&str> {
+        vec!["email", "profile"]
     }
 }
 
@@ -134,7 +134,7 @@ impl GoogleAuthProvider {
     async fn client(
         &self,
         mm: &ModelManager,
-        redirect_uri: Url,
+        redirect_uri: Option<Url>,
     ) -> Result<GoogleCoreClientWrapper> {
         let provider_metadata: GoogleProviderMetadata =
             oidc_discover(mm, "https://accounts.google.com").await?;
@@ -142,27 +142,35 @@ impl GoogleAuthProvider {
             .additional_metadata()
             .revocation_endpoint
             .clone();
-        let client = CoreClient::from_provider_metadata(
+        let mut client = CoreClient::from_provider_metadata(
             provider_metadata,
             ClientId::new(self.client_id.clone()),
             Some(ClientSecret::new(self.client_secret.clone())),
         )
-        .set_redirect_uri(RedirectUrl::from_url(redirect_uri))
         .set_revocation_url(
             RevocationUrl::new(revocation_endpoint).map_err(DiscoveryError::UrlParse)?,
         );
+        // revocation requests are not part of the authorization code flow, so they do not
+        // need a redirect URI
+        if let Some(redirect_uri) = redirect_uri {
+            client = client.set_redirect_uri(RedirectUrl::from_url(redirect_uri));
+        }
         Ok(GoogleCoreClientWrapper(client))
     }
 }
 
 #[async_trait]
 impl AuthProvider for GoogleAuthProvider {
-    fn name(&self) -> &'static str {
+    fn name(&self) -> &str {
         "google"
     }
 
+    fn display_name(&self) -> &str {
+        "Google"
+    }
+
     async fn authorize_url(&self, mm: &ModelManager, redirect_uri: Url) -> Result<AuthorizeUrl> {
-        self.client(mm, redirect_uri)
+        self.client(mm, Some(redirect_uri))
             .await?
             .create_authorize_url_oidc()
     }
@@ -174,7 +182,7 @@ impl AuthProvider for GoogleAuthProvider {
         redirect_uri: Url,
         pkce_verifier: Option<PkceCodeVerifier>,
     ) -> Result<(TokenResponse, IdToken)> {
-        self.client(mm, redirect_uri)
+        self.client(mm, Some(redirect_uri))
             .await?
             .exchange_code_oidc(mm.http_client_wrapper(), code, pkce_verifier)
             .await
@@ -188,9 +196,30 @@ impl AuthProvider for GoogleAuthProvider {
         access_token: &AccessToken,
         redirect_uri: Url,
     ) -> Result<UserIdentity> {
-        self.client(mm, redirect_uri)
+        self.client(mm, Some(redirect_uri))
             .await?
             .fetch_identity_oidc(mm.http_client_wrapper(), &id_token, access_token, nonce)
             .await
     }
+
+    async fn refresh_token(
+        &self,
+        mm: &ModelManager,
+        refresh_token: &RefreshToken,
+    ) -> Result<TokenResponse> {
+        self.client(mm, None)
+            .await?
+            .refresh_token_oidc(mm.http_client_wrapper(), refresh_token)
+            .await
+    }
+
+    async fn revoke_token(&self, mm: &ModelManager, token: StandardRevocableToken) -> Result<()> {
+        self.client(mm, None)
+            .await?
+            .0
+            .revoke_token(token)?
+            .request_async(mm.http_client_wrapper())
+            .await?;
+        Ok(())
+    }
 }
@@ -2,6 +2,7 @@ use crate::auth::external::AuthProvider;
 
 mod github;
 mod google;
+mod oidc;
 
 pub fn google() -> Option<Box<dyn AuthProvider>> {
     google::GoogleAuthProvider::from_config().map(|p| Box::new(p) as Box<dyn AuthProvider>)
@@ -10,3 +11,7 @@ pub fn google() -> Option<Box<dyn AuthProvider>> {
 pub fn github() -> Option<Box<dyn AuthProvider>> {
     github::GithubAuthProvider::from_config().map(|p| Box::new(p) as Box<dyn AuthProvider>)
 }
+
+pub fn oidc() -> impl Iterator<Item = Box<dyn AuthProvider>> {
+    oidc::OidcAuthProvider::all_from_config().map(|p| Box::new(p) as Box<dyn AuthProvider>)
+}
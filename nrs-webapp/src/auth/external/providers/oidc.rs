@@ -0,0 +1,214 @@
+use crate::auth::external::Result;
+use crate::auth::external::auth_url::{
+    AuthorizeUrl, BaseAuthorizeUrlGenerator, OidcAuthorizeUrlGeneratorTrait,
+};
+use crate::auth::external::exch_code::{BaseCodeExchanger, OidcCodeExchangerTrait};
+use crate::auth::external::oidc_discover::oidc_discover;
+use crate::auth::external::oidc_fetch_identity::{BaseIdentityFetcher, OidcIdentityFetcherTrait};
+use crate::auth::external::{AuthProvider, IdToken, TokenResponse, UserIdentity};
+use crate::config::OidcProviderConfig;
+use crate::model::ModelManager;
+use async_trait::async_trait;
+use oauth2::{
+    AccessToken, EndpointMaybeSet, EndpointNotSet, EndpointSet, PkceCodeVerifier, RefreshToken,
+    StandardRevocableToken,
+};
+
+use openidconnect::DiscoveryError;
+use openidconnect::core::{
+    CoreAuthDisplay, CoreClaimName, CoreClaimType, CoreClient, CoreClientAuthMethod, CoreGrantType,
+    CoreJsonWebKey, CoreJweContentEncryptionAlgorithm, CoreJweKeyManagementAlgorithm,
+    CoreResponseMode, CoreResponseType, CoreSubjectIdentifierType,
+};
+use openidconnect::{
+    AdditionalProviderMetadata, ClientId, ClientSecret, Nonce, ProviderMetadata, RedirectUrl,
+    RevocationUrl,
+};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+// unlike Google, most providers do not advertise a revocation endpoint in their discovery
+// document, so it is optional here
+#[derive(Clone, Debug, Deserialize, Serialize)]
+struct OptionalRevocationEndpointProviderMetadata {
+    #[serde(default)]
+    revocation_endpoint: Option<String>,
+}
+impl AdditionalProviderMetadata for OptionalRevocationEndpointProviderMetadata {}
+
+type OidcProviderMetadata = ProviderMetadata<
+    OptionalRevocationEndpointProviderMetadata,
+    CoreAuthDisplay,
+    CoreClientAuthMethod,
+    CoreClaimName,
+    CoreClaimType,
+    CoreGrantType,
+    CoreJweContentEncryptionAlgorithm,
+    CoreJweKeyManagementAlgorithm,
+    CoreJsonWebKey,
+    CoreResponseMode,
+    CoreResponseType,
+    CoreSubjectIdentifierType,
+>;
+
+type OidcCoreClient = CoreClient<
+    EndpointSet,
+    EndpointNotSet,
+    EndpointNotSet,
+    EndpointNotSet,
+    EndpointMaybeSet,
+    EndpointMaybeSet,
+>;
+
+struct OidcCoreClientWrapper<'a> {
+    client: OidcCoreClient,
+    scopes: &'a [String],
+}
+
+impl BaseAuthorizeUrlGenerator for OidcCoreClientWrapper<'_> {
+    type Client = OidcCoreClient;
+
+    fn as_client(&self) -> &Self::Client {
+        &self.client
+    }
+
+    fn scopes(&self) -> Vec<&str> {
+        self.scopes.iter().map(String::as_str).collect()
+    }
+}
+
+impl BaseCodeExchanger for OidcCoreClientWrapper<'_> {
+    type Client = OidcCoreClient;
+
+    fn as_client(&self) -> &Self::Client {
+        &self.client
+    }
+}
+
+impl BaseIdentityFetcher for OidcCoreClientWrapper<'_> {
+    type Client = OidcCoreClient;
+
+    fn as_client(&self) -> &Self::Client {
+        &self.client
+    }
+}
+
+/// An OpenID Connect provider defined entirely by an [`OidcProviderConfig`].
+pub struct OidcAuthProvider {
+    config: &'static OidcProviderConfig,
+}
+
+impl OidcAuthProvider {
+    pub fn new(config: &'static OidcProviderConfig) -> Self {
+        Self { config }
+    }
+
+    pub fn all_from_config() -> impl Iterator<Item = Self> {
+        crate::config::AppConfig::get()
+            .OIDC_PROVIDERS
+            .iter()
+            .map(Self::new)
+    }
+
+    async fn discover(&self, mm: &ModelManager) -> Result<OidcProviderMetadata> {
+        oidc_discover(mm, &self.config.issuer_url).await
+    }
+
+    fn client_from_metadata(&self, provider_metadata: OidcProviderMetadata) -> OidcCoreClient {
+        CoreClient::from_provider_metadata(
+            provider_metadata,
+            ClientId::new(self.config.client_id.clone()),
+            self.config.client_secret.clone().map(ClientSecret::new),
+        )
+    }
+
+    async fn client(
+        &self,
+        mm: &ModelManager,
+        redirect_uri: Option<Url>,
+    ) -> Result<OidcCoreClientWrapper<'_>> {
+        let mut client = self.client_from_metadata(self.discover(mm).await?);
+        if let Some(redirect_uri) = redirect_uri {
+            client = client.set_redirect_uri(RedirectUrl::from_url(redirect_uri));
+        }
+        Ok(OidcCoreClientWrapper {
+            client,
+            scopes: &self.config.scopes,
+        })
+    }
+}
+
+#[async_trait]
+impl AuthProvider for OidcAuthProvider {
+    fn name(&self) -> &str {
+        &self.config.name
+    }
+
+    fn display_name(&self) -> &str {
+        &self.config.display_name
+    }
+
+    async fn authorize_url(&self, mm: &ModelManager, redirect_uri: Url) -> Result<AuthorizeUrl> {
+        self.client(mm, Some(redirect_uri))
+            .await?
+            .create_authorize_url_oidc()
+    }
+
+    async fn exchange_code(
+        &self,
+        mm: &ModelManager,
+        code: String,
+        redirect_uri: Url,
+        pkce_verifier: Option<PkceCodeVerifier>,
+    ) -> Result<(TokenResponse, IdToken)> {
+        self.client(mm, Some(redirect_uri))
+            .await?
+            .exchange_code_oidc(mm.http_client_wrapper(), code, pkce_verifier)
+            .await
+    }
+
+    async fn fetch_identity(
+        &self,
+        mm: &ModelManager,
+        id_token: IdToken,
+        nonce: Option<Nonce>,
+        access_token: &AccessToken,
+        redirect_uri: Url,
+    ) -> Result<UserIdentity> {
+        self.client(mm, Some(redirect_uri))
+            .await?
+            .fetch_identity_oidc(mm.http_client_wrapper(), &id_token, access_token, nonce)
+            .await
+    }
+
+    async fn refresh_token(
+        &self,
+        mm: &ModelManager,
+        refresh_token: &RefreshToken,
+    ) -> Result<TokenResponse> {
+        self.client(mm, None)
+            .await?
+            .refresh_token_oidc(mm.http_client_wrapper(), refresh_token)
+            .await
+    }
+
+    async fn revoke_token(&self, mm: &ModelManager, token: StandardRevocableToken) -> Result<()> {
+        let provider_metadata = self.discover(mm).await?;
+        let Some(revocation_endpoint) = provider_metadata
+            .additional_metadata()
+            .revocation_endpoint
+            .clone()
+        else {
+            return Ok(());
+        };
+
+        self.client_from_metadata(provider_metadata)
+            .set_revocation_url(
+                RevocationUrl::new(revocation_endpoint).map_err(DiscoveryError::UrlParse)?,
+            )
+            .revoke_token(token)?
+            .request_async(mm.http_client_wrapper())
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,184 @@
+use oauth2::{AccessToken, RefreshToken};
+use reqwest::{IntoUrl, Method};
+use reqwest_middleware::RequestBuilder;
+use serde::de::DeserializeOwned;
+use time::{Duration, OffsetDateTime};
+use uuid::Uuid;
+
+use crate::{
+    Error, Result, auth,
+    auth::external::{self, exch_code::TokenResponse},
+    crypt::symmetric::SymmetricCipher,
+    model::{
+        ModelManager,
+        oauth_links::{OAuthLink, OAuthLinkBmc, OAuthLinkForUpdate},
+    },
+};
+
+// refresh a bit before the actual expiry, so that the token is still valid when the provider
+// receives the request made with it
+const EXPIRY_LEEWAY: Duration = Duration::seconds(60);
+
+/// Gives the rest of the app access to the provider APIs on behalf of a user, using the tokens
+/// stored in the user's OAuth links.
+///
+/// Access tokens are decrypted with [`SymmetricCipher`] and refreshed through the provider's
+/// token endpoint when they are about to expire. Refreshed tokens are stored back, encrypted.
+///
+/// # Examples
+///
+/// ```no_run
+/// let tokens = OAuthTokenManager::new(mm.clone());
+/// let user: serde_json::Value = tokens
+///     .get_json(user_id, "github", "https://api.github.com/user")
+///     .await?;
+/// ```
+#[derive(Clone)]
+pub struct OAuthTokenManager {
+    mm: ModelManager,
+}
+
+impl OAuthTokenManager {
+    pub fn new(mm: ModelManager) -> Self {
+        Self { mm }
+    }
+
+    /// Returns a valid access token for the link of `user_id` with `provider`, refreshing it
+    /// first if it has expired.
+    ///
+    /// # Errors
+    ///
+    /// - `ProviderNotLinked` when the user has no active link with the provider.
+    /// - `AccessTokenExpired` when the token expired and there is no refresh token, or the
+    ///   provider rejected it; the user has to link the account again.
+    pub async fn access_token(&self, user_id: Uuid, provider: &str) -> Result<AccessToken> {
+        let mut mm = self.mm.clone();
+        let link: OAuthLink = OAuthLinkBmc::get_active(&mut mm, user_id, provider)
+            .await?
+            .ok_or_else(|| external_error(external::Error::ProviderNotLinked(provider.into())))?;
+
+        let expired = link
+            .access_token_expires_at
+            .is_some_and(|expires_at| expires_at <= OffsetDateTime::now_utc() + EXPIRY_LEEWAY);
+        if let (Some(access_token), false) = (&link.access_token, expired) {
+            return Ok(AccessToken::new(decrypt_token(access_token)?));
+        }
+
+        let Some(stored_refresh_token) = &link.refresh_token else {
+            return Err(external_error(external::Error::AccessTokenExpired(
+                provider.into(),
+            )));
+        };
+        let refresh_token = RefreshToken::new(decrypt_token(stored_refresh_token)?);
+
+        let auth_provider = self.mm.auth_providers().get(provider).ok_or_else(|| {
+            external_error(external::Error::ProviderNotFound(provider.to_string()))
+        })?;
+
+        tracing::debug!(
+            "{:<12} -- Refreshing {} access token of user {}",
+            "OAUTH",
+            provider,
+            user_id
+        );
+        let tokens = auth_provider
+            .refresh_token(&self.mm, &refresh_token)
+            .await
+            .map_err(|err| match err {
+                external::Error::TokenExchange(err) => {
+                    tracing::warn!(
+                        "{:<12} -- Failed to refresh {} access token: {}",
+                        "OAUTH",
+                        provider,
+                        err
+                    );
+                    external_error(external::Error::AccessTokenExpired(provider.into()))
+                }
+                err => external_error(err),
+            })?;
+
+        // the link is not locked during the refresh, so the tokens are only stored if no
+        // concurrent request refreshed them first (providers may rotate refresh tokens on use)
+        let (access_token, refresh_token) = encrypt_tokens(&tokens)?;
+        let stored = OAuthLinkBmc::update_tokens(
+            &mut mm,
+            user_id,
+            provider,
+            stored_refresh_token,
+            OAuthLinkForUpdate {
+                access_token,
+                // `None` keeps the current refresh token
+                refresh_token,
+                access_token_expires_at: tokens.expires_at,
+            },
+        )
+        .await?;
+        if !stored {
+            tracing::debug!(
+                "{:<12} -- {} tokens of user {} were refreshed concurrently",
+                "OAUTH",
+                provider,
+                user_id
+            );
+        }
+
+        Ok(tokens.access_token)
+    }
+
+    /// Starts a request to a provider API, authenticated as `user_id` with a bearer token.
+    pub async fn request(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        method: Method,
+        url: impl IntoUrl,
+    ) -> Result<RequestBuilder> {
+        let access_token = self.access_token(user_id, provider).await?;
+        Ok(self
+            .mm
+            .http_client()
+            .request(method, url)
+            .header("User-Agent", "nrs-webapp")
+            .bearer_auth(access_token.secret()))
+    }
+
+    /// Sends an authenticated `GET` request to a provider API and deserializes the JSON body.
+    pub async fn get_json<T: DeserializeOwned>(
+        &self,
+        user_id: Uuid,
+        provider: &str,
+        url: impl IntoUrl,
+    ) -> Result<T> {
+        let resp = self
+            .request(user_id, provider, Method::GET, url)
+            .await?
+            .send()
+            .await
+            .map_err(|err| external_error(err.into()))?
+            .error_for_status()
+            .map_err(|err| external_error(err.into()))?;
+        resp.json().await.map_err(|err| external_error(err.into()))
+    }
+}
+
+fn external_error(err: external::Error) -> Error {
+    Error::Auth(auth::Error::ExternalAuth(err))
+}
+
+/// Encrypts the access and refresh tokens of `tokens` for storage in an OAuth link.
+pub fn encrypt_tokens(tokens: &TokenResponse) -> Result<(Vec<u8>, Option<Vec<u8>>)> {
+    let cipher = SymmetricCipher::get_from_config();
+    let encrypted_access_token = cipher.encrypt(tokens.access_token.secret().as_bytes())?;
+    let encrypted_refresh_token = tokens
+        .refresh_token
+        .as_ref()
+        .map(|refresh_token| cipher.encrypt(refresh_token.secret().as_bytes()))
+        .transpose()?;
+    Ok((encrypted_access_token, encrypted_refresh_token))
+}
+
+/// Decrypts a token stored in an OAuth link.
+pub fn decrypt_token(token: &[u8]) -> Result<String> {
+    let token = SymmetricCipher::get_from_config().decrypt(token)?;
+    String::from_utf8(token).map_err(|err| Error::Unexpected(err.into()))
+}
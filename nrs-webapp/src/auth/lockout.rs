@@ -0,0 +1,319 @@
+//! Brute-force protection of password logins.
+//!
+//! Failed logins are recorded per account and per client IP. After a few failures, each further
+//! attempt has to wait for a delay that doubles with every failure. An account that reaches
+//! `SERVICE_LOGIN_MAX_FAILURES` within `SERVICE_LOGIN_FAILURE_WINDOW_SECS` is locked for
+//! `SERVICE_LOGIN_LOCKOUT_SECS`, and its owner receives an email with a link to unlock it early.
+//! An IP address reaching `SERVICE_LOGIN_MAX_IP_FAILURES` is refused until its failures leave the
+//! window.
+
+use std::time::Duration;
+
+use always_send::FutureExt;
+use nrs_webapp_frontend::views::email::Locale;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::{
+    Error, Result, auth,
+    auth::error::LoginError,
+    config::AppConfig,
+    crypt::token::{Token, TokenHasher},
+    mail::{outbox, queue_account_locked_mail},
+    model::{
+        ModelManager,
+        audit_event::AuditContext,
+        login_attempt::{LoginAttemptBmc, LoginFailureStats},
+        login_event::LoginEventBmc,
+        token::{TokenPurpose, UserOneTimeTokenBmc, UserOneTimeTokenCreateReq},
+        user::UserBmc,
+    },
+};
+
+// failures allowed before delays kick in; an IP may be shared by several users
+const ACCOUNT_FREE_FAILURES: i64 = 3;
+const IP_FREE_FAILURES: i64 = 10;
+const BASE_DELAY: Duration = Duration::from_secs(1);
+const MAX_DELAY: Duration = Duration::from_secs(60);
+
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(600);
+// how long the successful logins are shown to the admins
+const LOGIN_EVENT_RETENTION: time::Duration = time::Duration::days(90);
+
+// reported in `Error::RateLimitExceeded` when a login is refused
+const DELAY_POLICY: &str = "login-delay";
+const IP_BLOCK_POLICY: &str = "login-ip-block";
+
+/// The account a password login was attempted for.
+pub struct LoginTarget<'a> {
+    pub user_id: Uuid,
+    pub username: &'a str,
+    pub email: &'a str,
+    pub locked_until: Option<OffsetDateTime>,
+}
+
+impl LoginTarget<'_> {
+    fn is_locked(&self, now: OffsetDateTime) -> bool {
+        self.locked_until.is_some_and(|until| until > now)
+    }
+}
+
+/// Checks whether a password login for `target` from `ip` may be attempted now, before the
+/// password is verified.
+///
+/// Whether the account is locked is deliberately not checked here, see [`ensure_not_locked`].
+///
+/// # Errors
+///
+/// Returns `Error::RateLimitExceeded` when the IP is blocked, or the progressive delay since the last
+///   failure has not elapsed yet.
+pub async fn check_login_allowed(
+    mm: &mut ModelManager,
+    target: Option<&LoginTarget<'_>>,
+    ip: &str,
+) -> Result<()> {
+    let config = AppConfig::get();
+    let now = OffsetDateTime::now_utc();
+    let window = time::Duration::try_from(config.SERVICE_LOGIN_FAILURE_WINDOW_DURATION)
+        .expect("negative duration");
+
+    let ip_failures = LoginAttemptBmc::failures_for_ip(mm, ip, now - window).await?;
+    if ip_failures.failures >= i64::from(config.SERVICE_LOGIN_MAX_IP_FAILURES) {
+        return Err(Error::RateLimitExceeded {
+            policy: IP_BLOCK_POLICY.into(),
+            retry_after: remaining(ip_failures.last_failure_at, window, now),
+        });
+    }
+
+    let account_failures = match target {
+        Some(target) => {
+            LoginAttemptBmc::failures_for_user(mm, target.user_id, now - window).await?
+        }
+        None => LoginFailureStats::default(),
+    };
+
+    let retry_after = [
+        (account_failures, ACCOUNT_FREE_FAILURES),
+        (ip_failures, IP_FREE_FAILURES),
+    ]
+    .into_iter()
+    .filter_map(|(stats, free_failures)| {
+        let delay = progressive_delay(stats.failures, free_failures)?;
+        let delay = time::Duration::try_from(delay).expect("delay out of range");
+        Some(remaining(stats.last_failure_at, delay, now))
+    })
+    .max()
+    .unwrap_or_default();
+
+    if !retry_after.is_zero() {
+        return Err(Error::RateLimitExceeded {
+            policy: DELAY_POLICY.into(),
+            retry_after,
+        });
+    }
+
+    Ok(())
+}
+
+/// Checks that the account of `target` is not locked.
+///
+/// Must only be called once the password has been verified, so that the lock tells nothing about
+/// the account to someone who does not know its password.
+///
+/// # Errors
+///
+/// Returns `LoginError::AccountLocked` when the account is locked.
+pub fn ensure_not_locked(target: &LoginTarget<'_>) -> Result<()> {
+    if target.is_locked(OffsetDateTime::now_utc()) {
+        return Err(Error::Auth(auth::Error::Login(LoginError::AccountLocked)));
+    }
+    Ok(())
+}
+
+/// Records a failed password login from `ip`, locking the account of `target` if it reached the
+/// maximum number of failures. An account that is already locked is not locked again.
+///
+/// When the account gets locked, an unlock token is created and an email with the unlock link is
+/// queued for the user in the same transaction. The lock is recorded in the audit log with
+/// `audit`.
+///
+/// # Returns
+///
+/// `true` if the account has just been locked.
+pub async fn record_failure(
+    mm: &ModelManager,
+    audit: &AuditContext,
+    target: Option<&LoginTarget<'_>>,
+    ip: &str,
+    user_agent: &str,
+) -> Result<bool> {
+    let config = AppConfig::get();
+    let now = OffsetDateTime::now_utc();
+    let window = time::Duration::try_from(config.SERVICE_LOGIN_FAILURE_WINDOW_DURATION)
+        .expect("negative duration");
+
+    let mut tx = mm.tx().await?;
+    LoginAttemptBmc::record_failure(&mut tx, target.map(|target| target.user_id), ip)
+        .always_send()
+        .await?;
+
+    let Some(target) = target else {
+        tx.commit().await?;
+        return Ok(false);
+    };
+
+    let failures = LoginAttemptBmc::failures_for_user(&mut tx, target.user_id, now - window)
+        .always_send()
+        .await?;
+    if failures.failures < i64::from(config.SERVICE_LOGIN_MAX_FAILURES) || target.is_locked(now) {
+        tx.commit().await?;
+        return Ok(false);
+    }
+
+    let lockout =
+        time::Duration::try_from(config.SERVICE_LOGIN_LOCKOUT_DURATION).expect("negative duration");
+    UserBmc::lock_until(&mut tx, audit, target.user_id, now + lockout)
+        .always_send()
+        .await?;
+
+    let unlock_token = Token::generate()?;
+    UserOneTimeTokenBmc::create_token(
+        &mut tx,
+        UserOneTimeTokenCreateReq {
+            user_id: target.user_id,
+            purpose: TokenPurpose::AccountUnlock,
+            token_hash: TokenHasher::get_from_config().hash(&unlock_token),
+            expires_at: now + config.account_unlock_expiry_duration(),
+            request_ip: Some(ip.to_string()),
+            user_agent: Some(user_agent.to_string()),
+        },
+    )
+    .always_send()
+    .await?;
+    // the failed logins may not come from the account owner, so their language says nothing
+    // about the owner's
+    queue_account_locked_mail(
+        &mut tx,
+        target.email,
+        target.username,
+        &unlock_token,
+        Locale::default(),
+    )
+    .always_send()
+    .await?;
+    tx.commit().await?;
+    outbox::wake_worker();
+
+    tracing::info!(
+        "{:<12} -- Locked account {} after {} failed logins",
+        "LOCKOUT",
+        target.user_id,
+        failures.failures
+    );
+
+    Ok(true)
+}
+
+/// Forgets the failed logins of `user_id` after a successful login.
+pub async fn record_success(mm: &mut ModelManager, user_id: Uuid) -> Result<()> {
+    LoginAttemptBmc::clear_for_user(mm, user_id).await?;
+    Ok(())
+}
+
+/// Lifts the lockout of the account owning the unlock `token`, and forgets its failed logins.
+///
+/// # Errors
+///
+/// Returns `model::Error::InvalidOrExpiredToken` if the token is unknown, used or expired.
+pub async fn unlock_account(
+    mm: &ModelManager,
+    audit: &AuditContext,
+    token: &Token,
+) -> Result<Uuid> {
+    let mut tx = mm.tx().await?;
+    let user_id = UserOneTimeTokenBmc::check_and_consume_token(
+        &mut tx,
+        &TokenHasher::get_from_config().hash(token),
+        TokenPurpose::AccountUnlock,
+    )
+    .always_send()
+    .await?;
+
+    UserBmc::unlock(&mut tx, audit, user_id)
+        .always_send()
+        .await?;
+    LoginAttemptBmc::clear_for_user(&mut tx, user_id)
+        .always_send()
+        .await?;
+    tx.commit().await?;
+
+    tracing::info!("{:<12} -- Unlocked account {}", "LOCKOUT", user_id);
+    Ok(user_id)
+}
+
+/// Periodically deletes the failed logins that left the failure window, and the successful ones
+/// older than 90 days.
+pub fn spawn_cleanup(mut mm: ModelManager) {
+    tokio::spawn(async move {
+        let window =
+            time::Duration::try_from(AppConfig::get().SERVICE_LOGIN_FAILURE_WINDOW_DURATION)
+                .expect("negative duration");
+        let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+        loop {
+            interval.tick().await;
+            let before = OffsetDateTime::now_utc() - window;
+            if let Err(err) = LoginAttemptBmc::delete_older_than(&mut mm, before).await {
+                tracing::warn!(
+                    "{:<12} -- Failed to clean up login attempts: {}",
+                    "LOCKOUT",
+                    err
+                );
+            }
+            let before = OffsetDateTime::now_utc() - LOGIN_EVENT_RETENTION;
+            if let Err(err) = LoginEventBmc::delete_older_than(&mut mm, before).await {
+                tracing::warn!(
+                    "{:<12} -- Failed to clean up login events: {}",
+                    "LOCKOUT",
+                    err
+                );
+            }
+        }
+    });
+}
+
+/// The delay before the next attempt after `failures` failed logins, if any.
+fn progressive_delay(failures: i64, free_failures: i64) -> Option<Duration> {
+    let exponent = u32::try_from(failures - free_failures).ok()?;
+    Some(
+        2u32.checked_pow(exponent)
+            .and_then(|factor| BASE_DELAY.checked_mul(factor))
+            .map_or(MAX_DELAY, |delay| delay.min(MAX_DELAY)),
+    )
+}
+
+/// Time left until `duration` has passed since `since`, zero if it already has.
+fn remaining(
+    since: Option<OffsetDateTime>,
+    duration: time::Duration,
+    now: OffsetDateTime,
+) -> Duration {
+    since
+        .map(|since| since + duration - now)
+        .and_then(|left| Duration::try_from(left).ok())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn progressive_delay_doubles_up_to_max() {
+        assert_eq!(progressive_delay(2, 3), None);
+        assert_eq!(progressive_delay(3, 3), Some(Duration::from_secs(1)));
+        assert_eq!(progressive_delay(4, 3), Some(Duration::from_secs(2)));
+        assert_eq!(progressive_delay(6, 3), Some(Duration::from_secs(8)));
+        assert_eq!(progressive_delay(9, 3), Some(MAX_DELAY));
+        assert_eq!(progressive_delay(100, 3), Some(MAX_DELAY));
+    }
+}
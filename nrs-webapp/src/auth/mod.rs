@@ -1,5 +1,7 @@
+pub mod bootstrap;
 pub mod error;
 pub mod external;
+pub mod lockout;
 pub mod session;
 
 use axum_extra::extract::{
@@ -135,8 +137,8 @@ pub fn get_auth_flow_state_cookie(jar: &PrivateCookieJar) -> Option<AuthFlowStat
 #[derive(Serialize, Deserialize)]
 pub struct TempTokensCookie {
     pub tokens: TokenResponse,
-    pub email: Option<String>,
-    pub email_verified: bool,
+    // addresses the user can register with without confirming them by mail
+    pub verified_emails: Vec<String>,
     pub subject: String,
     pub provider_name: String,
 }
@@ -1,12 +1,152 @@
+use std::{
+    convert::Infallible,
+    marker::PhantomData,
+    sync::{Arc, OnceLock},
+};
+
+use axum::{
+    extract::{FromRef, FromRequestParts, OptionalFromRequestParts},
+    http::request::Parts,
+};
 use uuid::Uuid;
 
+use crate::{
+    Result, auth,
+    model::{
+        ModelManager,
+        user_role::{Role, UserRoleBmc},
+    },
+};
+
 #[derive(Debug, Clone)]
 pub struct Session {
     pub user_id: Uuid,
+    // loaded on the first role check of the request, and shared by the clones of the session
+    roles: Arc<OnceLock<Vec<Role>>>,
 }
 
 impl Session {
     pub fn new(user_id: Uuid) -> Self {
-        Self { user_id }
+        Self {
+            user_id,
+            roles: Default::default(),
+        }
+    }
+
+    /// The roles granted to the user, loaded from the database once per request so that role
+    /// changes apply immediately.
+    pub async fn roles(&self, mm: &mut ModelManager) -> Result<&[Role]> {
+        if self.roles.get().is_none() {
+            let roles = UserRoleBmc::list_for_user(mm, self.user_id).await?;
+            // a concurrent check of the same request may have loaded them first
+            let _ = self.roles.set(roles);
+        }
+        Ok(self.roles.get().map(Vec::as_slice).unwrap_or_default())
+    }
+
+    /// Whether the user was granted `role`, or a role including it.
+    pub async fn has_role(&self, mm: &mut ModelManager, role: Role) -> Result<bool> {
+        Ok(self
+            .roles(mm)
+            .await?
+            .iter()
+            .any(|granted| granted.includes(role)))
+    }
+}
+
+impl<S: Send + Sync> FromRequestParts<S> for Session {
+    type Rejection = crate::Error;
+
+    /// Extracts the `Session` inserted by `mw_req_session`, rejecting the request with
+    /// `auth::Error::NotLoggedIn` when the user is not logged in.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// // use as a handler argument to require a logged in user:
+    /// // async fn handler(session: Session) -> impl IntoResponse { ... }
+    /// ```
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> core::result::Result<Self, Self::Rejection> {
+        tracing::debug!("{:<12} -- Session", "EXTRACTOR");
+
+        parts
+            .extensions
+            .get::<Session>()
+            .cloned()
+            .ok_or(crate::Error::Auth(auth::Error::NotLoggedIn))
+    }
+}
+
+impl<S: Send + Sync> OptionalFromRequestParts<S> for Session {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> core::result::Result<Option<Self>, Self::Rejection> {
+        Ok(parts.extensions.get::<Session>().cloned())
+    }
+}
+
+/// A role a route can require with [`RequireRole`].
+pub trait RequiredRole {
+    const ROLE: Role;
+}
+
+/// Marker of the [`Role::Admin`] role, for [`RequireRole`].
+pub struct Admin;
+
+impl RequiredRole for Admin {
+    const ROLE: Role = Role::Admin;
+}
+
+/// Marker of the [`Role::Moderator`] role, for [`RequireRole`].
+pub struct Moderator;
+
+impl RequiredRole for Moderator {
+    const ROLE: Role = Role::Moderator;
+}
+
+/// Marker of the [`Role::Editor`] role, for [`RequireRole`].
+pub struct Editor;
+
+impl RequiredRole for Editor {
+    const ROLE: Role = Role::Editor;
+}
+
+/// A logged in user with the role `R`, or a role including it.
+///
+/// # Examples
+///
+/// ```no_run
+/// // use as a handler argument to require an administrator:
+/// // async fn handler(RequireRole(session, ..): RequireRole<Admin>) -> impl IntoResponse { ... }
+/// ```
+#[derive(Debug, Clone)]
+pub struct RequireRole<R: RequiredRole>(pub Session, PhantomData<R>);
+
+impl<S, R> FromRequestParts<S> for RequireRole<R>
+where
+    S: Send + Sync,
+    R: RequiredRole,
+    ModelManager: FromRef<S>,
+{
+    type Rejection = crate::Error;
+
+    /// Extracts the `Session` like [`Session`] does, rejecting the request with
+    /// `auth::Error::MissingRole` when the user lacks the role.
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &S,
+    ) -> core::result::Result<Self, Self::Rejection> {
+        let session = <Session as FromRequestParts<S>>::from_request_parts(parts, state).await?;
+        let mut mm = ModelManager::from_ref(state);
+        if !session.has_role(&mut mm, R::ROLE).await? {
+            return Err(crate::Error::Auth(auth::Error::MissingRole(R::ROLE)));
+        }
+        Ok(Self(session, PhantomData))
     }
 }
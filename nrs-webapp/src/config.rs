@@ -1,9 +1,17 @@
-use std::{fs::File, str::FromStr, sync::OnceLock, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    fs::File,
+    num::{NonZeroU32, NonZeroU64},
+    str::FromStr,
+    sync::OnceLock,
+    time::Duration,
+};
 
 use anyhow::Context;
 use axum_client_ip::ClientIpSource;
 use base64::{Engine as _, prelude::BASE64_URL_SAFE};
 use serde::Deserialize;
+use strum::EnumString;
 use url::Url;
 
 #[derive(Debug, Deserialize)]
@@ -20,6 +28,196 @@ pub struct GithubOAuthConfig {
     pub client_secret: String,
 }
 
+/// A generic OpenID Connect provider, configured through the file at `OIDC_PROVIDERS_CONFIG_PATH`.
+///
+/// The endpoints are discovered from `issuer_url`, so providers such as Keycloak, Authentik,
+/// GitLab or Microsoft only need an entry in that file.
+#[derive(Debug, Deserialize)]
+pub struct OidcProviderConfig {
+    /// Used in URLs (`/auth/oauth/authorize/<name>`) and stored with the account links, so it
+    /// should never change once users have logged in with the provider.
+    pub name: String,
+    pub display_name: String,
+    /// Kept as configured rather than parsed into a [`Url`], which would add a trailing `/` to an
+    /// issuer without a path: the discovery document has to name the exact same issuer.
+    pub issuer_url: String,
+    pub client_id: String,
+    pub client_secret: Option<String>,
+    #[serde(default = "OidcProviderConfig::default_scopes")]
+    pub scopes: Vec<String>,
+}
+
+impl OidcProviderConfig {
+    fn default_scopes() -> Vec<String> {
+        vec!["email".into(), "profile".into()]
+    }
+}
+
+/// How the connection to the SMTP server is secured, from `SMTP_TLS`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum SmtpTlsMode {
+    /// Plain connection upgraded with `STARTTLS`, which the server must support (port 587).
+    #[default]
+    StartTls,
+    /// TLS from the start of the connection (port 465).
+    Tls,
+    /// No encryption at all, only meant for local relays and tests (port 25).
+    None,
+}
+
+/// The output format of the logs, from `LOG_FORMAT`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum LogFormat {
+    /// Human-readable lines, without timestamps in debug builds.
+    Pretty,
+    /// One JSON object per line, with the fields of the enclosing spans.
+    Json,
+}
+
+impl LogFormat {
+    /// Read `LOG_FORMAT`, defaulting to `pretty` in debug builds and to `json` otherwise.
+    ///
+    /// This is not part of [`AppConfig`], as the logging is set up before the configuration is
+    /// loaded, so that the warnings of the loading are logged.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `LOG_FORMAT` is neither `pretty` nor `json`.
+    pub fn from_env() -> anyhow::Result<Self> {
+        match std::env::var("LOG_FORMAT") {
+            Ok(format) => format.parse().context("LOG_FORMAT"),
+            Err(_) if cfg!(debug_assertions) => Ok(Self::Pretty),
+            Err(_) => Ok(Self::Json),
+        }
+    }
+}
+
+/// An SMTP relay used to send the emails, enabled by setting `SMTP_HOST`.
+#[derive(Debug)]
+pub struct SmtpConfig {
+    pub host: String,
+    /// Defaults to the standard port of `tls`.
+    pub port: Option<u16>,
+    pub tls: SmtpTlsMode,
+    pub username: Option<String>,
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RateLimitBackendKind {
+    /// Counters are kept per instance, and lost on restart.
+    #[default]
+    Memory,
+    /// Counters are kept in the `rate_limit_bucket` table, shared by every instance.
+    Postgres,
+}
+
+/// Names of the built-in rate-limit policies, see `RateLimitConfig` for their default quotas.
+pub mod rate_limit_policy {
+    pub const LOGIN_IP: &str = "login-ip";
+    pub const LOGIN_USERNAME: &str = "login-username";
+    pub const REGISTER_IP: &str = "register-ip";
+    pub const CONFIRM_EMAIL: &str = "confirm-email";
+    pub const PASSWORD_RESET: &str = "password-reset";
+    pub const CSP_REPORT: &str = "csp-report";
+}
+
+/// What a rate-limit policy counts requests by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RateLimitKeyKind {
+    Ip,
+    /// The logged in user, or the IP address for anonymous requests.
+    Session,
+    // the two below are only known to the handlers, after parsing the request body, so policies
+    // using them cannot be bound to routes
+    Username,
+    Email,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RateLimitPolicyConfig {
+    pub max_requests: NonZeroU32,
+    pub period_secs: NonZeroU64,
+    pub key: RateLimitKeyKind,
+}
+
+/// Rate-limit settings, optionally read from the JSON file at `RATE_LIMIT_CONFIG_PATH`.
+///
+/// `policies` and `routes` are merged over the built-in defaults: a policy or route listed in the
+/// file replaces the default one with the same name. Routes are keyed by method and matched path,
+/// e.g. `"POST /auth/login"`, and list the names of the policies applied to them.
+#[derive(Debug, Deserialize)]
+pub struct RateLimitConfig {
+    #[serde(default)]
+    pub backend: RateLimitBackendKind,
+    #[serde(default)]
+    pub policies: HashMap<String, RateLimitPolicyConfig>,
+    #[serde(default)]
+    pub routes: HashMap<String, Vec<String>>,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        let policy = |max_requests: u32, period_secs: u64, key| RateLimitPolicyConfig {
+            max_requests: NonZeroU32::new(max_requests).expect("non-zero"),
+            period_secs: NonZeroU64::new(period_secs).expect("non-zero"),
+            key,
+        };
+
+        Self {
+            backend: RateLimitBackendKind::default(),
+            policies: HashMap::from([
+                (
+                    rate_limit_policy::LOGIN_IP.into(),
+                    policy(20, 60, RateLimitKeyKind::Ip),
+                ),
+                (
+                    rate_limit_policy::LOGIN_USERNAME.into(),
+                    policy(5, 60, RateLimitKeyKind::Username),
+                ),
+                (
+                    rate_limit_policy::REGISTER_IP.into(),
+                    policy(10, 3600, RateLimitKeyKind::Ip),
+                ),
+                (
+                    rate_limit_policy::CONFIRM_EMAIL.into(),
+                    policy(1, 60, RateLimitKeyKind::Username),
+                ),
+                (
+                    rate_limit_policy::PASSWORD_RESET.into(),
+                    policy(5, 60, RateLimitKeyKind::Email),
+                ),
+                (
+                    rate_limit_policy::CSP_REPORT.into(),
+                    policy(30, 60, RateLimitKeyKind::Ip),
+                ),
+            ]),
+            routes: HashMap::from([
+                (
+                    "POST /auth/login".into(),
+                    vec![rate_limit_policy::LOGIN_IP.into()],
+                ),
+                (
+                    "POST /auth/register".into(),
+                    vec![rate_limit_policy::REGISTER_IP.into()],
+                ),
+                (
+                    "POST /auth/oauth/register".into(),
+                    vec![rate_limit_policy::REGISTER_IP.into()],
+                ),
+                (
+                    "POST /csp-report".into(),
+                    vec![rate_limit_policy::CSP_REPORT.into()],
+                ),
+            ]),
+        }
+    }
+}
+
 #[derive(Debug)]
 #[allow(non_snake_case)]
 pub struct AppConfig {
@@ -27,6 +225,9 @@ pub struct AppConfig {
     pub SERVICE_BASE_URL: Url,
     pub SERVICE_DB_URL: String,
     pub SERVICE_CACHE_DIR: Option<String>,
+    /// Whether to apply the pending migrations on startup, rather than only refusing to start
+    /// until they are applied with the `migrate` command.
+    pub SERVICE_DB_MIGRATE_ON_STARTUP: bool,
     pub IP_SOURCE: ClientIpSource,
 
     pub SERVICE_ENCRYPTION_KEY: Vec<u8>,
@@ -38,11 +239,25 @@ pub struct AppConfig {
     pub SERVICE_EMAIL_VERIFICATION_EXPIRY_DURATION: Duration,
     pub SERVICE_PASSWORD_RESET_EXPIRY_DURATION: Duration,
     pub SERVICE_OAUTH_EXPIRY_DURATION: Duration,
+    pub SERVICE_ACCOUNT_UNLOCK_EXPIRY_DURATION: Duration,
+    pub SERVICE_LOGIN_MAX_FAILURES: u32,
+    pub SERVICE_LOGIN_MAX_IP_FAILURES: u32,
+    pub SERVICE_LOGIN_FAILURE_WINDOW_DURATION: Duration,
+    pub SERVICE_LOGIN_LOCKOUT_DURATION: Duration,
+    pub SERVICE_MAIL_MAX_ATTEMPTS: u32,
+    pub SERVICE_MAIL_RETRY_BASE_DURATION: Duration,
+    /// User granted the admin role on startup, as long as no user has it.
+    pub SERVICE_BOOTSTRAP_ADMIN_USERNAME: Option<String>,
+    /// Bearer token required to read `/metrics`, which is not served without one.
+    pub SERVICE_METRICS_TOKEN: Option<String>,
     pub RESEND_API_KEY: Option<String>,
+    pub SMTP: Option<SmtpConfig>,
 
     pub EMAIL_ACCOUNT_SUPPORT: Option<String>,
     pub GOOGLE_OAUTH_CREDENTIALS: Option<GoogleOAuthConfig>,
     pub GITHUB_OAUTH_CREDENTIALS: Option<GithubOAuthConfig>,
+    pub OIDC_PROVIDERS: Vec<OidcProviderConfig>,
+    pub RATE_LIMIT: RateLimitConfig,
 }
 
 impl AppConfig {
@@ -137,9 +352,22 @@ impl AppConfig {
     /// - `IP_SOURCE` (parsed as `ClientIpSource`)
     /// - `SERVICE_PASSWORD_PEPPER`, `SERVICE_COOKIE_KEY`, `SERVICE_TOKEN_SECRET` (URL-safe base64 decoded to `Vec<u8>`)
     /// - `SERVICE_SESSION_EXPIRY_SECS`, `SERVICE_EMAIL_VERIFICATION_EXPIRY_SECS`, `SERVICE_PASSWORD_RESET_EXPIRY_SECS` (seconds parsed to `std::time::Duration`)
+    /// - `SERVICE_ACCOUNT_UNLOCK_EXPIRY_SECS`, `SERVICE_LOGIN_FAILURE_WINDOW_SECS`, `SERVICE_LOGIN_LOCKOUT_SECS` (seconds)
+    /// - `SERVICE_LOGIN_MAX_FAILURES` (failed logins of an account before it is locked),
+    ///   `SERVICE_LOGIN_MAX_IP_FAILURES` (failed logins from an IP before it is blocked)
+    /// - `SERVICE_MAIL_MAX_ATTEMPTS` (delivery attempts of an email before it is given up on),
+    ///   `SERVICE_MAIL_RETRY_BASE_SECS` (delay before the first retry, doubled on each failure)
     ///
     /// Optional environment variables (treated as `Option<String>`):
     /// - `RESEND_API_KEY`, `EMAIL_ACCOUNT_SUPPORT`
+    /// - `SERVICE_DB_MIGRATE_ON_STARTUP` (`true` or `false`, defaults to `true`)
+    /// - `SERVICE_BOOTSTRAP_ADMIN_USERNAME` (user made the first admin on startup, once registered)
+    /// - `SERVICE_METRICS_TOKEN` (bearer token of the Prometheus scrapers, enables `/metrics`)
+    /// - `SMTP_HOST` (enables the SMTP mailer), with `SMTP_PORT`, `SMTP_TLS` (`starttls`, `tls` or
+    ///   `none`, defaults to `starttls`), `SMTP_USERNAME` and `SMTP_PASSWORD`
+    /// - `GOOGLE_OAUTH_CREDENTIALS_PATH`, `GITHUB_OAUTH_CREDENTIALS_PATH`,
+    ///   `OIDC_PROVIDERS_CONFIG_PATH` (paths to JSON files; the provider is disabled when missing)
+    /// - `RATE_LIMIT_CONFIG_PATH` (path to a JSON file; the defaults are used when missing)
     ///
     /// # Errors
     ///
@@ -158,6 +386,12 @@ impl AppConfig {
             SERVICE_BASE_URL: Self::get_env_parse("SERVICE_BASE_URL")?,
             SERVICE_DB_URL: Self::get_env("SERVICE_DB_URL")?,
             SERVICE_CACHE_DIR: Self::get_env("SERVICE_CACHE_DIR").ok(),
+            SERVICE_DB_MIGRATE_ON_STARTUP: Self::get_env("SERVICE_DB_MIGRATE_ON_STARTUP")
+                .ok()
+                .map(|value| value.parse())
+                .transpose()
+                .context("Invalid SERVICE_DB_MIGRATE_ON_STARTUP")?
+                .unwrap_or(true),
             IP_SOURCE: Self::get_env_parse::<ClientIpSource>("IP_SOURCE")?,
 
             SERVICE_ENCRYPTION_KEY: Self::get_env_b64u("SERVICE_ENCRYPTION_KEY")?,
@@ -171,7 +405,24 @@ impl AppConfig {
                 "SERVICE_PASSWORD_RESET_EXPIRY_SECS",
             )?,
             SERVICE_OAUTH_EXPIRY_DURATION: Self::get_env_dur_secs("SERVICE_OAUTH_EXPIRY_SECS")?,
+            SERVICE_ACCOUNT_UNLOCK_EXPIRY_DURATION: Self::get_env_dur_secs(
+                "SERVICE_ACCOUNT_UNLOCK_EXPIRY_SECS",
+            )?,
+            SERVICE_LOGIN_MAX_FAILURES: Self::get_env_parse("SERVICE_LOGIN_MAX_FAILURES")?,
+            SERVICE_LOGIN_MAX_IP_FAILURES: Self::get_env_parse("SERVICE_LOGIN_MAX_IP_FAILURES")?,
+            SERVICE_LOGIN_FAILURE_WINDOW_DURATION: Self::get_env_dur_secs(
+                "SERVICE_LOGIN_FAILURE_WINDOW_SECS",
+            )?,
+            SERVICE_LOGIN_LOCKOUT_DURATION: Self::get_env_dur_secs("SERVICE_LOGIN_LOCKOUT_SECS")?,
+            SERVICE_MAIL_MAX_ATTEMPTS: Self::get_env_parse("SERVICE_MAIL_MAX_ATTEMPTS")?,
+            SERVICE_MAIL_RETRY_BASE_DURATION: Self::get_env_dur_secs(
+                "SERVICE_MAIL_RETRY_BASE_SECS",
+            )?,
+            SERVICE_BOOTSTRAP_ADMIN_USERNAME: Self::get_env("SERVICE_BOOTSTRAP_ADMIN_USERNAME")
+                .ok(),
+            SERVICE_METRICS_TOKEN: Self::get_env("SERVICE_METRICS_TOKEN").ok(),
             RESEND_API_KEY: Self::get_env("RESEND_API_KEY").ok(),
+            SMTP: Self::load_smtp_config()?,
             SERVICE_TOKEN_SECRET: Self::get_env_b64u("SERVICE_TOKEN_SECRET")?,
             EMAIL_ACCOUNT_SUPPORT: Self::get_env("EMAIL_ACCOUNT_SUPPORT").ok(),
             GOOGLE_OAUTH_CREDENTIALS: Self::load_google_oauth_config()
@@ -180,6 +431,10 @@ impl AppConfig {
             GITHUB_OAUTH_CREDENTIALS: Self::load_github_oauth_config()
                 .inspect_err(|err| tracing::warn!("GITHUB_OAUTH_CREDENTIALS not loaded: {err:?}"))
                 .ok(),
+            OIDC_PROVIDERS: Self::load_oidc_providers_config()
+                .inspect_err(|err| tracing::warn!("OIDC_PROVIDERS not loaded: {err:?}"))
+                .unwrap_or_default(),
+            RATE_LIMIT: Self::load_rate_limit_config()?,
         })
     }
 
@@ -268,6 +523,46 @@ impl AppConfig {
         Self::duration_to_time_duration(self.SERVICE_PASSWORD_RESET_EXPIRY_DURATION)
     }
 
+    /// Returns how long an account unlock link stays valid, as a `time::Duration`.
+    pub fn account_unlock_expiry_duration(&self) -> time::Duration {
+        Self::duration_to_time_duration(self.SERVICE_ACCOUNT_UNLOCK_EXPIRY_DURATION)
+    }
+
+    /// Load the SMTP relay settings, `None` when `SMTP_HOST` is not set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `SMTP_PORT` or `SMTP_TLS` is set but invalid, or if only one of
+    /// `SMTP_USERNAME` and `SMTP_PASSWORD` is set.
+    fn load_smtp_config() -> anyhow::Result<Option<SmtpConfig>> {
+        let Ok(host) = Self::get_env("SMTP_HOST") else {
+            return Ok(None);
+        };
+
+        let port = match Self::get_env("SMTP_PORT") {
+            Ok(_) => Some(Self::get_env_parse("SMTP_PORT")?),
+            Err(_) => None,
+        };
+        let tls = match Self::get_env("SMTP_TLS") {
+            Ok(_) => Self::get_env_parse("SMTP_TLS")?,
+            Err(_) => SmtpTlsMode::default(),
+        };
+        let username = Self::get_env("SMTP_USERNAME").ok();
+        let password = Self::get_env("SMTP_PASSWORD").ok();
+        anyhow::ensure!(
+            username.is_some() == password.is_some(),
+            "SMTP_USERNAME and SMTP_PASSWORD must be set together"
+        );
+
+        Ok(Some(SmtpConfig {
+            host,
+            port,
+            tls,
+            username,
+            password,
+        }))
+    }
+
     fn load_google_oauth_config() -> anyhow::Result<GoogleOAuthConfig> {
         #[derive(Deserialize)]
         struct GoogleOAuthConfigWrapped {
@@ -293,4 +588,138 @@ impl AppConfig {
 
         Ok(credentials)
     }
+
+    /// Load the generic OIDC providers from the JSON array at `OIDC_PROVIDERS_CONFIG_PATH`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed, if a provider name is not made of
+    /// lowercase ASCII letters, digits, `-` and `_`, if two providers share a name, or if an
+    /// issuer is not a valid URL.
+    ///
+    /// # Examples
+    ///
+    /// ```json
+    /// [
+    ///   {
+    ///     "name": "keycloak",
+    ///     "display_name": "Keycloak",
+    ///     "issuer_url": "https://sso.example.com/realms/nrs",
+    ///     "client_id": "nrs-webapp",
+    ///     "client_secret": "...",
+    ///     "scopes": ["email", "profile"]
+    ///   }
+    /// ]
+    /// ```
+    fn load_oidc_providers_config() -> anyhow::Result<Vec<OidcProviderConfig>> {
+        let path = Self::get_env("OIDC_PROVIDERS_CONFIG_PATH")?;
+        tracing::info!("Loading OIDC providers from {}", path);
+        let providers = serde_json::from_reader::<_, Vec<OidcProviderConfig>>(File::open(&path)?)
+            .with_context(|| format!("Failed to read OIDC providers from {}", path))?;
+        Self::validate_oidc_providers(&providers)?;
+
+        Ok(providers)
+    }
+
+    fn validate_oidc_providers(providers: &[OidcProviderConfig]) -> anyhow::Result<()> {
+        let mut names = HashSet::new();
+        for provider in providers {
+            anyhow::ensure!(
+                !provider.name.is_empty()
+                    && provider.name.chars().all(|c| {
+                        c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_'
+                    }),
+                "Invalid OIDC provider name {:?}",
+                provider.name
+            );
+            anyhow::ensure!(
+                names.insert(provider.name.as_str()),
+                "Duplicate OIDC provider name {:?}",
+                provider.name
+            );
+            openidconnect::IssuerUrl::new(provider.issuer_url.clone()).with_context(|| {
+                format!("Invalid issuer URL of OIDC provider {:?}", provider.name)
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Load the rate-limit settings from `RATE_LIMIT_CONFIG_PATH`, merged over the defaults.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read or parsed, if a route refers to an
+    /// unknown policy, or to a policy keyed by username or email.
+    ///
+    /// # Examples
+    ///
+    /// ```json
+    /// {
+    ///   "backend": "postgres",
+    ///   "policies": {
+    ///     "login-ip": { "max_requests": 50, "period_secs": 60, "key": "ip" },
+    ///     "entry-write": { "max_requests": 30, "period_secs": 60, "key": "session" }
+    ///   },
+    ///   "routes": { "POST /entry": ["entry-write"] }
+    /// }
+    /// ```
+    fn load_rate_limit_config() -> anyhow::Result<RateLimitConfig> {
+        let mut config = RateLimitConfig::default();
+
+        if let Ok(path) = Self::get_env("RATE_LIMIT_CONFIG_PATH") {
+            tracing::info!("Loading rate-limit config from {}", path);
+            let overrides = serde_json::from_reader::<_, RateLimitConfig>(File::open(&path)?)
+                .with_context(|| format!("Failed to read rate-limit config from {}", path))?;
+            config.backend = overrides.backend;
+            config.policies.extend(overrides.policies);
+            config.routes.extend(overrides.routes);
+        }
+
+        for (route, policies) in &config.routes {
+            for name in policies {
+                let policy = config.policies.get(name).with_context(|| {
+                    format!("Unknown rate-limit policy {name:?} for route {route:?}")
+                })?;
+                anyhow::ensure!(
+                    matches!(policy.key, RateLimitKeyKind::Ip | RateLimitKeyKind::Session),
+                    "Rate-limit policy {name:?} of route {route:?} must be keyed by ip or session"
+                );
+            }
+        }
+
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn oidc_provider(issuer_url: &str) -> OidcProviderConfig {
+        serde_json::from_value(json!({
+            "name": "gitlab",
+            "display_name": "GitLab",
+            "issuer_url": issuer_url,
+            "client_id": "nrs-webapp",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn oidc_issuer_without_path_is_kept_as_configured() {
+        let providers = [oidc_provider("https://gitlab.com")];
+        AppConfig::validate_oidc_providers(&providers).unwrap();
+
+        let issuer = openidconnect::IssuerUrl::new(providers[0].issuer_url.clone()).unwrap();
+        assert_eq!(issuer.as_str(), "https://gitlab.com");
+    }
+
+    #[test]
+    fn oidc_issuer_must_be_a_url() {
+        let providers = [oidc_provider("gitlab.com")];
+        assert!(AppConfig::validate_oidc_providers(&providers).is_err());
+    }
 }
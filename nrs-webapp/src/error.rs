@@ -1,4 +1,4 @@
-use std::{borrow::Cow, sync::Arc};
+use std::{borrow::Cow, sync::Arc, time::Duration};
 
 use axum::{
     http::{StatusCode, Uri},
@@ -26,8 +26,14 @@ pub enum Error {
     #[error(transparent)]
     Rejection(#[from] RejectionError),
 
-    #[error("Rate limit exceeded: {service}")]
-    RateLimitExceeded { service: &'static str },
+    #[error("Rate limit exceeded: {policy} (retry after {retry_after:?})")]
+    RateLimitExceeded {
+        policy: String,
+        retry_after: Duration,
+    },
+
+    #[error("Missing or invalid CSRF token")]
+    CsrfTokenMismatch,
 
     #[error("Page not found: {uri}")]
     PageNotFound { uri: Uri },
@@ -87,18 +93,47 @@ impl Error {
                     "The page you are looking for does not exist.".into(),
                 )
             }
+            Error::Auth(auth::Error::ExternalAuth(auth::external::Error::ProviderNotLinked(_))) => (
+                StatusCode::NOT_FOUND,
+                "This provider is not linked to your account.".into(),
+            ),
             Error::MethodNotAllowed { .. } => (
                 StatusCode::METHOD_NOT_ALLOWED,
                 "The page you are looking for does not exist.".into(),
             ),
+            Error::Auth(auth::Error::NotLoggedIn) => (
+                StatusCode::UNAUTHORIZED,
+                "You need to log in to access this page.".into(),
+            ),
+            Error::Auth(auth::Error::MissingRole(_)) => (
+                StatusCode::FORBIDDEN,
+                "You are not allowed to access this page.".into(),
+            ),
+            Error::Auth(auth::Error::NotAllowed) => (
+                StatusCode::FORBIDDEN,
+                "You are not allowed to do this.".into(),
+            ),
             Error::Auth(auth::Error::Login(auth::error::LoginError::InvalidCredentials)) => (
                 StatusCode::UNAUTHORIZED,
                 "Invalid credentials provided.".into(),
             ),
+            Error::Auth(auth::Error::Login(auth::error::LoginError::AccountLocked)) => (
+                StatusCode::FORBIDDEN,
+                "Your account is temporarily locked after too many failed logins. Check your email for a link to unlock it, or try again later.".into(),
+            ),
+            Error::Auth(auth::Error::Login(auth::error::LoginError::AccountDisabled)) => (
+                StatusCode::FORBIDDEN,
+                "Your account has been disabled.".into(),
+            ),
+            Error::Auth(auth::Error::ExternalAuth(auth::external::Error::AccessTokenExpired(_))) => (
+                StatusCode::UNAUTHORIZED,
+                "The authorization of your linked account has expired. Please unlink and link it again.".into(),
+            ),
 
             Error::Auth(auth::Error::ExternalAuth(
                 auth::external::Error::OidcDiscovery(_)
                 | auth::external::Error::TokenExchange(_)
+                | auth::external::Error::TokenRevocation(_)
                 | auth::external::Error::Reqwest(_),
             )) => (
                 StatusCode::BAD_GATEWAY,
@@ -109,6 +144,7 @@ impl Error {
                 auth::external::Error::AuthFlowStateCookieNotFound
                 | auth::external::Error::TempTokenCookieNotFound
                 | auth::external::Error::CsrfStateMismatch
+                | auth::external::Error::LinkSessionMismatch
                 | auth::external::Error::EmailMismatch
                 | auth::external::Error::NonceMissing
                 | auth::external::Error::InvalidIdTokenType
@@ -118,16 +154,52 @@ impl Error {
                 "Invalid authentication flow. Please try again.".into(),
             ),
 
+            Error::Auth(auth::Error::ExternalAuth(
+                auth::external::Error::IdentityAlreadyLinked
+                | auth::external::Error::ProviderAlreadyLinked(_),
+            )) => (
+                StatusCode::CONFLICT,
+                "This provider account is already linked to another account, or you already linked an account of this provider.".into(),
+            ),
+            Error::Auth(auth::Error::LastLoginMethod) => (
+                StatusCode::CONFLICT,
+                "You cannot unlink your only remaining way to log in. Verify your email address to enable password login first.".into(),
+            ),
+
+            Error::Model(model::Error::EntityNotFound { .. }) => (
+                StatusCode::NOT_FOUND,
+                "The requested item does not exist.".into(),
+            ),
+            Error::Model(model::Error::InvalidCursor) => (
+                StatusCode::BAD_REQUEST,
+                "The page link is invalid or out of date.".into(),
+            ),
             Error::Model(model::Error::EmailOrUsernameAlreadyExists) => (
                 StatusCode::UNPROCESSABLE_ENTITY,
                 "A user with the given email or username already exists.".into(),
             ),
+            Error::Model(model::Error::EntryAlreadyExists) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                "An entry with the given ID already exists.".into(),
+            ),
+            Error::Rejection(RejectionError::Query(_)) => (
+                StatusCode::BAD_REQUEST,
+                "The parameters of the page are invalid.".into(),
+            ),
             Error::Rejection(RejectionError::Validation(err)) => {
                 (StatusCode::UNPROCESSABLE_ENTITY, err.to_string().into())
             }
-            Error::RateLimitExceeded { service } => (
+            Error::RateLimitExceeded { retry_after, .. } => (
                 StatusCode::TOO_MANY_REQUESTS,
-                format!("Rate limit exceeded for {} service.", service).into(),
+                format!(
+                    "Too many requests. Please try again in {} seconds.",
+                    retry_after_secs(*retry_after)
+                )
+                .into(),
+            ),
+            Error::CsrfTokenMismatch => (
+                StatusCode::FORBIDDEN,
+                "Your session has expired. Please reload the page and try again.".into(),
             ),
             _ => (StatusCode::INTERNAL_SERVER_ERROR, "Unknown error.".into()),
         }
@@ -163,3 +235,8 @@ impl IntoResponse for Error {
         response
     }
 }
+
+/// Rounds `retry_after` up to whole seconds, as sent in the `Retry-After` header.
+pub fn retry_after_secs(retry_after: Duration) -> u64 {
+    retry_after.as_secs() + u64::from(retry_after.subsec_nanos() > 0)
+}
@@ -0,0 +1,32 @@
+use std::convert::Infallible;
+
+use axum::{extract::FromRequestParts, http::request::Parts};
+use axum_client_ip::ClientIp;
+
+use crate::{
+    auth::session::Session, middleware::mw_req_stamp::ReqStamp, model::audit_event::AuditContext,
+};
+
+impl<S> FromRequestParts<S> for AuditContext
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    /// Attributes the changes made by the request to its logged in user, if any, along with the
+    /// request UUID of [`ReqStamp`] and the client IP.
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let ip = ClientIp::from_request_parts(parts, state)
+            .await
+            .ok()
+            .map(|ClientIp(ip)| ip.to_string());
+        Ok(Self {
+            actor_id: parts
+                .extensions
+                .get::<Session>()
+                .map(|session| session.user_id),
+            request_id: parts.extensions.get::<ReqStamp>().map(|stamp| stamp.uuid),
+            ip,
+        })
+    }
+}
@@ -7,7 +7,11 @@ use axum::{
 use nrs_webapp_frontend::views::document::DocumentProps;
 use serde::Deserialize;
 
-use crate::{auth::session::Session, toasts::ConstToast};
+use crate::{
+    auth::session::Session,
+    middleware::{mw_csrf::CsrfToken, mw_sec_headers::CspNonce},
+    toasts::ConstToast,
+};
 
 #[derive(Clone, Default)]
 pub struct DocProps(pub DocumentProps);
@@ -34,6 +38,7 @@ where
     ///
     /// The extractor:
     /// - sets `logged_in` to `true` when a `Session` is present in `parts.extensions`, `false` otherwise;
+    /// - copies the `CsrfToken` and `CspNonce` of the request, if any, to `csrf_token` and `csp_nonce`;
     /// - parses an optional `toast` query parameter, converts it to a `ConstToast` via `FromStr`, and places a single converted toast in `toasts` when parsing succeeds (invalid or absent values produce an empty `toasts` vector);
     /// - leaves all other `DocumentProps` fields as their defaults.
     ///
@@ -55,10 +60,14 @@ where
                 .unwrap_or_default();
 
         let session = parts.extensions.get::<Session>();
+        let csrf_token = parts.extensions.get::<CsrfToken>().map(|t| t.0.clone());
+        let csp_nonce = parts.extensions.get::<CspNonce>().map(|n| n.0.clone());
 
         // TODO: implement this
         Ok(Self(DocumentProps {
             logged_in: session.is_some(),
+            csrf_token,
+            csp_nonce,
             toasts: toast
                 .and_then(|t| ConstToast::from_str(&t).ok())
                 .map(|t| vec![t.into()])
@@ -0,0 +1,30 @@
+use std::convert::Infallible;
+
+use axum::{
+    extract::FromRequestParts,
+    http::{header::ACCEPT_LANGUAGE, request::Parts},
+};
+use nrs_webapp_frontend::views::email::Locale;
+
+/// The locale the client prefers, from its `Accept-Language` header.
+///
+/// Falls back to [`Locale::default`] when the header is absent or names no supported language.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PreferredLocale(pub Locale);
+
+impl<S> FromRequestParts<S> for PreferredLocale
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let locale = parts
+            .headers
+            .get(ACCEPT_LANGUAGE)
+            .and_then(|value| value.to_str().ok())
+            .map(Locale::from_accept_language)
+            .unwrap_or_default();
+        Ok(Self(locale))
+    }
+}
@@ -1,2 +1,4 @@
+pub mod audit;
 pub mod doc_props;
+pub mod locale;
 pub mod with_rejection;
@@ -0,0 +1,154 @@
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+use async_trait::async_trait;
+use nrs_webapp_frontend::views::email::RenderedEmail;
+use time::OffsetDateTime;
+
+use super::Result;
+use crate::mail::Mailer;
+
+// older mails are dropped, so that a long-running dev server does not grow forever
+const CAPACITY: usize = 100;
+
+/// A mail kept by the [`CaptureMailer`] instead of being sent.
+#[derive(Debug, Clone)]
+pub struct CapturedMail {
+    /// Sequence number of the mail, starting at 1.
+    pub id: u64,
+    pub to: String,
+    pub from: String,
+    pub email: RenderedEmail,
+    pub captured_at: OffsetDateTime,
+}
+
+#[derive(Default)]
+struct Mailbox {
+    last_id: u64,
+    mails: VecDeque<CapturedMail>,
+}
+
+/// Keeps the sent mails in memory, for the dev mailbox page and for tests to assert on.
+///
+/// Clones share the same mailbox.
+#[derive(Clone, Default)]
+pub struct CaptureMailer(Arc<Mutex<Mailbox>>);
+
+impl CaptureMailer {
+    /// The captured mails, newest first.
+    pub fn mails(&self) -> Vec<CapturedMail> {
+        let mailbox = self.0.lock().expect("mailbox lock poisoned");
+        mailbox.mails.iter().rev().cloned().collect()
+    }
+
+    /// The captured mail with the sequence number `id`, unless it was dropped.
+    pub fn get(&self, id: u64) -> Option<CapturedMail> {
+        let mailbox = self.0.lock().expect("mailbox lock poisoned");
+        mailbox.mails.iter().find(|mail| mail.id == id).cloned()
+    }
+
+    /// Drops every captured mail.
+    pub fn clear(&self) {
+        self.0.lock().expect("mailbox lock poisoned").mails.clear();
+    }
+}
+
+#[async_trait]
+impl Mailer for CaptureMailer {
+    /// Adds the email to the mailbox, dropping the oldest one when it is full, and always
+    /// succeeds.
+    async fn send_mail(&self, to: &str, from: &str, email: RenderedEmail) -> Result<()> {
+        let mut mailbox = self.0.lock().expect("mailbox lock poisoned");
+        mailbox.last_id += 1;
+        let id = mailbox.last_id;
+
+        tracing::info!(
+            "{:<12} -- Captured mail {} to {}: {} (see /__dev_only/mailbox/{})",
+            "EMAIL",
+            id,
+            to,
+            email.subject,
+            id
+        );
+
+        if mailbox.mails.len() == CAPACITY {
+            mailbox.mails.pop_front();
+        }
+        mailbox.mails.push_back(CapturedMail {
+            id,
+            to: to.to_string(),
+            from: from.to_string(),
+            email,
+            captured_at: OffsetDateTime::now_utc(),
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hypertext::prelude::*;
+
+    use super::*;
+
+    fn sample_email(subject: &str) -> RenderedEmail {
+        RenderedEmail {
+            subject: subject.to_string(),
+            html: rsx! { <p>"Hello"</p> }.render(),
+            text: "Hello".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn captures_mails_newest_first() {
+        let mailer = CaptureMailer::default();
+        mailer
+            .send_mail(
+                "alice@example.com",
+                "from@example.com",
+                sample_email("First"),
+            )
+            .await
+            .unwrap();
+        mailer
+            .send_mail(
+                "bob@example.com",
+                "from@example.com",
+                sample_email("Second"),
+            )
+            .await
+            .unwrap();
+
+        let mails = mailer.mails();
+        assert_eq!(mails.len(), 2);
+        assert_eq!(mails[0].id, 2);
+        assert_eq!(mails[0].to, "bob@example.com");
+        assert_eq!(mails[1].email.subject, "First");
+        assert_eq!(mailer.get(1).unwrap().to, "alice@example.com");
+
+        mailer.clear();
+        assert!(mailer.mails().is_empty());
+        assert!(mailer.get(1).is_none());
+    }
+
+    #[tokio::test]
+    async fn drops_oldest_mail_when_full() {
+        let mailer = CaptureMailer::default();
+        for i in 0..=CAPACITY {
+            mailer
+                .send_mail(
+                    "to@example.com",
+                    "from@example.com",
+                    sample_email(&i.to_string()),
+                )
+                .await
+                .unwrap();
+        }
+
+        assert_eq!(mailer.mails().len(), CAPACITY);
+        assert!(mailer.get(1).is_none());
+        assert_eq!(mailer.mails()[0].id, CAPACITY as u64 + 1);
+    }
+}
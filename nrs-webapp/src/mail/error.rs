@@ -4,6 +4,15 @@ use thiserror::Error;
 pub enum Error {
     #[error("Resend error: {0}")]
     Resend(#[from] resend_rs::Error),
+
+    #[error("SMTP error: {0}")]
+    Smtp(#[from] lettre::transport::smtp::Error),
+
+    #[error("Invalid email address: {0}")]
+    Address(#[from] lettre::address::AddressError),
+
+    #[error("Invalid email message: {0}")]
+    Message(#[from] lettre::error::Error),
 }
 
 pub type Result<T> = core::result::Result<T, Error>;
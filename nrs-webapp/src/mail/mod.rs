@@ -1,37 +1,45 @@
+#[cfg(any(debug_assertions, test))]
+pub mod capture_mail;
 mod error;
+#[cfg(not(any(debug_assertions, test)))]
 mod log_mail;
+pub mod outbox;
 mod resend_mail;
+mod smtp_mail;
 
 use std::sync::{Arc, OnceLock};
 
+use always_send::FutureExt;
 use async_trait::async_trait;
 pub use error::{Error, Result};
-use hypertext::{Renderable, Rendered};
 use nrs_webapp_frontend::views::email::{
-    email_verify::email_verify, password_reset::password_reset,
+    EmailTemplate, Locale, RenderedEmail, account_locked::AccountLocked, email_verify::EmailVerify,
+    entry_reviewed::EntryReviewed, password_reset::PasswordReset,
 };
+use sqlx::{Postgres, Transaction};
 
 use crate::{
     config::AppConfig,
-    crypt::token::Token,
-    mail::{log_mail::LogMailer, resend_mail::ResendMailer},
+    crypt::{symmetric::SymmetricCipher, token::Token},
+    mail::{resend_mail::ResendMailer, smtp_mail::SmtpMailer},
+    model::outbound_mail::{OutboundMailBmc, OutboundMailForCreate},
 };
 
 #[async_trait]
 pub trait Mailer: Send + Sync {
-    async fn send_mail(
-        &self,
-        to: &str,
-        from: &str,
-        subject: &str,
-        html_body: Rendered<String>,
-    ) -> Result<()>;
+    async fn send_mail(&self, to: &str, from: &str, email: RenderedEmail) -> Result<()>;
 }
 
 /// Returns a shared, static mailer implementation chosen from application configuration.
 ///
-/// The returned reference points to a singleton `Mailer` instance: if `AppConfig::RESEND_API_KEY`
-/// is set, a `ResendMailer` is used; otherwise a `LogMailer` is used.
+/// The returned reference points to a singleton `Mailer` instance: if `AppConfig::SMTP` is set, an
+/// `SmtpMailer` is used; otherwise if `AppConfig::RESEND_API_KEY` is set, a `ResendMailer` is used;
+/// otherwise the mails are kept by the [`capture_mailer`] in debug builds, and logged in release
+/// builds. Tests always use the [`capture_mailer`].
+///
+/// # Panics
+///
+/// Panics if the SMTP mailer cannot be built from its configuration.
 ///
 /// # Examples
 ///
@@ -45,17 +53,50 @@ pub fn get_mailer() -> &'static dyn Mailer {
     static MAILER: OnceLock<Arc<dyn Mailer>> = OnceLock::new();
     MAILER
         .get_or_init(|| {
-            if let Some(resend_api_key) = AppConfig::get().RESEND_API_KEY.as_ref() {
+            if cfg!(test) {
+                fallback_mailer()
+            } else if let Some(smtp_config) = AppConfig::get().SMTP.as_ref() {
+                tracing::info!(
+                    "{:<12} -- Using SMTP mailer via {}",
+                    "MAILER-IMPL",
+                    smtp_config.host
+                );
+                Arc::new(SmtpMailer::from_config(smtp_config).expect("invalid SMTP config"))
+            } else if let Some(resend_api_key) = AppConfig::get().RESEND_API_KEY.as_ref() {
                 tracing::info!("{:<12} -- Using Resend mailer", "MAILER-IMPL");
                 Arc::new(ResendMailer::new(resend_api_key.as_str()))
             } else {
-                tracing::info!("{:<12} -- Using Log mailer", "MAILER-IMPL");
-                Arc::new(LogMailer)
+                fallback_mailer()
             }
         })
         .as_ref()
 }
 
+#[cfg(any(debug_assertions, test))]
+fn fallback_mailer() -> Arc<dyn Mailer> {
+    tracing::info!(
+        "{:<12} -- Using capture mailer, see /__dev_only/mailbox",
+        "MAILER-IMPL"
+    );
+    Arc::new(capture_mailer().clone())
+}
+
+#[cfg(not(any(debug_assertions, test)))]
+fn fallback_mailer() -> Arc<dyn Mailer> {
+    tracing::info!("{:<12} -- Using Log mailer", "MAILER-IMPL");
+    Arc::new(log_mail::LogMailer)
+}
+
+/// The mailbox of the mails sent through [`get_mailer`] when it captures them.
+///
+/// It stays empty when another mailer is configured.
+#[cfg(any(debug_assertions, test))]
+pub fn capture_mailer() -> &'static capture_mail::CaptureMailer {
+    static CAPTURE_MAILER: std::sync::LazyLock<capture_mail::CaptureMailer> =
+        std::sync::LazyLock::new(Default::default);
+    &CAPTURE_MAILER
+}
+
 /// Get the configured support email address used as the sender for account-related messages.
 ///
 /// If the application configuration does not specify a support address, this returns
@@ -74,91 +115,147 @@ fn email_account_support() -> &'static str {
         .unwrap_or("accounts@nrs.dev")
 }
 
-/// Sends an email verification message containing a confirmation link to the specified user email.
+/// Encrypts the bodies of `email` and adds it to the outbox, from the support address.
 ///
-/// The message includes a generated confirmation URL that embeds the provided token and uses the configured
-/// support address as the sender.
+/// The mail is only sent once `tx` commits, after which the caller should call
+/// [`outbox::wake_worker`].
+async fn queue_mail(
+    tx: &mut Transaction<'static, Postgres>,
+    to: &str,
+    email: RenderedEmail,
+) -> crate::Result<()> {
+    let cipher = SymmetricCipher::get_from_config();
+    let id = OutboundMailBmc::enqueue(
+        tx,
+        OutboundMailForCreate {
+            recipient: to.to_string(),
+            sender: email_account_support().to_string(),
+            subject: email.subject,
+            html_body: cipher.encrypt(email.html.as_inner().as_bytes())?,
+            text_body: cipher.encrypt(email.text.as_bytes())?,
+        },
+    )
+    .always_send()
+    .await?;
+
+    tracing::debug!("{:<12} -- Queued mail {} to {}", "MAILER", id, to);
+    Ok(())
+}
+
+/// Queues an email verification message in `locale` containing a confirmation link to the
+/// specified user email.
+///
+/// The message includes a generated confirmation URL that embeds the provided token. It is
+/// written with `tx`, so that it is only sent if the token is committed with it.
+///
+/// # Errors
+///
+/// Returns an error if the bodies cannot be encrypted or the mail cannot be stored.
 ///
 /// # Examples
 ///
 /// ```no_run
-/// # async fn run() -> nrs_webapp::Result<()> {
-/// // Construct or obtain a `Token` appropriate for email confirmation.
-/// let token = /* Token for confirmation */ unimplemented!();
-/// nrs_webapp::mail::send_email_verification_mail("user@example.com", "alice", &token).await?;
-/// # Ok(())
-/// # }
+/// let mut tx = mm.tx().await?;
+/// // ... store the token hash with `tx` ...
+/// queue_email_verification_mail(&mut tx, "user@example.com", "alice", &token, Locale::En).await?;
+/// tx.commit().await?;
+/// outbox::wake_worker();
 /// ```
-///
-/// @returns `Ok(())` on success, `Err` on failure.
-pub async fn send_email_verification_mail(
+pub async fn queue_email_verification_mail(
+    tx: &mut Transaction<'static, Postgres>,
     user_email: &str,
     username: &str,
     token: &Token,
-) -> Result<()> {
-    tracing::debug!(
-        "{:<12} -- Sending email verification mail to {}",
-        "MAILER",
-        user_email
-    );
-
-    let subject = "nrs-webapp - Please verify your email address";
+    locale: Locale,
+) -> crate::Result<()> {
     let href = format!(
         "{}/auth/confirmmail/confirm?token={token}",
         AppConfig::get().SERVICE_BASE_URL
     );
 
-    let body = email_verify(username, &href);
-
-    get_mailer()
-        .send_mail(user_email, email_account_support(), subject, body.render())
-        .await?;
-
-    Ok(())
+    let email = EmailVerify {
+        username,
+        href: &href,
+    }
+    .render(locale);
+    queue_mail(tx, user_email, email).await
 }
 
-/// Sends a password-reset email to the specified user containing a link with the provided token.
-///
-/// The message uses the subject "nrs-webapp - Password Reset Request" and is sent from the configured support address.
+/// Queues a password-reset email in `locale` to the specified user containing a link with the
+/// provided token.
 ///
 /// # Errors
 ///
-/// Returns an error if the mailer fails to send the message.
-///
-/// # Examples
-///
-/// ```no_run
-/// use nrs_webapp::mail::send_password_reset_mail;
-/// use nrs_webapp::token::Token;
-///
-/// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
-/// let token = Token::from("example-token");
-/// send_password_reset_mail("user@example.com", "alice", &token).await?;
-/// # Ok(())
-/// # }
-/// ```
-pub async fn send_password_reset_mail(
+/// Returns an error if the bodies cannot be encrypted or the mail cannot be stored.
+pub async fn queue_password_reset_mail(
+    tx: &mut Transaction<'static, Postgres>,
     user_email: &str,
     username: &str,
     token: &Token,
-) -> Result<()> {
-    tracing::debug!(
-        "{:<12} -- Sending password reset mail to {}",
-        "MAILER",
-        user_email
+    locale: Locale,
+) -> crate::Result<()> {
+    let href = format!(
+        "{}/auth/forgotpass/reset?token={token}",
+        AppConfig::get().SERVICE_BASE_URL
     );
 
-    let subject = "nrs-webapp - Password Reset Request";
+    let email = PasswordReset {
+        username,
+        href: &href,
+    }
+    .render(locale);
+    queue_mail(tx, user_email, email).await
+}
+
+/// Queues the notice in `locale` that the account of `username` was locked after too many failed
+/// logins, with a link to unlock it using `token`.
+///
+/// # Errors
+///
+/// Returns an error if the bodies cannot be encrypted or the mail cannot be stored.
+pub async fn queue_account_locked_mail(
+    tx: &mut Transaction<'static, Postgres>,
+    user_email: &str,
+    username: &str,
+    token: &Token,
+    locale: Locale,
+) -> crate::Result<()> {
     let href = format!(
-        "{}/auth/forgotpass/reset?token={token}",
+        "{}/auth/unlock?token={token}",
         AppConfig::get().SERVICE_BASE_URL
     );
 
-    let body = password_reset(username, &href);
+    let email = AccountLocked {
+        username,
+        href: &href,
+    }
+    .render(locale);
+    queue_mail(tx, user_email, email).await
+}
 
-    get_mailer()
-        .send_mail(user_email, email_account_support(), subject, body.render())
-        .await?;
+/// Queues the notice in `locale` that a moderator reviewed the entry `entry_id` submitted by
+/// `username`: approved when `rejection_reason` is `None`, rejected otherwise.
+///
+/// # Errors
+///
+/// Returns an error if the bodies cannot be encrypted or the mail cannot be stored.
+pub async fn queue_entry_reviewed_mail(
+    tx: &mut Transaction<'static, Postgres>,
+    user_email: &str,
+    username: &str,
+    entry_id: &str,
+    entry_title: &str,
+    rejection_reason: Option<&str>,
+    locale: Locale,
+) -> crate::Result<()> {
+    let href = format!("{}/entry/{entry_id}", AppConfig::get().SERVICE_BASE_URL);
 
-    Ok(())
+    let email = EntryReviewed {
+        username,
+        entry_title,
+        href: &href,
+        rejection_reason,
+    }
+    .render(locale);
+    queue_mail(tx, user_email, email).await
 }
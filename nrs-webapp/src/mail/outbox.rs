@@ -0,0 +1,189 @@
+use std::{sync::LazyLock, time::Duration};
+
+use always_send::FutureExt;
+use hypertext::Raw;
+use nrs_webapp_frontend::views::email::RenderedEmail;
+use time::OffsetDateTime;
+use tokio::sync::Notify;
+
+use crate::{
+    Result,
+    config::AppConfig,
+    crypt::symmetric::SymmetricCipher,
+    mail::get_mailer,
+    model::{
+        ModelManager,
+        outbound_mail::{DueOutboundMail, OutboundMailBmc},
+    },
+    monitoring::{self, MailDeliveryOutcome},
+};
+
+// fallback for mails queued by other instances, or due for a retry
+const POLL_INTERVAL: Duration = Duration::from_secs(5);
+const BATCH_SIZE: u64 = 10;
+// longer than the mailers can take to send a mail
+const SEND_LEASE: time::Duration = time::Duration::minutes(5);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60 * 60);
+const SENT_RETENTION: time::Duration = time::Duration::days(7);
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+static WAKE_WORKER: LazyLock<Notify> = LazyLock::new(Notify::new);
+
+/// Wakes the delivery worker up, to send the mails queued by a transaction that just committed
+/// without waiting for the next poll.
+pub fn wake_worker() {
+    WAKE_WORKER.notify_one();
+}
+
+/// Spawns the worker delivering the queued mails through [`get_mailer`].
+///
+/// Failed deliveries are retried with an exponential backoff starting at
+/// `SERVICE_MAIL_RETRY_BASE_SECS`, until `SERVICE_MAIL_MAX_ATTEMPTS` attempts have failed and
+/// the mail is marked as dead. Sent mails are deleted after a week.
+pub fn spawn_worker(mut mm: ModelManager) {
+    tokio::spawn(async move {
+        let mut last_cleanup = tokio::time::Instant::now();
+        loop {
+            loop {
+                match deliver_due(&mut mm).await {
+                    Ok(claimed) if claimed as u64 == BATCH_SIZE => continue,
+                    Ok(_) => break,
+                    Err(err) => {
+                        tracing::warn!("{:<12} -- Failed to deliver mails: {}", "OUTBOX", err);
+                        break;
+                    }
+                }
+            }
+
+            if last_cleanup.elapsed() >= CLEANUP_INTERVAL {
+                last_cleanup = tokio::time::Instant::now();
+                let before = OffsetDateTime::now_utc() - SENT_RETENTION;
+                if let Err(err) = OutboundMailBmc::delete_sent_before(&mut mm, before).await {
+                    tracing::warn!("{:<12} -- Failed to clean up sent mails: {}", "OUTBOX", err);
+                }
+            }
+
+            let _ = tokio::time::timeout(POLL_INTERVAL, WAKE_WORKER.notified()).await;
+        }
+    });
+}
+
+/// Sends a batch of due mails, returning how many were claimed.
+async fn deliver_due(mm: &mut ModelManager) -> Result<usize> {
+    let mut tx = mm.tx().await?;
+    let mails = OutboundMailBmc::claim_due(&mut tx, BATCH_SIZE, SEND_LEASE)
+        .always_send()
+        .await?;
+    tx.commit().await?;
+
+    let claimed = mails.len();
+    for mail in mails {
+        deliver(mm, mail).await?;
+    }
+    Ok(claimed)
+}
+
+async fn deliver(mm: &mut ModelManager, mail: DueOutboundMail) -> Result<()> {
+    let error = match send(&mail).await {
+        Ok(()) => {
+            tracing::debug!("{:<12} -- Sent mail {}", "OUTBOX", mail.id);
+            monitoring::record_mail_delivery(MailDeliveryOutcome::Sent);
+            OutboundMailBmc::mark_sent(mm, mail.id).await?;
+            return Ok(());
+        }
+        Err(err) => err.to_string(),
+    };
+
+    let config = AppConfig::get();
+    let attempts = u32::try_from(mail.attempts).unwrap_or_default();
+    if attempts >= config.SERVICE_MAIL_MAX_ATTEMPTS {
+        tracing::error!(
+            "{:<12} -- Giving up on mail {} after {} attempts: {}",
+            "OUTBOX",
+            mail.id,
+            attempts,
+            error
+        );
+        monitoring::record_mail_delivery(MailDeliveryOutcome::Dead);
+        OutboundMailBmc::mark_dead(mm, mail.id, error).await?;
+    } else {
+        let delay = retry_delay(attempts, config.SERVICE_MAIL_RETRY_BASE_DURATION);
+        tracing::warn!(
+            "{:<12} -- Failed to send mail {}, retrying in {}s: {}",
+            "OUTBOX",
+            mail.id,
+            delay.as_secs(),
+            error
+        );
+        monitoring::record_mail_delivery(MailDeliveryOutcome::Retried);
+        let next_attempt_at =
+            OffsetDateTime::now_utc() + time::Duration::try_from(delay).expect("negative duration");
+        OutboundMailBmc::schedule_retry(mm, mail.id, error, next_attempt_at).await?;
+    }
+    Ok(())
+}
+
+async fn send(mail: &DueOutboundMail) -> Result<()> {
+    let email = RenderedEmail {
+        subject: mail.subject.clone(),
+        html: Raw::dangerously_create(decrypt_body(mail.html_body.as_deref())?).rendered(),
+        text: decrypt_body(mail.text_body.as_deref())?,
+    };
+
+    get_mailer()
+        .send_mail(&mail.recipient, &mail.sender, email)
+        .await?;
+    Ok(())
+}
+
+fn decrypt_body(body: Option<&[u8]>) -> Result<String> {
+    let body = SymmetricCipher::get_from_config().decrypt(body.unwrap_or_default())?;
+    String::from_utf8(body).map_err(|err| crate::Error::Unexpected(err.into()))
+}
+
+/// The delay before retrying a mail whose `attempts`-th delivery attempt failed.
+fn retry_delay(attempts: u32, base: Duration) -> Duration {
+    2u32.checked_pow(attempts.saturating_sub(1))
+        .and_then(|factor| base.checked_mul(factor))
+        .map_or(MAX_RETRY_DELAY, |delay| delay.min(MAX_RETRY_DELAY))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn retry_delay_doubles_up_to_max() {
+        let base = Duration::from_secs(30);
+        assert_eq!(retry_delay(1, base), Duration::from_secs(30));
+        assert_eq!(retry_delay(2, base), Duration::from_secs(60));
+        assert_eq!(retry_delay(4, base), Duration::from_secs(240));
+        assert_eq!(retry_delay(8, base), MAX_RETRY_DELAY);
+        assert_eq!(retry_delay(100, base), MAX_RETRY_DELAY);
+    }
+
+    #[tokio::test]
+    async fn send_delivers_decrypted_bodies() {
+        let cipher = SymmetricCipher::get_from_config();
+        let mail = DueOutboundMail {
+            id: 1,
+            recipient: "outbox-send@example.com".into(),
+            sender: "accounts@nrs.dev".into(),
+            subject: "Hello".into(),
+            html_body: Some(cipher.encrypt(b"<p>Hi</p>").unwrap()),
+            text_body: Some(cipher.encrypt(b"Hi").unwrap()),
+            attempts: 1,
+        };
+        send(&mail).await.unwrap();
+
+        let captured = crate::mail::capture_mailer()
+            .mails()
+            .into_iter()
+            .find(|captured| captured.to == mail.recipient)
+            .unwrap();
+        assert_eq!(captured.from, "accounts@nrs.dev");
+        assert_eq!(captured.email.subject, "Hello");
+        assert_eq!(captured.email.html.as_inner(), "<p>Hi</p>");
+        assert_eq!(captured.email.text, "Hi");
+    }
+}
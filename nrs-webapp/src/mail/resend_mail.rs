@@ -1,5 +1,5 @@
 use async_trait::async_trait;
-use hypertext::Rendered;
+use nrs_webapp_frontend::views::email::RenderedEmail;
 use resend_rs::{Resend, types::CreateEmailBaseOptions};
 
 use super::Result;
@@ -25,14 +25,13 @@ impl ResendMailer {
 
 #[async_trait]
 impl Mailer for ResendMailer {
-    /// Sends an HTML email using the Resend client.
+    /// Sends an email using the Resend client.
     ///
-    /// Builds and email with the given sender, single recipient, subject, and HTML body, then dispatches it via the inner Resend client.
+    /// Builds an email with the given sender, single recipient, and the subject, HTML and plain-text bodies of `email`, then dispatches it via the inner Resend client.
     ///
     /// `to` — recipient email address.
     /// `from` — sender email address.
-    /// `subject` — message subject.
-    /// `html_body` — HTML content wrapped in `Rendered<String>`.
+    /// `email` — the rendered subject and bodies.
     ///
     /// # Returns
     ///
@@ -41,24 +40,24 @@ impl Mailer for ResendMailer {
     /// # Examples
     ///
     /// ```no_run
-    /// # use crate::mail::resend_mail::ResendMailer;
-    /// # use hypertext::Rendered;
+    /// # use crate::mail::{Mailer, resend_mail::ResendMailer};
+    /// # use hypertext::prelude::*;
+    /// # use nrs_webapp_frontend::views::email::RenderedEmail;
     /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
     /// let mailer = ResendMailer::new("RE_SEND_API_KEY");
-    /// let body = Rendered::from(String::from("<p>Hello</p>"));
-    /// mailer.send_mail("recipient@example.com", "sender@example.com", "Greetings", body).await?;
+    /// let email = RenderedEmail {
+    ///     subject: "Greetings".into(),
+    ///     html: rsx! { <p>"Hello"</p> }.render(),
+    ///     text: "Hello".into(),
+    /// };
+    /// mailer.send_mail("recipient@example.com", "sender@example.com", email).await?;
     /// # Ok(()) }
     /// ```
-    async fn send_mail(
-        &self,
-        to: &str,
-        from: &str,
-        subject: &str,
-        html_body: Rendered<String>,
-    ) -> Result<()> {
-        let email = CreateEmailBaseOptions::new(from, [to], subject)
-            .with_html(html_body.as_inner().as_str());
-        self.0.emails.send(email).await?;
+    async fn send_mail(&self, to: &str, from: &str, email: RenderedEmail) -> Result<()> {
+        let options = CreateEmailBaseOptions::new(from, [to], email.subject)
+            .with_html(email.html.as_inner().as_str())
+            .with_text(&email.text);
+        self.0.emails.send(options).await?;
         Ok(())
     }
 }
@@ -0,0 +1,217 @@
+use async_trait::async_trait;
+use lettre::{
+    AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor,
+    message::MultiPart,
+    transport::smtp::{AsyncSmtpTransportBuilder, authentication::Credentials},
+};
+
+use nrs_webapp_frontend::views::email::RenderedEmail;
+
+use super::Result;
+use crate::{
+    config::{SmtpConfig, SmtpTlsMode},
+    mail::Mailer,
+};
+
+/// Sends the emails through an SMTP relay, such as a self-hosted Postfix.
+pub struct SmtpMailer(AsyncSmtpTransport<Tokio1Executor>);
+
+impl SmtpMailer {
+    /// Create a mailer for the relay described by `config`.
+    ///
+    /// No connection is made until the first email is sent.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the TLS parameters cannot be built for `config.host`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// let config = AppConfig::get().SMTP.as_ref().unwrap();
+    /// let mailer = SmtpMailer::from_config(config)?;
+    /// ```
+    pub fn from_config(config: &SmtpConfig) -> Result<Self> {
+        let mut builder: AsyncSmtpTransportBuilder = match config.tls {
+            SmtpTlsMode::StartTls => {
+                AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&config.host)?
+            }
+            SmtpTlsMode::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(&config.host)?,
+            SmtpTlsMode::None => {
+                AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(&config.host)
+            }
+        };
+        if let Some(port) = config.port {
+            builder = builder.port(port);
+        }
+        if let (Some(username), Some(password)) = (&config.username, &config.password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+        Ok(Self(builder.build()))
+    }
+}
+
+#[async_trait]
+impl Mailer for SmtpMailer {
+    /// Sends an email through the SMTP relay, as a `multipart/alternative` message with the
+    /// plain-text and HTML bodies.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `to` or `from` is not a valid address, or if the relay rejects the
+    /// message or cannot be reached.
+    async fn send_mail(&self, to: &str, from: &str, email: RenderedEmail) -> Result<()> {
+        let message = Message::builder()
+            .from(from.parse()?)
+            .to(to.parse()?)
+            .subject(email.subject)
+            .multipart(MultiPart::alternative_plain_html(
+                email.text,
+                email.html.into_inner(),
+            ))?;
+        self.0.send(message).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::{Engine, prelude::BASE64_STANDARD};
+    use hypertext::{Rendered, prelude::*};
+    use tokio::{
+        io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+        net::TcpListener,
+        task::JoinHandle,
+    };
+
+    use super::*;
+
+    /// A minimal SMTP server accepting a single message, returning the commands and data it
+    /// received once the message is accepted.
+    async fn smtp_sink() -> (u16, JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let handle = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let (reader, mut writer) = stream.into_split();
+            let mut lines = BufReader::new(reader).lines();
+            let mut transcript = String::new();
+
+            writer
+                .write_all(b"220 localhost ESMTP sink\r\n")
+                .await
+                .unwrap();
+            while let Some(line) = lines.next_line().await.unwrap() {
+                transcript.push_str(&line);
+                transcript.push('\n');
+
+                let command = line.to_ascii_uppercase();
+                let reply: &[u8] = if command.starts_with("EHLO") {
+                    b"250-localhost\r\n250 AUTH PLAIN LOGIN\r\n"
+                } else if command.starts_with("AUTH") {
+                    b"235 2.7.0 Authentication successful\r\n"
+                } else if command.starts_with("DATA") {
+                    b"354 End data with <CR><LF>.<CR><LF>\r\n"
+                } else if line == "." {
+                    writer.write_all(b"250 2.0.0 Queued\r\n").await.unwrap();
+                    break;
+                } else if command.starts_with("MAIL") || command.starts_with("RCPT") {
+                    b"250 2.1.0 Ok\r\n"
+                } else {
+                    // message content
+                    continue;
+                };
+                writer.write_all(reply).await.unwrap();
+            }
+            transcript
+        });
+
+        (port, handle)
+    }
+
+    fn sample_email(html: Rendered<String>) -> RenderedEmail {
+        RenderedEmail {
+            subject: "Hello there".into(),
+            text: "Hi, alice".into(),
+            html,
+        }
+    }
+
+    fn sink_config(port: u16) -> SmtpConfig {
+        SmtpConfig {
+            host: "127.0.0.1".into(),
+            port: Some(port),
+            tls: SmtpTlsMode::None,
+            username: None,
+            password: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn send_mail_delivers_text_and_html_alternatives() {
+        let (port, sink) = smtp_sink().await;
+        let mailer = SmtpMailer::from_config(&sink_config(port)).unwrap();
+
+        mailer
+            .send_mail(
+                "alice@example.com",
+                "accounts@nrs.dev",
+                sample_email(rsx! { <p>"Hi, alice"</p> }.render()),
+            )
+            .await
+            .unwrap();
+
+        let transcript = sink.await.unwrap();
+        assert!(transcript.contains("MAIL FROM:<accounts@nrs.dev>"));
+        assert!(transcript.contains("RCPT TO:<alice@example.com>"));
+        assert!(transcript.contains("Subject: Hello there"));
+        assert!(transcript.contains("Content-Type: multipart/alternative"));
+        assert!(transcript.contains("Content-Type: text/plain"));
+        assert!(transcript.contains("Content-Type: text/html"));
+        let text_at = transcript.find("\nHi, alice\n").unwrap();
+        let html_at = transcript.find("<p>Hi, alice</p>").unwrap();
+        // the last alternative is the preferred one
+        assert!(text_at < html_at);
+        assert!(!transcript.contains("AUTH"));
+    }
+
+    #[tokio::test]
+    async fn send_mail_authenticates_with_credentials() {
+        let (port, sink) = smtp_sink().await;
+        let config = SmtpConfig {
+            username: Some("nrs".into()),
+            password: Some("secret".into()),
+            ..sink_config(port)
+        };
+        let mailer = SmtpMailer::from_config(&config).unwrap();
+
+        mailer
+            .send_mail(
+                "alice@example.com",
+                "accounts@nrs.dev",
+                sample_email(rsx! { <p>"Hi"</p> }.render()),
+            )
+            .await
+            .unwrap();
+
+        let transcript = sink.await.unwrap();
+        let credentials = BASE64_STANDARD.encode("\0nrs\0secret");
+        assert!(transcript.contains(&format!("AUTH PLAIN {credentials}")));
+    }
+
+    #[tokio::test]
+    async fn send_mail_rejects_invalid_address() {
+        let mailer = SmtpMailer::from_config(&sink_config(25)).unwrap();
+
+        let result = mailer
+            .send_mail(
+                "not an address",
+                "accounts@nrs.dev",
+                sample_email(rsx! { <p>"Hi"</p> }.render()),
+            )
+            .await;
+
+        assert!(matches!(result, Err(crate::mail::Error::Address(_))));
+    }
+}
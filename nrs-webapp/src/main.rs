@@ -3,7 +3,12 @@ use std::net::SocketAddr;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 pub use crate::error::{Error, Result};
-use crate::{model::ModelManager, routes::router};
+use crate::{
+    config::{AppConfig, LogFormat},
+    model::ModelManager,
+    rate_limit::RateLimiter,
+    routes::router,
+};
 
 #[cfg(debug_assertions)]
 mod _dev_utils;
@@ -15,13 +20,53 @@ pub mod extract;
 pub mod mail;
 pub mod middleware;
 pub mod model;
+pub mod monitoring;
+pub mod rate_limit;
 pub mod routes;
 pub mod toasts;
 pub mod validate;
 
-/// Starts the HTTP server: initializes logging, optional development utilities, the model manager, routes, and runs the Axum service loop.
+/// What the binary was asked to do, parsed from its arguments.
+#[derive(Debug, PartialEq, Eq)]
+enum Command {
+    /// Run the HTTP server, the default.
+    Serve {
+        /// Drop and recreate the dev database before starting, only available in debug builds.
+        reset_dev_db: bool,
+    },
+    /// Apply the pending database migrations and exit.
+    Migrate,
+}
+
+impl Command {
+    const USAGE: &str = "Usage: nrs-webapp [serve [--reset-dev-db] | migrate]";
+
+    fn parse(args: impl IntoIterator<Item = String>) -> anyhow::Result<Self> {
+        let args = args.into_iter().collect::<Vec<_>>();
+        let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+        let command = match args.as_slice() {
+            [] | ["serve"] => Self::Serve {
+                reset_dev_db: false,
+            },
+            ["--reset-dev-db"] | ["serve", "--reset-dev-db"] if cfg!(debug_assertions) => {
+                Self::Serve { reset_dev_db: true }
+            }
+            ["migrate"] => Self::Migrate,
+            _ => anyhow::bail!("Invalid arguments {args:?}\n{}", Self::USAGE),
+        };
+        Ok(command)
+    }
+}
+
+/// Runs the command given in the arguments, serving HTTP requests by default.
 ///
-/// This function configures tracing from the environment, runs debug-only development setup when built in debug mode, constructs the ModelManager, builds the application routes, binds a TCP listener on 0.0.0.0:3621, logs the listening address, and serves HTTP requests until shutdown.
+/// `migrate` applies the pending database migrations and exits. Otherwise the server starts: it
+/// configures tracing from the environment (see [`init_tracing`]), brings the database schema
+/// up to date (or only checks it, see `SERVICE_DB_MIGRATE_ON_STARTUP`), grants the admin role to
+/// `SERVICE_BOOTSTRAP_ADMIN_USERNAME` if nobody has it, spawns the background
+/// workers, binds a TCP listener on 0.0.0.0:3621, and serves HTTP requests until shutdown.
+///
+/// In debug builds, `--reset-dev-db` drops, recreates and seeds the dev database first.
 ///
 /// # Returns
 ///
@@ -32,24 +77,41 @@ pub mod validate;
 /// ```no_run
 /// // Run the server:
 /// // cargo run --bin nrs-webapp
+/// // Apply the migrations only:
+/// // cargo run --bin nrs-webapp -- migrate
 /// ```
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
-    tracing_subscriber::registry()
-        .with(tracing_subscriber::EnvFilter::try_from_default_env()?)
-        .with(
-            // Disable timestamps and targets for cleaner output during development
-            // TODO: Adjust this for production use
-            tracing_subscriber::fmt::layer()
-                .with_target(false)
-                .without_time(),
-        )
-        .init();
-
-    #[cfg(debug_assertions)]
-    _dev_utils::init_dev().await;
+    init_tracing(LogFormat::from_env()?)?;
+
+    let reset_dev_db = match Command::parse(std::env::args().skip(1))? {
+        Command::Serve { reset_dev_db } => reset_dev_db,
+        Command::Migrate => {
+            let applied = ModelManager::new().await?.migrate().await?;
+            tracing::info!("Applied {applied} migrations");
+            return Ok(());
+        }
+    };
+
+    // only ever set in debug builds
+    if reset_dev_db {
+        #[cfg(debug_assertions)]
+        _dev_utils::init_dev().await;
+    }
+
+    monitoring::install_recorder()?;
 
     let mm = ModelManager::new().await?;
+    if AppConfig::get().SERVICE_DB_MIGRATE_ON_STARTUP {
+        mm.migrate().await?;
+    } else {
+        mm.check_migrations().await?;
+    }
+    auth::bootstrap::bootstrap_admin(&mm).await?;
+
+    RateLimiter::get_from_config().spawn_cleanup(mm.clone());
+    auth::lockout::spawn_cleanup(mm.clone());
+    mail::outbox::spawn_worker(mm.clone());
     let routes = router(mm.clone()).with_state(mm);
 
     let addr = "0.0.0.0:3621";
@@ -63,3 +125,59 @@ async fn main() -> anyhow::Result<()> {
     .await?;
     Ok(())
 }
+
+/// Sets up the global tracing subscriber, filtered by `RUST_LOG`, writing the logs to stdout in
+/// `format`.
+fn init_tracing(format: LogFormat) -> anyhow::Result<()> {
+    let registry =
+        tracing_subscriber::registry().with(tracing_subscriber::EnvFilter::try_from_default_env()?);
+    match format {
+        LogFormat::Pretty => {
+            let layer = tracing_subscriber::fmt::layer().with_target(false);
+            // timestamps only clutter the output during development
+            #[cfg(debug_assertions)]
+            let layer = layer.without_time();
+            registry.with(layer).init();
+        }
+        LogFormat::Json => registry
+            .with(
+                tracing_subscriber::fmt::layer()
+                    .json()
+                    .flatten_event(true)
+                    .with_current_span(true)
+                    .with_span_list(false),
+            )
+            .init(),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> anyhow::Result<Command> {
+        Command::parse(args.iter().map(ToString::to_string))
+    }
+
+    #[test]
+    fn command_parse_defaults_to_serve() {
+        let serve = Command::Serve {
+            reset_dev_db: false,
+        };
+        assert_eq!(parse(&[]).unwrap(), serve);
+        assert_eq!(parse(&["serve"]).unwrap(), serve);
+        assert_eq!(parse(&["migrate"]).unwrap(), Command::Migrate);
+        // the dev database can only be reset by debug builds
+        if cfg!(debug_assertions) {
+            assert_eq!(
+                parse(&["serve", "--reset-dev-db"]).unwrap(),
+                Command::Serve { reset_dev_db: true }
+            );
+        } else {
+            assert!(parse(&["serve", "--reset-dev-db"]).is_err());
+        }
+        assert!(parse(&["migrate", "--reset-dev-db"]).is_err());
+        assert!(parse(&["unknown"]).is_err());
+    }
+}
@@ -1,3 +1,7 @@
+pub mod mw_csrf;
+pub mod mw_metrics;
+pub mod mw_rate_limit;
 pub mod mw_req_session;
 pub mod mw_req_stamp;
 pub mod mw_res_map;
+pub mod mw_sec_headers;
@@ -0,0 +1,103 @@
+use axum::{
+    extract::Request,
+    http::{HeaderName, Method, header},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use axum_extra::extract::{
+    SignedCookieJar,
+    cookie::{Cookie, SameSite},
+};
+use subtle::ConstantTimeEq;
+
+use crate::{Error, Result, crypt::token::Token, middleware::mw_sec_headers::CSP_REPORT_PATH};
+
+const CSRF_COOKIE_NAME: &str = "nrs_csrf_token";
+
+// posted by browsers on their own, without any page involved
+const EXEMPT_PATHS: &[&str] = &[CSP_REPORT_PATH];
+
+/// Header carrying the CSRF token, set on every HTMX request through `hx-headers` in `document`.
+pub const CSRF_HEADER_NAME: HeaderName = HeaderName::from_static("x-csrf-token");
+
+/// The CSRF token of the browser session, available in the request extensions.
+#[derive(Debug, Clone)]
+pub struct CsrfToken(pub String);
+
+/// Middleware that makes sure every browser session has a CSRF token.
+///
+/// The token is read from the signed `nrs_csrf_token` cookie, or generated and set as a session
+/// cookie if there is none. It is inserted into the request extensions as [`CsrfToken`], so that
+/// `DocProps` can hand it to the rendered document. This layer has to wrap the response mapper,
+/// so that error pages carry the token as well.
+pub async fn mw_csrf_token(jar: SignedCookieJar, mut req: Request, next: Next) -> Result<Response> {
+    tracing::debug!("{:<12} -- mw_csrf_token", "MIDDLEWARE");
+
+    let (jar, token) = match jar.get(CSRF_COOKIE_NAME) {
+        Some(cookie) => (jar, cookie.value().to_string()),
+        None => {
+            let token = Token::generate()?.to_string();
+            let jar = jar.add(
+                Cookie::build((CSRF_COOKIE_NAME, token.clone()))
+                    .http_only(true)
+                    .secure(!cfg!(debug_assertions))
+                    .same_site(SameSite::Lax)
+                    .path("/"),
+            );
+            (jar, token)
+        }
+    };
+
+    req.extensions_mut().insert(CsrfToken(token));
+    Ok((jar, next.run(req).await).into_response())
+}
+
+/// Middleware that rejects state-changing requests without the CSRF token of the session.
+///
+/// Requests with a method other than `GET`, `HEAD`, `OPTIONS` and `TRACE` must send the token
+/// from [`mw_csrf_token`] in the `X-CSRF-Token` header. Requests authenticated with an
+/// `Authorization: Bearer` header are exempt: browsers never attach that header on their own, so
+/// they cannot be forged cross-site. So are the endpoints that browsers post to by themselves,
+/// such as the CSP violation reports.
+///
+/// # Errors
+///
+/// Returns `Error::CsrfTokenMismatch` without calling the handler when the header is missing or
+/// does not match.
+pub async fn mw_csrf_check(req: Request, next: Next) -> Result<Response> {
+    tracing::debug!("{:<12} -- mw_csrf_check", "MIDDLEWARE");
+
+    if is_safe_method(req.method())
+        || has_bearer_auth(&req)
+        || EXEMPT_PATHS.contains(&req.uri().path())
+    {
+        return Ok(next.run(req).await);
+    }
+
+    let expected = req.extensions().get::<CsrfToken>().map(|t| t.0.as_bytes());
+    let actual = req
+        .headers()
+        .get(&CSRF_HEADER_NAME)
+        .map(|value| value.as_bytes());
+
+    match (expected, actual) {
+        (Some(expected), Some(actual)) if bool::from(expected.ct_eq(actual)) => {
+            Ok(next.run(req).await)
+        }
+        _ => Err(Error::CsrfTokenMismatch),
+    }
+}
+
+fn is_safe_method(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::TRACE
+    )
+}
+
+fn has_bearer_auth(req: &Request) -> bool {
+    req.headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("Bearer "))
+}
@@ -0,0 +1,29 @@
+use std::time::Instant;
+
+use axum::{
+    extract::{MatchedPath, Request},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::monitoring;
+
+// requests matching no route are counted together, so that scanners probing random paths do not
+// create a time series each
+const UNMATCHED_ROUTE: &str = "unmatched";
+
+/// Middleware that records the count and latency of the requests, by route pattern and status.
+///
+/// This layer has to wrap the response mapper, so that the status of the error pages is counted.
+pub async fn mw_metrics(matched_path: Option<MatchedPath>, req: Request, next: Next) -> Response {
+    let method = req.method().clone();
+    let start = Instant::now();
+
+    let resp = next.run(req).await;
+
+    let route = matched_path
+        .as_ref()
+        .map_or(UNMATCHED_ROUTE, MatchedPath::as_str);
+    monitoring::record_request(&method, route, resp.status(), start.elapsed());
+    resp
+}
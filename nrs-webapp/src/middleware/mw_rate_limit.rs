@@ -0,0 +1,45 @@
+use axum::{
+    extract::{MatchedPath, Request, State},
+    middleware::Next,
+    response::Response,
+};
+use axum_client_ip::ClientIp;
+
+use crate::{
+    Result, auth::session::Session, config::RateLimitKeyKind, model::ModelManager,
+    rate_limit::RateLimiter,
+};
+
+/// Middleware that applies the rate-limit policies configured for the matched route.
+///
+/// Policies are looked up by `"<METHOD> <route path>"` (e.g. `"POST /auth/login"`) in
+/// `AppConfig::RATE_LIMIT.routes`. Each policy counts the request either per client IP or per
+/// session; requests without a session fall back to the client IP. Policies keyed by a value from
+/// the request body (username, email) are checked by the handlers themselves.
+///
+/// # Errors
+///
+/// Returns `Error::RateLimitExceeded` without calling the handler when any policy is exhausted.
+pub async fn mw_rate_limit(
+    State(mm): State<ModelManager>,
+    ClientIp(ip_addr): ClientIp,
+    session: Option<Session>,
+    matched_path: Option<MatchedPath>,
+    req: Request,
+    next: Next,
+) -> Result<Response> {
+    tracing::debug!("{:<12} -- mw_rate_limit", "MIDDLEWARE");
+
+    if let Some(matched_path) = matched_path {
+        let limiter = RateLimiter::get_from_config();
+        for policy in limiter.route_policies(req.method(), matched_path.as_str()) {
+            let key = match (policy.key, &session) {
+                (RateLimitKeyKind::Session, Some(session)) => session.user_id.to_string(),
+                _ => ip_addr.to_string(),
+            };
+            limiter.check_policy(&mm, policy, &key).await?;
+        }
+    }
+
+    Ok(next.run(req).await)
+}
@@ -1,17 +1,28 @@
-use axum::{extract::Request, middleware::Next, response::Response};
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
 use axum_extra::extract::SignedCookieJar;
 
 use crate::{
+    Result,
     auth::{get_auth_cookie, session::Session},
     crypt::session_token::SessionToken,
+    model::{ModelManager, user::UserBmc},
 };
 
 /// Middleware that attaches an authenticated Session to request extensions when a valid auth cookie is present.
 ///
-/// If an auth cookie exists and the session token parses and validates successfully, a `Session` constructed from the token
-/// is inserted into the request's extensions. The request is forwarded to the next handler regardless of
+/// If an auth cookie exists, the session token parses and validates successfully and the account of the user is still active
+/// (neither deleted nor disabled), a `Session` constructed from the token
+/// is inserted into the request's extensions, and its user ID is recorded on the request span. The request is forwarded to the next handler regardless of
 /// whether a session was inserted; the middleware returns the response produced by the next handler.
 ///
+/// # Errors
+///
+/// Returns a model error without calling the handler when the user cannot be loaded.
+///
 /// # Examples
 ///
 /// ```
@@ -21,16 +32,23 @@ use crate::{
 ///     .route("/", get(|| async { "ok" }))
 ///     .layer(axum::middleware::from_fn(crate::middleware::mw_req_session));
 /// ```
-pub async fn mw_req_session(jar: SignedCookieJar, mut req: Request, next: Next) -> Response {
+pub async fn mw_req_session(
+    State(mut mm): State<ModelManager>,
+    jar: SignedCookieJar,
+    mut req: Request,
+    next: Next,
+) -> Result<Response> {
     tracing::debug!("{:<12} -- mw_req_session", "MIDDLEWARE");
 
     if let Some(token) = get_auth_cookie(&jar)
         && let Ok(token) = token.parse::<SessionToken>()
         && let Ok(user_id) = token.validate()
+        && UserBmc::is_active(&mut mm, user_id).await?
     {
         let session = Session::new(user_id);
         tracing::debug!("Got session {session:?}");
+        tracing::Span::current().record("user_id", tracing::field::display(user_id));
         req.extensions_mut().insert(session);
     }
-    next.run(req).await
+    Ok(next.run(req).await)
 }
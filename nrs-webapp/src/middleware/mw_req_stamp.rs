@@ -1,30 +1,52 @@
 use crate::Result;
 use axum::{
     extract::{FromRequestParts, Request},
-    http::request::Parts,
+    http::{HeaderName, HeaderValue, request::Parts},
     middleware::Next,
     response::Response,
 };
 use time::OffsetDateTime;
+use tracing::{Instrument, field::Empty};
 use uuid::Uuid;
 
+pub const REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
+
 #[derive(Debug, Clone)]
 pub struct ReqStamp {
     pub uuid: Uuid,
     pub time_in: OffsetDateTime,
 }
 
+/// Stamps the request with a fresh UUID and its arrival time, and handles it inside a `request`
+/// tracing span.
+///
+/// The span carries the request UUID, method and path. The `user_id` field is recorded by
+/// `mw_req_session`, and `status` and `latency_ms` by `mw_res_mapper`. The UUID is returned to
+/// the client in the `X-Request-Id` header, so that a report can be matched with the logs.
 pub async fn mw_req_stamp(mut req: Request, next: Next) -> Result<Response> {
-    tracing::debug!("{:<12} -- mw_req_stamp", "MIDDLEWARE");
-
     let stamp = ReqStamp {
         uuid: Uuid::new_v4(),
         time_in: OffsetDateTime::now_utc(),
     };
 
+    let span = tracing::info_span!(
+        "request",
+        id = %stamp.uuid,
+        method = %req.method(),
+        path = %req.uri().path(),
+        user_id = Empty,
+        status = Empty,
+        latency_ms = Empty,
+    );
+    span.in_scope(|| tracing::debug!("{:<12} -- mw_req_stamp", "MIDDLEWARE"));
+
+    let request_id =
+        HeaderValue::try_from(stamp.uuid.to_string()).expect("a UUID is a valid header value");
     req.extensions_mut().insert(stamp);
 
-    Ok(next.run(req).await)
+    let mut resp = next.run(req).instrument(span).await;
+    resp.headers_mut().insert(REQUEST_ID_HEADER, request_id);
+    Ok(resp)
 }
 
 impl<S: Send + Sync> FromRequestParts<S> for ReqStamp {
@@ -1,14 +1,18 @@
 use std::sync::Arc;
 
 use axum::{
-    http::{Method, Uri},
+    http::{HeaderValue, Method, Uri, header},
     response::{IntoResponse, Response},
 };
 use axum_htmx::HxRequest;
-use nrs_webapp_frontend::views::{self, error::ClientError};
-use serde_json::{Value, json};
+use nrs_webapp_frontend::views;
+use serde_json::json;
+use time::OffsetDateTime;
 
-use crate::{Error, extract::doc_props::DocProps, middleware::mw_req_stamp::ReqStamp};
+use crate::{
+    Error, error::retry_after_secs, extract::doc_props::DocProps,
+    middleware::mw_req_stamp::ReqStamp,
+};
 
 pub struct RequestTitle(pub String);
 
@@ -30,38 +34,70 @@ pub async fn mw_res_mapper(
     let client_error_parts =
         error.map(|e| e.get_client_error(title.map(|t| t.into()), req_stamp.uuid.to_string()));
 
-    let client_error = client_error_parts.as_ref().map(|(_, err)| err);
+    // kept for the log line, as the error response takes them
+    let error_type = error.map(|e| e.to_string());
+    let client_error = client_error_parts
+        .as_ref()
+        .map(|(_, err)| json!(err).to_string());
+
+    let retry_after = match error {
+        Some(Error::RateLimitExceeded { retry_after, .. }) => Some(retry_after_secs(*retry_after)),
+        _ => None,
+    };
 
-    // TODO: log line
-    println!(
-        "{:<12} -- {}",
-        "REQ-LOG-LINE",
-        to_log_line(method, uri, req_stamp, error, client_error)
-    );
+    let response_error = client_error_parts.map(|(code, error)| {
+        let mut resp = views::error::error(code, hx_request, &doc_props, &error).into_response();
+        if let Some(retry_after) = retry_after {
+            resp.headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+        }
+        resp
+    });
+
+    let resp = response_error.unwrap_or(resp);
+    log_request(&method, &uri, &req_stamp, &resp, error_type, client_error);
 
     // during development, print a newline to separate requests
     #[cfg(debug_assertions)]
     tracing::debug!("DONE-REQUEST");
 
-    let response_error = client_error_parts.map(|(code, error)| {
-        views::error::error(code, hx_request, &doc_props, &error).into_response()
-    });
-
-    response_error.unwrap_or(resp)
+    resp
 }
 
-fn to_log_line(
-    method: Method,
-    uri: Uri,
-    req_stamp: ReqStamp,
-    error: Option<&Error>,
-    client_error: Option<&ClientError>,
-) -> Value {
-    json!({
-        "uri": uri.to_string(),
-        "uuid": req_stamp.uuid,
-        "method": method.to_string(),
-        "error_type": error.map(|e| e.to_string()),
-        "client_error": client_error,
-    })
+/// Records the outcome of the request on its span, and logs it.
+fn log_request(
+    method: &Method,
+    uri: &Uri,
+    req_stamp: &ReqStamp,
+    resp: &Response,
+    error: Option<String>,
+    client_error: Option<String>,
+) {
+    let status = resp.status().as_u16();
+    let latency_ms = (OffsetDateTime::now_utc() - req_stamp.time_in).as_seconds_f64() * 1000.0;
+
+    let span = tracing::Span::current();
+    span.record("status", status);
+    span.record("latency_ms", latency_ms);
+
+    match error {
+        Some(error) => tracing::warn!(
+            status,
+            latency_ms,
+            error = %error,
+            client_error,
+            "{:<12} -- {} {}",
+            "REQ-LOG-LINE",
+            method,
+            uri
+        ),
+        None => tracing::info!(
+            status,
+            latency_ms,
+            "{:<12} -- {} {}",
+            "REQ-LOG-LINE",
+            method,
+            uri
+        ),
+    }
 }
@@ -0,0 +1,292 @@
+use sea_query::{Expr, ExprTrait, Order, Query, Value};
+use serde::Deserialize;
+use serde_json::Map;
+use sqlbindable::{FieldNames, Filter, SelectContext, TryIntoExpr, TryIntoExprError};
+use sqlx::{FromRow, types::Json};
+use strum::IntoStaticStr;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use super::Result;
+use crate::model::{entity::DbBmc, store::primary_store::PrimaryStore};
+
+/// A row as stored in the audit log, converted by `to_jsonb`.
+pub type RowSnapshot = Map<String, serde_json::Value>;
+
+// stands for the values of the redacted columns
+const REDACTED: &str = "[redacted]";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, Deserialize, IntoStaticStr)]
+#[sqlx(type_name = "AUDIT_ACTION")]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum AuditAction {
+    #[sqlx(rename = "CREATE")]
+    Create,
+    #[sqlx(rename = "UPDATE")]
+    Update,
+    #[sqlx(rename = "DELETE")]
+    Delete,
+}
+
+impl AuditAction {
+    /// Get the SQL enum string of this action: `"CREATE"`, `"UPDATE"` or `"DELETE"`.
+    pub fn to_enum_string(&self) -> &'static str {
+        match self {
+            AuditAction::Create => "CREATE",
+            AuditAction::Update => "UPDATE",
+            AuditAction::Delete => "DELETE",
+        }
+    }
+}
+
+impl From<AuditAction> for Expr {
+    fn from(action: AuditAction) -> Self {
+        Value::String(Some(action.to_enum_string().into())).cast_as("AUDIT_ACTION")
+    }
+}
+
+impl TryIntoExpr for AuditAction {
+    fn into_expr(self) -> core::result::Result<Expr, TryIntoExprError> {
+        Ok(self.into())
+    }
+}
+
+/// Who made a change, recorded along with it in the audit log.
+///
+/// Extracted from the request by the handlers; the default context, with every field unset,
+/// stands for the service itself, such as its background jobs.
+#[derive(Debug, Clone, Default)]
+pub struct AuditContext {
+    /// The logged in user, if any.
+    pub actor_id: Option<Uuid>,
+    /// The UUID given to the request by `mw_req_stamp`.
+    pub request_id: Option<Uuid>,
+    pub ip: Option<String>,
+}
+
+/// The account of the actor of an audit event.
+#[derive(Debug, FieldNames, FromRow)]
+pub struct AuditEventActor {
+    /// `None` when there is no actor, or when the actor deleted their account.
+    #[sqlx(rename = "actor.username")]
+    pub username: Option<String>,
+}
+
+/// An entry of the audit log, as shown to the admins.
+#[derive(Debug, FieldNames, FromRow)]
+pub struct AuditEvent {
+    pub id: i64,
+    pub actor_id: Option<Uuid>,
+    #[sqlx(flatten)]
+    #[field(join = "app_user", on = "actor_id")]
+    pub actor: AuditEventActor,
+    pub request_id: Option<Uuid>,
+    pub ip: Option<String>,
+    pub action: AuditAction,
+    pub entity_table: String,
+    pub entity_id: String,
+    /// The changed fields before the change, `None` for a creation.
+    pub before: Option<Json<RowSnapshot>>,
+    /// The changed fields after the change, `None` for a deletion.
+    pub after: Option<Json<RowSnapshot>>,
+    pub created_at: OffsetDateTime,
+}
+
+/// Narrows the audit log down to the changes of an entity, or made by a user.
+#[derive(Debug, Clone, Default, Filter)]
+#[filter(table = "audit_event")]
+pub struct AuditEventFilter {
+    pub entity_table: Option<String>,
+    pub entity_id: Option<String>,
+    pub actor_id: Option<Uuid>,
+}
+
+pub struct AuditEventBmc;
+
+impl DbBmc for AuditEventBmc {
+    const TABLE_NAME: &'static str = "audit_event";
+}
+
+impl AuditEventBmc {
+    /// Record the change of a row of the table of `B`, from `before` to `after`.
+    ///
+    /// Only the fields that changed are kept, with the values of
+    /// [`DbBmc::AUDIT_REDACTED_COLUMNS`] replaced by a placeholder. Updates that changed nothing
+    /// are not recorded.
+    pub(in crate::model) async fn record<B: DbBmc + ?Sized>(
+        ps: &mut impl PrimaryStore,
+        audit: &AuditContext,
+        action: AuditAction,
+        before: Option<&RowSnapshot>,
+        after: Option<&RowSnapshot>,
+    ) -> Result<()> {
+        let Some(entity_id) = after
+            .or(before)
+            .map(|row| entity_id(row, B::AUDIT_ID_COLUMNS))
+        else {
+            return Ok(());
+        };
+        let (before, after) = changed_fields(before, after, B::AUDIT_REDACTED_COLUMNS);
+        if action == AuditAction::Update && after.as_ref().is_none_or(Map::is_empty) {
+            return Ok(());
+        }
+
+        let json =
+            |row: Option<RowSnapshot>| row.map(|row| Expr::val(serde_json::Value::Object(row)));
+        ps.query_with(
+            Query::insert()
+                .into_table(Self::TABLE_NAME)
+                .columns([
+                    "actor_id",
+                    "request_id",
+                    "ip",
+                    "action",
+                    "entity_table",
+                    "entity_id",
+                    "before",
+                    "after",
+                ])
+                .values_panic([
+                    audit.actor_id.into(),
+                    audit.request_id.into(),
+                    audit.ip.clone().into(),
+                    action.into(),
+                    B::TABLE_NAME.into(),
+                    entity_id.into(),
+                    json(before).unwrap_or_else(Expr::null),
+                    json(after).unwrap_or_else(Expr::null),
+                ]),
+        )
+        .execute()
+        .await?;
+        Ok(())
+    }
+
+    /// List the most recent audit events matching `filter`, newest first.
+    pub async fn list_recent(
+        ps: &mut impl PrimaryStore,
+        filter: AuditEventFilter,
+        limit: u64,
+    ) -> Result<Vec<AuditEvent>> {
+        let mut query = Query::select();
+        query
+            .from(Self::TABLE_NAME)
+            .select_fields::<AuditEvent>(Self::TABLE_NAME)
+            .cond_where(filter.into_condition()?)
+            .order_by((Self::TABLE_NAME, "id"), Order::Desc)
+            .limit(limit);
+
+        let events = ps.query_as_with::<AuditEvent>(&query).fetch_all().await?;
+        Ok(events)
+    }
+}
+
+/// The identifier of the audited `row`: the values of its `id_columns` as text, joined with `/`.
+fn entity_id(row: &RowSnapshot, id_columns: &[&str]) -> String {
+    id_columns
+        .iter()
+        .map(|column| match row.get(*column) {
+            Some(serde_json::Value::String(id)) => id.clone(),
+            Some(id) => id.to_string(),
+            None => String::new(),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Keep the fields of a change worth recording: every field of a created or deleted row, and
+/// the fields that differ between `before` and `after` for an updated one.
+///
+/// The values of the `redacted` fields are replaced by a placeholder, which still shows that
+/// they changed.
+fn changed_fields(
+    before: Option<&RowSnapshot>,
+    after: Option<&RowSnapshot>,
+    redacted: &[&str],
+) -> (Option<RowSnapshot>, Option<RowSnapshot>) {
+    let redact = |row: &RowSnapshot, keep: &dyn Fn(&str) -> bool| {
+        row.iter()
+            .filter(|(field, _)| keep(field))
+            .map(|(field, value)| {
+                let value = if redacted.contains(&field.as_str()) && !value.is_null() {
+                    REDACTED.into()
+                } else {
+                    value.clone()
+                };
+                (field.clone(), value)
+            })
+            .collect::<RowSnapshot>()
+    };
+
+    match (before, after) {
+        (Some(before), Some(after)) => {
+            let changed = |field: &str| before.get(field) != after.get(field);
+            (
+                Some(redact(before, &changed)),
+                Some(redact(after, &changed)),
+            )
+        }
+        (before, after) => (
+            before.map(|row| redact(row, &|_| true)),
+            after.map(|row| redact(row, &|_| true)),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn row(value: serde_json::Value) -> RowSnapshot {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn changed_fields_keeps_only_changes_of_updates() {
+        let before = row(json!({"id": "a", "title": "Old", "password_hash": "x", "n": 1}));
+        let after = row(json!({"id": "a", "title": "New", "password_hash": "y", "n": 1}));
+
+        let (before, after) = changed_fields(Some(&before), Some(&after), &["password_hash"]);
+        assert_eq!(
+            before.unwrap(),
+            row(json!({"title": "Old", "password_hash": REDACTED}))
+        );
+        assert_eq!(
+            after.unwrap(),
+            row(json!({"title": "New", "password_hash": REDACTED}))
+        );
+    }
+
+    #[test]
+    fn changed_fields_keeps_whole_created_and_deleted_rows() {
+        let created = row(json!({"id": "a", "password_hash": "x", "locked_until": null}));
+
+        let (before, after) =
+            changed_fields(None, Some(&created), &["password_hash", "locked_until"]);
+        assert_eq!(before, None);
+        assert_eq!(
+            after.unwrap(),
+            row(json!({"id": "a", "password_hash": REDACTED, "locked_until": null}))
+        );
+
+        let (before, after) = changed_fields(Some(&created), None, &[]);
+        assert_eq!(before.unwrap(), created);
+        assert_eq!(after, None);
+    }
+
+    #[test]
+    fn entity_id_converts_any_value_to_text() {
+        assert_eq!(entity_id(&row(json!({"id": "abc"})), &["id"]), "abc");
+        assert_eq!(entity_id(&row(json!({"id": 42})), &["id"]), "42");
+        assert_eq!(entity_id(&row(json!({"user_id": "u"})), &["id"]), "");
+    }
+
+    #[test]
+    fn entity_id_joins_composite_keys() {
+        let link = row(json!({"user_id": "u", "provider": "google", "revoked_at": null}));
+        assert_eq!(entity_id(&link, &["user_id", "provider"]), "u/google");
+    }
+}
@@ -0,0 +1,398 @@
+//! Keyset pagination.
+//!
+//! Rather than skipping `offset` rows, a page starts right after (or before) the row its
+//! [`Cursor`] was taken from, by comparing the sort key of the rows with the one in the cursor.
+//! Unlike offsets, this stays fast on large tables when the sort columns are indexed, and the
+//! pages do not shift when rows are inserted or deleted in between.
+
+use std::{fmt::Display, str::FromStr};
+
+use base64::{Engine, prelude::BASE64_URL_SAFE_NO_PAD};
+use sea_query::{Alias, Expr, ExprTrait, Order, Query, SelectStatement};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sqlx::{Decode, FromRow, Row, TypeInfo, ValueRef, postgres::PgTypeKind};
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+use uuid::Uuid;
+
+use crate::model::{Error, Result, SqlxDatabase, SqlxRow, store::primary_store::PrimaryStore};
+
+/// Prefix of the aliases of the sort columns added to the selected ones.
+const KEY_ALIAS_PREFIX: &str = "_keyset_";
+
+/// What to fetch a page of rows with.
+#[derive(Clone, Debug)]
+pub struct KeysetPayload {
+    /// The sort columns, to which the unique key of the table is appended to break the ties.
+    ///
+    /// The sort columns cannot be `NULL`.
+    pub order_by: Vec<(&'static str, Order)>,
+    /// Where the page starts, `None` for the first page.
+    pub cursor: Option<Cursor>,
+    pub limit: u64,
+    /// Whether to also count the rows of every page, which costs a scan of them.
+    pub with_total: bool,
+}
+
+/// The position of a page: the sort key of the row it starts after, and in which direction.
+///
+/// Its string form is opaque to the clients, which only pass it back.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Cursor {
+    /// Whether the page is the one before the row, rather than after it.
+    #[serde(rename = "b")]
+    backward: bool,
+    #[serde(rename = "k")]
+    key: Vec<KeyValue>,
+}
+
+/// A page of rows, with the cursors of its neighbours.
+#[derive(Debug)]
+pub struct Page<E> {
+    pub items: Vec<E>,
+    /// `None` on the last page.
+    pub next: Option<Cursor>,
+    /// `None` on the first page.
+    pub prev: Option<Cursor>,
+    /// The number of rows of all the pages, when requested.
+    pub total: Option<u64>,
+}
+
+/// The value of a sort column, in a cursor.
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+enum KeyValue {
+    Bool(bool),
+    Int(i64),
+    Text(String),
+    Uuid(Uuid),
+    Timestamp(#[serde_as(as = "Rfc3339")] OffsetDateTime),
+    /// The label of a value of the Postgres enum `type_name`.
+    Enum {
+        type_name: String,
+        label: String,
+    },
+}
+
+impl KeyValue {
+    /// Decode the value of the column `index` of `row`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `sqlx::Error::ColumnDecode` if the value is `NULL` or of an unsupported type.
+    fn decode(row: &SqlxRow, index: usize) -> sqlx::Result<Self> {
+        let raw = row.try_get_raw(index)?;
+        if raw.is_null() {
+            return Err(decode_error(index, "keyset sort columns cannot be NULL"));
+        }
+        let type_info = raw.type_info().into_owned();
+        if let PgTypeKind::Enum(_) = type_info.kind() {
+            let label = <String as Decode<SqlxDatabase>>::decode(raw).map_err(|source| {
+                sqlx::Error::ColumnDecode {
+                    index: index.to_string(),
+                    source,
+                }
+            })?;
+            return Ok(Self::Enum {
+                type_name: type_info.name().to_string(),
+                label,
+            });
+        }
+        let value = match type_info.name() {
+            "BOOL" => Self::Bool(row.try_get(index)?),
+            "INT2" => Self::Int(row.try_get::<i16, _>(index)?.into()),
+            "INT4" => Self::Int(row.try_get::<i32, _>(index)?.into()),
+            "INT8" => Self::Int(row.try_get(index)?),
+            "TEXT" | "VARCHAR" | "BPCHAR" | "NAME" => Self::Text(row.try_get(index)?),
+            "UUID" => Self::Uuid(row.try_get(index)?),
+            "TIMESTAMPTZ" => Self::Timestamp(row.try_get(index)?),
+            name => {
+                return Err(decode_error(
+                    index,
+                    &format!("unsupported keyset sort column type {name}"),
+                ));
+            }
+        };
+        Ok(value)
+    }
+
+    fn into_expr(self) -> Expr {
+        match self {
+            Self::Bool(value) => Expr::val(value),
+            Self::Int(value) => Expr::val(value),
+            Self::Text(value) => Expr::val(value),
+            Self::Uuid(value) => Expr::val(value),
+            Self::Timestamp(value) => Expr::val(value),
+            // the type name was checked to be an identifier when the cursor was parsed
+            Self::Enum { type_name, label } => Expr::val(label).cast_as(Alias::new(type_name)),
+        }
+    }
+}
+
+fn decode_error(index: usize, message: &str) -> sqlx::Error {
+    sqlx::Error::ColumnDecode {
+        index: index.to_string(),
+        source: message.into(),
+    }
+}
+
+/// Whether `name` can be written unquoted as a type name in the SQL.
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+impl Display for Cursor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let json = serde_json::to_vec(self).map_err(|_| std::fmt::Error)?;
+        f.write_str(&BASE64_URL_SAFE_NO_PAD.encode(json))
+    }
+}
+
+impl FromStr for Cursor {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let json = BASE64_URL_SAFE_NO_PAD
+            .decode(s)
+            .map_err(|_| Error::InvalidCursor)?;
+        let cursor: Self = serde_json::from_slice(&json).map_err(|_| Error::InvalidCursor)?;
+        let valid = cursor.key.iter().all(|value| match value {
+            KeyValue::Enum { type_name, .. } => is_identifier(type_name),
+            _ => true,
+        });
+        if !valid {
+            return Err(Error::InvalidCursor);
+        }
+        Ok(cursor)
+    }
+}
+
+/// A row of a page, along with its sort key.
+struct KeysetRow<E> {
+    entity: E,
+    key: Vec<KeyValue>,
+}
+
+impl<'r, E> FromRow<'r, SqlxRow> for KeysetRow<E>
+where
+    E: FromRow<'r, SqlxRow>,
+{
+    fn from_row(row: &'r SqlxRow) -> sqlx::Result<Self> {
+        let key = row
+            .columns()
+            .iter()
+            .enumerate()
+            .filter(|(_, column)| sqlx::Column::name(*column).starts_with(KEY_ALIAS_PREFIX))
+            .map(|(index, _)| KeyValue::decode(row, index))
+            .collect::<sqlx::Result<_>>()?;
+        Ok(Self {
+            entity: E::from_row(row)?,
+            key,
+        })
+    }
+}
+
+fn flip(order: &Order) -> Order {
+    match order {
+        Order::Desc => Order::Asc,
+        _ => Order::Desc,
+    }
+}
+
+/// Map the errors of comparing the sort columns with the values of a cursor of other types to
+/// `Error::InvalidCursor`.
+///
+/// The cursors of the pages always have the types of the sort columns, but a tampered one could
+/// make the comparisons fail in the database: with no operator between the types
+/// (`undefined_function`), mismatched types in a row comparison (`datatype_mismatch`), an
+/// unknown enum type (`undefined_object`), or a label that is not one
+/// of the enum (`invalid_text_representation`).
+fn map_key_type_error(e: Error) -> Error {
+    const KEY_TYPE_ERROR_CODES: [&str; 4] = ["42883", "42704", "42804", "22P02"];
+    match e {
+        Error::Sqlx(sqlx::Error::Database(db_err))
+            if db_err
+                .code()
+                .is_some_and(|code| KEY_TYPE_ERROR_CODES.contains(&code.as_ref())) =>
+        {
+            Error::InvalidCursor
+        }
+        _ => e,
+    }
+}
+
+/// The condition selecting the rows after `key` in the order of `columns`.
+fn after_key(columns: &[(Expr, Order)], key: Vec<KeyValue>) -> Expr {
+    let values = key.into_iter().map(KeyValue::into_expr).collect::<Vec<_>>();
+
+    // a row comparison can use a multicolumn index, but only compares in a single direction
+    let direction = std::mem::discriminant(&columns[0].1);
+    if columns
+        .iter()
+        .all(|(_, order)| std::mem::discriminant(order) == direction)
+    {
+        let (lhs, rhs) = (
+            Expr::tuple(columns.iter().map(|(column, _)| column.clone())),
+            Expr::tuple(values),
+        );
+        return match columns[0].1 {
+            Order::Desc => lhs.lt(rhs),
+            _ => lhs.gt(rhs),
+        };
+    }
+
+    // (a > x) OR (a = x AND b < y) OR ...
+    (0..columns.len())
+        .map(|i| {
+            let (column, order) = &columns[i];
+            let past = match order {
+                Order::Desc => column.clone().lt(values[i].clone()),
+                _ => column.clone().gt(values[i].clone()),
+            };
+            (0..i).fold(past, |cond, j| {
+                cond.and(columns[j].0.clone().eq(values[j].clone()))
+            })
+        })
+        .reduce(ExprTrait::or)
+        .expect("keyset pages are sorted by at least one column")
+}
+
+impl KeysetPayload {
+    /// Fetch the page of the rows of `query` at the cursor of this payload.
+    ///
+    /// The sort columns are the ones of `table_alias` in the query, followed by the `unique_key`
+    /// columns that are not already sorted on. `query` must not be sorted nor limited already.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidCursor` if the cursor does not match the sort columns.
+    pub async fn fetch_page<E>(
+        self,
+        ps: &mut impl PrimaryStore,
+        mut query: SelectStatement,
+        table_alias: &'static str,
+        unique_key: &[&'static str],
+    ) -> Result<Page<E>>
+    where
+        E: for<'r> FromRow<'r, SqlxRow> + Send + Unpin,
+    {
+        let total = if self.with_total {
+            let (total,) = ps
+                .query_as_with::<(i64,)>(
+                    Query::select()
+                        .expr(Expr::cust("COUNT(*)"))
+                        .from_subquery(query.clone(), "keyset_total"),
+                )
+                .fetch_one()
+                .await?;
+            Some(total as u64)
+        } else {
+            None
+        };
+
+        let mut order_by = self.order_by;
+        for column in unique_key {
+            if !order_by.iter().any(|(sorted, _)| sorted == column) {
+                order_by.push((column, Order::Asc));
+            }
+        }
+        let backward = self.cursor.as_ref().is_some_and(|cursor| cursor.backward);
+        let columns = order_by
+            .into_iter()
+            .map(|(column, order)| {
+                let order = if backward { flip(&order) } else { order };
+                (Expr::col((table_alias, column)), order)
+            })
+            .collect::<Vec<_>>();
+
+        for (i, (column, order)) in columns.iter().enumerate() {
+            query
+                .expr_as(column.clone(), format!("{KEY_ALIAS_PREFIX}{i}"))
+                .order_by_expr(column.clone(), order.clone());
+        }
+        let has_cursor = self.cursor.is_some();
+        if let Some(cursor) = self.cursor {
+            if cursor.key.len() != columns.len() {
+                return Err(Error::InvalidCursor);
+            }
+            query.and_where(after_key(&columns, cursor.key));
+        }
+        // the extra row tells whether there is a page after this one
+        query.limit(self.limit + 1);
+
+        let mut rows = ps
+            .query_as_with::<KeysetRow<E>>(&query)
+            .fetch_all()
+            .await
+            .map_err(|e| if has_cursor { map_key_type_error(e) } else { e })?;
+        let has_more = rows.len() as u64 > self.limit;
+        rows.truncate(self.limit as usize);
+        if backward {
+            rows.reverse();
+        }
+
+        let cursor_at = |row: Option<&KeysetRow<E>>, backward: bool| {
+            row.map(|row| Cursor {
+                backward,
+                key: row.key.clone(),
+            })
+        };
+        let (has_next, has_prev) = if backward {
+            (true, has_more)
+        } else {
+            (has_more, has_cursor)
+        };
+        Ok(Page {
+            next: cursor_at(rows.last().filter(|_| has_next), false),
+            prev: cursor_at(rows.first().filter(|_| has_prev), true),
+            items: rows.into_iter().map(|row| row.entity).collect(),
+            total,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let cursor = Cursor {
+            backward: true,
+            key: vec![
+                KeyValue::Timestamp(OffsetDateTime::UNIX_EPOCH),
+                KeyValue::Text("A-MAL-1".into()),
+                KeyValue::Enum {
+                    type_name: "entry_status".into(),
+                    label: "APPROVED".into(),
+                },
+            ],
+        };
+        let parsed: Cursor = cursor.to_string().parse().unwrap();
+        assert_eq!(parsed, cursor);
+    }
+
+    #[test]
+    fn test_cursor_rejects_tampering() {
+        assert!(matches!(
+            "not a cursor".parse::<Cursor>(),
+            Err(Error::InvalidCursor)
+        ));
+
+        let cursor = Cursor {
+            backward: false,
+            key: vec![KeyValue::Enum {
+                type_name: "text); DROP TABLE entry; --".into(),
+                label: "x".into(),
+            }],
+        };
+        assert!(matches!(
+            cursor.to_string().parse::<Cursor>(),
+            Err(Error::InvalidCursor)
+        ));
+    }
+}
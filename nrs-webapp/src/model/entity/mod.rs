@@ -1,13 +1,24 @@
 use sea_query::{
-    Expr, ExprTrait, Order, Query, ReturningClause, SelectStatement, SimpleExpr, Value,
+    Expr, ExprTrait, InsertStatement, LockType, OnConflict, Order, Query, ReturningClause,
+    SelectStatement, SimpleExpr, Value,
 };
-use sqlbindable::{BindContext, HasFieldNames, HasFields};
+use sqlbindable::{
+    BindContext, BindRowsContext, FieldVec, Filter, HasFieldNames, HasFields, SelectContext,
+};
+use sqlx::types::Json;
 
 use crate::model::{
-    Error, Result, SqlxDatabase, SqlxRow, entity::id::EntityId, store::primary_store::PrimaryStore,
+    Error, Result, SqlxDatabase, SqlxRow,
+    audit_event::{AuditAction, AuditContext, AuditEventBmc, RowSnapshot},
+    entity::{
+        id::EntityId,
+        keyset::{KeysetPayload, Page},
+    },
+    store::primary_store::PrimaryStore,
 };
 
 pub mod id;
+pub mod keyset;
 
 pub trait ApplyExt<T> {
     fn apply(&mut self, option: T) -> &mut Self;
@@ -18,7 +29,8 @@ pub trait ApplyExt<T> {
 pub struct ListPayload {
     pub offset: Option<usize>,
     pub limit: Option<usize>,
-    pub order_by: Option<(&'static str, Order)>,
+    /// The sort columns, the first one taking precedence.
+    pub order_by: Vec<(&'static str, Order)>,
 }
 
 impl ListPayload {
@@ -33,13 +45,13 @@ impl ListPayload {
     }
 
     pub fn apply_order_by(self, query: &mut SelectStatement) {
-        if let Some((col, order)) = self.order_by.as_ref().cloned() {
+        for (col, order) in self.order_by {
             query.order_by(col, order);
         }
     }
 
     pub fn apply_order_by_alias(self, query: &mut SelectStatement, table_alias: &'static str) {
-        if let Some((col, order)) = self.order_by {
+        for (col, order) in self.order_by {
             query.order_by((table_alias, col), order);
         }
     }
@@ -67,9 +79,104 @@ impl ApplyExt<ListPayload> for SelectStatement {
     }
 }
 
+/// The most parameters Postgres binds to a statement, which bounds the rows of a batch insert.
+pub(in crate::model) const MAX_BIND_PARAMS: usize = u16::MAX as usize;
+
+/// The rows written by an upsert.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct UpsertCount {
+    pub inserted: u64,
+    pub updated: u64,
+}
+
+impl FromIterator<bool> for UpsertCount {
+    /// Count the rows of an upsert from whether each one was inserted rather than updated.
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let mut count = Self::default();
+        for inserted in iter {
+            match inserted {
+                true => count.inserted += 1,
+                false => count.updated += 1,
+            }
+        }
+        count
+    }
+}
+
+/// The statements upserting `rows` into `table`, one per batch, each returning whether its rows
+/// were inserted rather than updated.
+///
+/// # Errors
+///
+/// Returns `Error::UnknownColumn` if a column of `conflict_columns` is not a field of `R`.
+fn upsert_statements<R: HasFields>(
+    table: &'static str,
+    conflict_columns: &[&'static str],
+    rows: Vec<R>,
+) -> Result<Vec<InsertStatement>> {
+    let field_names = R::field_names();
+    if let Some(column) = conflict_columns
+        .iter()
+        .find(|col| !field_names.iter_copied().any(|name| name == **col))
+    {
+        return Err(Error::UnknownColumn { table, column });
+    }
+    let update_columns = field_names
+        .iter_copied()
+        .filter(|col| !conflict_columns.contains(col))
+        .collect::<Vec<_>>();
+    let mut on_conflict = OnConflict::columns(conflict_columns.iter().copied());
+    if update_columns.is_empty() {
+        on_conflict.do_nothing();
+    } else {
+        on_conflict.update_columns(update_columns);
+    }
+
+    let statements = batch_rows(rows)?
+        .into_iter()
+        .map(|batch| {
+            Query::insert()
+                .into_table(table)
+                .bind_rows(batch)
+                .on_conflict(on_conflict.clone())
+                // `xmax` is only set on the row versions written by the update
+                .returning(Query::returning().expr(Expr::cust("xmax = 0")))
+                .to_owned()
+        })
+        .collect();
+    Ok(statements)
+}
+
+/// The rows of a batch insert, split in statements within the parameter limit of Postgres.
+fn batch_rows<R: HasFields>(rows: Vec<R>) -> Result<Vec<Vec<FieldVec>>> {
+    let batch_size = (MAX_BIND_PARAMS / R::field_names().0.len().max(1)).max(1);
+    let mut batches = Vec::new();
+    let mut rows = rows.into_iter().peekable();
+    while rows.peek().is_some() {
+        batches.push(
+            rows.by_ref()
+                .take(batch_size)
+                .map(HasFields::all_fields)
+                .collect::<core::result::Result<Vec<_>, _>>()?,
+        );
+    }
+    Ok(batches)
+}
+
 #[allow(async_fn_in_trait)]
 pub trait DbBmc: Send {
     const TABLE_NAME: &'static str;
+    /// Columns identifying the rows in the audit log, whose values are joined with `/`.
+    const AUDIT_ID_COLUMNS: &'static [&'static str] = &["id"];
+    /// Columns whose values are kept out of the audit log, which only records that they changed.
+    const AUDIT_REDACTED_COLUMNS: &'static [&'static str] = &[];
+    /// Columns uniquely identifying a row, which break the ties in the order of keyset pages.
+    const UNIQUE_KEY: &'static [&'static str] = &["id"];
+
+    /// The whole row as a JSON object, to select or return for the audit log.
+    fn row_snapshot() -> Expr {
+        Expr::cust(format!("to_jsonb(\"{}\".*)", Self::TABLE_NAME))
+    }
 
     /// Constructs an `Error::EntityNotFound` for this trait's `TABLE_NAME` using the given id.
     ///
@@ -111,6 +218,50 @@ pub trait DbBmc: Send {
         Ok(())
     }
 
+    /// Inserts `rows` with multi-row statements, returning the number of inserted rows.
+    ///
+    /// Every field is inserted, `None` as `NULL` rather than the column default, as the rows of a
+    /// statement share their columns. `ps` should be a transaction for the rows to be inserted
+    /// all or none, as large batches take several statements.
+    async fn create_many<R: HasFields + Send>(
+        ps: &mut impl PrimaryStore,
+        rows: Vec<R>,
+    ) -> Result<u64> {
+        let mut inserted = 0;
+        for batch in batch_rows(rows)? {
+            inserted += ps
+                .query_with(
+                    Query::insert()
+                        .into_table(Self::TABLE_NAME)
+                        .bind_rows(batch),
+                )
+                .execute()
+                .await?;
+        }
+        Ok(inserted)
+    }
+
+    /// Like [`create_many`](Self::create_many), updating the other fields of the rows that
+    /// conflict with an existing one on `conflict_columns`, which need a unique constraint.
+    ///
+    /// A statement cannot update a row twice, so the rows should not conflict with each other.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnknownColumn` if a column of `conflict_columns` is not a field of `R`.
+    async fn upsert<R: HasFields + Send>(
+        ps: &mut impl PrimaryStore,
+        conflict_columns: &[&'static str],
+        rows: Vec<R>,
+    ) -> Result<UpsertCount> {
+        let mut inserted = Vec::new();
+        for statement in upsert_statements(Self::TABLE_NAME, conflict_columns, rows)? {
+            let rows = ps.query_as_with::<(bool,)>(&statement).fetch_all().await?;
+            inserted.extend(rows.into_iter().map(|(is_insert,)| is_insert));
+        }
+        Ok(inserted.into_iter().collect())
+    }
+
     async fn get_optional_by_expr<E>(ps: &mut impl PrimaryStore, cond: Expr) -> Result<Option<E>>
     where
         E: for<'r> sqlx::FromRow<'r, SqlxRow> + Send + Unpin + HasFieldNames,
@@ -119,7 +270,7 @@ pub trait DbBmc: Send {
             .query_as_with::<E>(
                 Query::select()
                     .from(Self::TABLE_NAME)
-                    .columns(E::field_names().iter().copied())
+                    .select_fields::<E>(Self::TABLE_NAME)
                     .and_where(cond),
             )
             .fetch_optional()
@@ -135,7 +286,7 @@ pub trait DbBmc: Send {
             .query_as_with::<E>(
                 Query::select()
                     .from(Self::TABLE_NAME)
-                    .columns(E::field_names().iter().copied())
+                    .select_fields::<E>(Self::TABLE_NAME)
                     .and_where(cond),
             )
             .fetch_all()
@@ -143,7 +294,12 @@ pub trait DbBmc: Send {
         Ok(entities)
     }
 
-    async fn list<E>(ps: &mut impl PrimaryStore, payload: ListPayload) -> Result<Vec<E>>
+    /// List the rows matching `filter`, `()` matching every row.
+    async fn list<E>(
+        ps: &mut impl PrimaryStore,
+        filter: impl Filter + Send,
+        payload: ListPayload,
+    ) -> Result<Vec<E>>
     where
         E: for<'r> sqlx::FromRow<'r, SqlxRow> + Send + Unpin + HasFieldNames,
     {
@@ -151,7 +307,8 @@ pub trait DbBmc: Send {
             .query_as_with::<E>(
                 Query::select()
                     .from(Self::TABLE_NAME)
-                    .columns(E::field_names().iter().copied())
+                    .select_fields::<E>(Self::TABLE_NAME)
+                    .cond_where(filter.into_condition()?)
                     .apply_alias(payload, Self::TABLE_NAME),
             )
             .fetch_all()
@@ -159,6 +316,30 @@ pub trait DbBmc: Send {
         Ok(entities)
     }
 
+    /// Like [`list`](Self::list), fetching a page of rows at a cursor rather than an offset.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidCursor` if the cursor of `payload` is not one of a page sorted the
+    /// same way.
+    async fn list_page<E>(
+        ps: &mut impl PrimaryStore,
+        filter: impl Filter + Send,
+        payload: KeysetPayload,
+    ) -> Result<Page<E>>
+    where
+        E: for<'r> sqlx::FromRow<'r, SqlxRow> + Send + Unpin + HasFieldNames,
+    {
+        let mut query = Query::select();
+        query
+            .from(Self::TABLE_NAME)
+            .select_fields::<E>(Self::TABLE_NAME)
+            .cond_where(filter.into_condition()?);
+        payload
+            .fetch_page(ps, query, Self::TABLE_NAME, Self::UNIQUE_KEY)
+            .await
+    }
+
     async fn update_cond(
         ps: &mut impl PrimaryStore,
         update_req: impl HasFields,
@@ -183,6 +364,97 @@ pub trait DbBmc: Send {
             .await?;
         Ok(rows_affected)
     }
+
+    /// Like [`create`](Self::create), also recording the created row in the audit log.
+    ///
+    /// The row and its audit event are only written together when `ps` is a transaction.
+    async fn create_audited(
+        ps: &mut impl PrimaryStore,
+        audit: &AuditContext,
+        create_req: impl HasFields + Send,
+    ) -> Result<()> {
+        let (Json(after),) = ps
+            .query_as_with::<(Json<RowSnapshot>,)>(
+                Query::insert()
+                    .into_table(Self::TABLE_NAME)
+                    .bind(create_req.not_none_fields()?)
+                    .returning(Query::returning().expr(Self::row_snapshot())),
+            )
+            .fetch_one()
+            .await?;
+        AuditEventBmc::record::<Self>(ps, audit, AuditAction::Create, None, Some(&after)).await
+    }
+
+    /// Like [`update_cond`](Self::update_cond), also recording the changed fields of every
+    /// updated row in the audit log.
+    ///
+    /// The updated rows are locked beforehand to read their previous values, so `ps` should be
+    /// a transaction.
+    async fn update_cond_audited(
+        ps: &mut impl PrimaryStore,
+        audit: &AuditContext,
+        update_req: impl HasFields,
+        cond: Expr,
+    ) -> Result<u64> {
+        let before_rows = ps
+            .query_as_with::<(Json<RowSnapshot>,)>(
+                Query::select()
+                    .expr(Self::row_snapshot())
+                    .from(Self::TABLE_NAME)
+                    .and_where(cond.clone())
+                    .lock(LockType::Update),
+            )
+            .fetch_all()
+            .await?;
+        let after_rows = ps
+            .query_as_with::<(Json<RowSnapshot>,)>(
+                Query::update()
+                    .table(Self::TABLE_NAME)
+                    .bind(update_req.not_none_fields()?)
+                    .and_where(cond)
+                    .returning(Query::returning().expr(Self::row_snapshot())),
+            )
+            .fetch_all()
+            .await?;
+
+        for (Json(after),) in &after_rows {
+            let before = before_rows
+                .iter()
+                .map(|(Json(before),)| before)
+                .find(|before| {
+                    Self::AUDIT_ID_COLUMNS
+                        .iter()
+                        .all(|column| before.get(*column) == after.get(*column))
+                });
+            AuditEventBmc::record::<Self>(ps, audit, AuditAction::Update, before, Some(after))
+                .await?;
+        }
+        Ok(after_rows.len() as u64)
+    }
+
+    /// Like [`delete_cond`](Self::delete_cond), also recording every deleted row in the audit
+    /// log.
+    async fn delete_cond_audited(
+        ps: &mut impl PrimaryStore,
+        audit: &AuditContext,
+        cond: Expr,
+    ) -> Result<u64> {
+        let before_rows = ps
+            .query_as_with::<(Json<RowSnapshot>,)>(
+                Query::delete()
+                    .from_table(Self::TABLE_NAME)
+                    .and_where(cond)
+                    .returning(Query::returning().expr(Self::row_snapshot())),
+            )
+            .fetch_all()
+            .await?;
+
+        for (Json(before),) in &before_rows {
+            AuditEventBmc::record::<Self>(ps, audit, AuditAction::Delete, Some(before), None)
+                .await?;
+        }
+        Ok(before_rows.len() as u64)
+    }
 }
 
 #[allow(async_fn_in_trait)]
@@ -263,4 +535,164 @@ pub trait DbBmcWithPkey: DbBmc {
         }
         Ok(())
     }
+
+    /// Like [`create_returning_pkey`](Self::create_returning_pkey), also recording the created
+    /// row in the audit log.
+    async fn create_returning_pkey_audited(
+        mm: &mut impl PrimaryStore,
+        audit: &AuditContext,
+        create_req: impl HasFields,
+    ) -> Result<Self::PkeyType>
+    where
+        Self::PkeyType:
+            for<'r> sqlx::Decode<'r, SqlxDatabase> + sqlx::Type<SqlxDatabase> + Send + Unpin,
+    {
+        let (pkey, Json(after)) = mm
+            .query_as_with::<(Self::PkeyType, Json<RowSnapshot>)>(
+                Query::insert()
+                    .into_table(Self::TABLE_NAME)
+                    .bind(create_req.not_none_fields()?)
+                    .returning(
+                        Query::returning()
+                            .exprs([Expr::col(Self::PRIMARY_KEY), Self::row_snapshot()]),
+                    ),
+            )
+            .fetch_one()
+            .await?;
+        AuditEventBmc::record::<Self>(mm, audit, AuditAction::Create, None, Some(&after)).await?;
+        Ok(pkey)
+    }
+
+    /// Like [`update`](Self::update), also recording the changed fields in the audit log.
+    async fn update_audited(
+        mm: &mut impl PrimaryStore,
+        audit: &AuditContext,
+        update_req: impl HasFields,
+        id: Self::PkeyType,
+    ) -> Result<()>
+    where
+        Value: From<Self::PkeyType>,
+    {
+        let rows_affected =
+            Self::update_cond_audited(mm, audit, update_req, Self::cond_pkey(id.clone())).await?;
+        if rows_affected == 0 {
+            return Err(Self::not_found_error(id));
+        }
+        Ok(())
+    }
+
+    /// Like [`delete`](Self::delete), also recording the deleted row in the audit log.
+    async fn delete_audited(
+        mm: &mut impl PrimaryStore,
+        audit: &AuditContext,
+        id: Self::PkeyType,
+    ) -> Result<()>
+    where
+        Value: From<Self::PkeyType>,
+    {
+        let rows_affected =
+            Self::delete_cond_audited(mm, audit, Self::cond_pkey(id.clone())).await?;
+        if rows_affected == 0 {
+            return Err(Self::not_found_error(id));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sea_query::PostgresQueryBuilder;
+    use sqlbindable::{FieldNames, Fields};
+
+    use super::*;
+
+    #[derive(FieldNames, Fields)]
+    struct Row {
+        a: i32,
+        b: i32,
+        c: Option<i32>,
+    }
+
+    #[test]
+    fn test_batch_rows_within_bind_params() {
+        let rows = (0..50_000)
+            .map(|i| Row {
+                a: i,
+                b: i,
+                c: None,
+            })
+            .collect();
+        let batches = batch_rows(rows).unwrap();
+        let sizes = batches.iter().map(Vec::len).collect::<Vec<_>>();
+        assert_eq!(sizes, vec![21845, 21845, 6310]);
+        // every field is bound, NULL included
+        assert!(batches.iter().flatten().all(|row| row.0.len() == 3));
+
+        assert!(batch_rows(Vec::<Row>::new()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_upsert_statements() {
+        let rows = vec![
+            Row {
+                a: 1,
+                b: 2,
+                c: None,
+            },
+            Row {
+                a: 3,
+                b: 4,
+                c: Some(5),
+            },
+        ];
+        let statements = upsert_statements("test", &["a"], rows).unwrap();
+        let sql = statements
+            .iter()
+            .map(|statement| statement.to_string(PostgresQueryBuilder))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            sql,
+            vec![
+                r#"INSERT INTO "test" ("a", "b", "c") VALUES (1, 2, NULL), (3, 4, 5) ON CONFLICT ("a") DO UPDATE SET "b" = "excluded"."b", "c" = "excluded"."c" RETURNING xmax = 0"#
+            ]
+        );
+    }
+
+    #[test]
+    fn test_upsert_statements_without_update_columns() {
+        let rows = vec![Row {
+            a: 1,
+            b: 2,
+            c: None,
+        }];
+        let statements = upsert_statements("test", &["a", "b", "c"], rows).unwrap();
+        assert_eq!(
+            statements[0].to_string(PostgresQueryBuilder),
+            r#"INSERT INTO "test" ("a", "b", "c") VALUES (1, 2, NULL) ON CONFLICT ("a", "b", "c") DO NOTHING RETURNING xmax = 0"#
+        );
+    }
+
+    #[test]
+    fn test_upsert_statements_reject_unknown_conflict_columns() {
+        let result = upsert_statements::<Row>("test", &["id"], vec![]);
+        assert!(matches!(
+            result,
+            Err(Error::UnknownColumn {
+                table: "test",
+                column: "id"
+            })
+        ));
+    }
+
+    #[test]
+    fn test_upsert_count() {
+        let count: UpsertCount = [true, false, true].into_iter().collect();
+        assert_eq!(
+            count,
+            UpsertCount {
+                inserted: 2,
+                updated: 1
+            }
+        );
+    }
 }
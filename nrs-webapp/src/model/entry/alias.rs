@@ -11,6 +11,7 @@ pub struct EntryAliasBmc;
 
 impl DbBmc for EntryAliasBmc {
     const TABLE_NAME: &'static str = "entry_alias";
+    const UNIQUE_KEY: &'static [&'static str] = &["old_id"];
 }
 
 impl DbBmcWithPkey for EntryAliasBmc {
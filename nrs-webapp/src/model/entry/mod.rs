@@ -1,18 +1,68 @@
 pub mod alias;
+pub mod revision;
 
-use sea_query::{Expr, ExprTrait, JoinType, Query, SelectExpr, SelectStatement};
-use sqlbindable::{FieldNames, Fields, HasFieldNames};
+use sea_query::{Expr, ExprTrait, LockType, Order, Query, SelectStatement, Value};
+use serde::{Deserialize, Serialize};
+use serde_with::{NoneAsEmptyString, StringWithSeparator, formats::CommaSeparator, serde_as};
+use sqlbindable::{
+    FieldNames, Fields, Filter, HasFieldNames, SelectContext, TryIntoExpr, TryIntoExprError,
+};
 use sqlx::{FromRow, types::Json};
+use strum::IntoStaticStr;
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
 use uuid::Uuid;
+use validator::Validate;
 
 use crate::model::{
-    Result,
-    entity::{ApplyExt, DbBmc, DbBmcWithPkey, ListPayload},
+    Error, Result, SqlxRow,
+    audit_event::AuditContext,
+    entity::{
+        DbBmc, DbBmcWithPkey,
+        keyset::{KeysetPayload, Page},
+    },
+    entry::revision::EntryRevisionBmc,
     store::primary_store::PrimaryStore,
-    user::UserBmc,
 };
 use nrs_webapp_core::data::entry::types::idtype::EntryType;
 
+/// Where an entry is in moderation. Only the approved entries are listed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, Serialize, Deserialize, IntoStaticStr)]
+#[sqlx(type_name = "ENTRY_STATUS")]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum EntryStatus {
+    /// Submitted, waiting for a moderator.
+    #[sqlx(rename = "PENDING")]
+    Pending,
+    #[sqlx(rename = "APPROVED")]
+    Approved,
+    #[sqlx(rename = "REJECTED")]
+    Rejected,
+}
+
+impl EntryStatus {
+    /// Get the SQL enum string of this status: `"PENDING"`, `"APPROVED"` or `"REJECTED"`.
+    pub fn to_enum_string(&self) -> &'static str {
+        match self {
+            EntryStatus::Pending => "PENDING",
+            EntryStatus::Approved => "APPROVED",
+            EntryStatus::Rejected => "REJECTED",
+        }
+    }
+}
+
+impl From<EntryStatus> for Expr {
+    fn from(status: EntryStatus) -> Self {
+        Value::String(Some(status.to_enum_string().into())).cast_as("ENTRY_STATUS")
+    }
+}
+
+impl TryIntoExpr for EntryStatus {
+    fn into_expr(self) -> core::result::Result<Expr, TryIntoExprError> {
+        Ok(self.into())
+    }
+}
+
 pub struct EntryBmc;
 
 impl DbBmc for EntryBmc {
@@ -29,15 +79,26 @@ pub struct EntryForCreate {
     pub id: String,
     pub title: String,
     pub entry_type: EntryType,
+    pub entry_info: Json<serde_json::Value>,
     pub added_by: Uuid,
+    pub status: EntryStatus,
+}
+
+/// The editable content of an entry, snapshotted by its revisions.
+#[derive(Debug, Clone, PartialEq, FromRow, FieldNames, Fields)]
+pub struct EntryForUpdate {
+    pub title: String,
+    pub entry_type: EntryType,
+    pub entry_info: Json<serde_json::Value>,
 }
 
 #[derive(Debug, Clone, FromRow, FieldNames)]
 pub struct EntryAddedBy {
+    // both are NULL when the submitter deleted their account
     #[sqlx(rename = "added_by.id")]
-    pub id: Uuid,
+    pub id: Option<Uuid>,
     #[sqlx(rename = "added_by.username")]
-    pub username: String,
+    pub username: Option<String>,
 }
 
 #[derive(Debug, Clone, FromRow, FieldNames)]
@@ -46,63 +107,242 @@ pub struct Entry {
     pub title: String,
     pub entry_type: EntryType,
     #[sqlx(flatten)]
+    #[field(join = "app_user", on = "added_by")]
     pub added_by: EntryAddedBy,
     pub entry_info: Json<serde_json::Value>,
+    pub status: EntryStatus,
+    pub submitted_at: OffsetDateTime,
+    pub rejection_reason: Option<String>,
+}
+
+/// Narrows the list of entries down, as deserialized from the query string of the list page.
+///
+/// Empty parameters are ignored, and `entry_type_in` is a comma-separated list of types.
+#[serde_as]
+#[derive(Debug, Clone, Default, Deserialize, Validate, Filter)]
+#[filter(table = "entry")]
+pub struct EntryFilter {
+    #[serde_as(as = "NoneAsEmptyString")]
+    #[serde(default)]
+    #[validate(length(max = 512))]
+    pub title_contains: Option<String>,
+    #[serde_as(as = "Option<StringWithSeparator<CommaSeparator, EntryType>>")]
+    #[serde(default)]
+    pub entry_type_in: Option<Vec<EntryType>>,
+    #[serde_as(as = "Option<Rfc3339>")]
+    #[serde(default)]
+    pub submitted_after: Option<OffsetDateTime>,
+}
+
+/// The decision of a moderator on a pending entry.
+#[derive(Debug, Clone)]
+pub enum EntryReview {
+    Approve,
+    Reject { reason: String },
+}
+
+#[derive(FieldNames, Fields)]
+struct EntryForReview {
+    status: EntryStatus,
+    reviewed_by: Option<Uuid>,
+    reviewed_at: Expr,
+    rejection_reason: Option<String>,
+}
+
+/// The comment of the first revision of the entries.
+const INITIAL_REVISION_COMMENT: &str = "Initial revision";
+
+/// Map the unique violations of the creation of entries to `Error::EntryAlreadyExists`.
+fn map_entry_exists(e: Error) -> Error {
+    match e {
+        Error::Sqlx(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {
+            Error::EntryAlreadyExists
+        }
+        _ => e,
+    }
 }
 
 impl EntryBmc {
+    /// Create an entry, with its initial revision.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::EntryAlreadyExists` if the ID is taken.
     pub async fn create_entry(
         mm: &mut impl PrimaryStore,
+        audit: &AuditContext,
         create_req: EntryForCreate,
     ) -> Result<()> {
-        <Self as DbBmc>::create(mm, create_req).await?;
+        let (id, added_by) = (create_req.id.clone(), create_req.added_by);
+        <Self as DbBmc>::create_audited(mm, audit, create_req)
+            .await
+            .map_err(map_entry_exists)?;
+        EntryRevisionBmc::record(mm, &id, Some(added_by), INITIAL_REVISION_COMMENT).await?;
         Ok(())
     }
 
+    /// Create entries in bulk, such as for an import, each with its initial revision by its
+    /// submitter. Returns the number of created entries.
+    ///
+    /// Unlike [`create_entry`](Self::create_entry), the creations are not recorded in the audit
+    /// log. `ps` should be a transaction, for the entries to be created all or none.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::EntryAlreadyExists` if an ID is taken.
+    pub async fn create_entries(
+        ps: &mut impl PrimaryStore,
+        create_reqs: Vec<EntryForCreate>,
+    ) -> Result<u64> {
+        let ids = create_reqs
+            .iter()
+            .map(|create_req| create_req.id.clone())
+            .collect::<Vec<_>>();
+        let created = <Self as DbBmc>::create_many(ps, create_reqs)
+            .await
+            .map_err(map_entry_exists)?;
+        EntryRevisionBmc::record_initial(ps, &ids, INITIAL_REVISION_COMMENT).await?;
+        Ok(created)
+    }
+
+    /// Replace the content of the entry `id`, recording it as a new revision by the actor of
+    /// `audit` with `comment`.
+    ///
+    /// Returns the number of the new revision, or `None` if the content did not change, in which
+    /// case nothing is recorded. `ps` should be a transaction, as the entry is locked until the
+    /// revision is recorded.
+    pub async fn update_entry(
+        ps: &mut impl PrimaryStore,
+        audit: &AuditContext,
+        id: String,
+        update_req: EntryForUpdate,
+        comment: &str,
+    ) -> Result<Option<i32>> {
+        let current = ps
+            .query_as_with::<EntryForUpdate>(
+                Query::select()
+                    .columns(EntryForUpdate::field_names().iter().copied())
+                    .from(Self::TABLE_NAME)
+                    .and_where(Self::cond_pkey(id.clone()))
+                    .lock(LockType::Update),
+            )
+            .fetch_optional()
+            .await?
+            .ok_or_else(|| Self::not_found_error(id.clone()))?;
+        if current == update_req {
+            return Ok(None);
+        }
+
+        <Self as DbBmcWithPkey>::update_audited(ps, audit, update_req, id.clone()).await?;
+        let revision = EntryRevisionBmc::record(ps, &id, audit.actor_id, comment).await?;
+        Ok(Some(revision))
+    }
+
+    /// Restore the content of the entry `id` from its earlier `revision`, as a new revision.
+    ///
+    /// Returns the number of the new revision, or `None` if the entry already has this content.
+    pub async fn restore_revision(
+        ps: &mut impl PrimaryStore,
+        audit: &AuditContext,
+        id: String,
+        revision: i32,
+    ) -> Result<Option<i32>> {
+        let restored = EntryRevisionBmc::get_revision(ps, &id, revision).await?;
+        Self::update_entry(
+            ps,
+            audit,
+            id,
+            EntryForUpdate {
+                title: restored.title,
+                entry_type: restored.entry_type,
+                entry_info: restored.entry_info,
+            },
+            &format!("Rolled back to revision {revision}"),
+        )
+        .await
+    }
+
     fn select_entry() -> SelectStatement {
         let mut query = Query::select();
         query
             .from(Self::TABLE_NAME)
-            .exprs(
-                Entry::field_names()
-                    .iter()
-                    .copied()
-                    .filter(|col| *col != "added_by")
-                    .map(|col| SelectExpr {
-                        expr: Expr::col((Self::TABLE_NAME, col)),
-                        alias: Some(col.into()),
-                        window: None,
-                    }),
-            )
-            .exprs(
-                EntryAddedBy::field_names()
-                    .iter()
-                    .copied()
-                    .map(|col| SelectExpr {
-                        expr: Expr::col((UserBmc::TABLE_NAME, col)),
-                        alias: Some(format!("added_by.{col}").into()),
-                        window: None,
-                    }),
-            )
-            .join(
-                JoinType::Join,
-                UserBmc::TABLE_NAME,
-                Expr::col((Self::TABLE_NAME, "added_by")).equals((UserBmc::TABLE_NAME, "id")),
-            );
+            .select_fields::<Entry>(Self::TABLE_NAME);
         query
     }
 
+    /// List a page of the approved entries matching `filter`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidCursor` if the cursor of `payload` is not one of a page sorted the
+    /// same way.
     pub async fn list_entries(
         ps: &mut impl PrimaryStore,
-        payload: ListPayload,
-    ) -> Result<Vec<Entry>> {
+        filter: impl Filter + Send,
+        payload: KeysetPayload,
+    ) -> Result<Page<Entry>> {
+        let mut query = Self::select_entry();
+        query
+            .and_where(Expr::col((Self::TABLE_NAME, "status")).eq(EntryStatus::Approved))
+            .cond_where(filter.into_condition()?);
+        payload
+            .fetch_page(ps, query, Self::TABLE_NAME, Self::UNIQUE_KEY)
+            .await
+    }
+
+    /// List the entries waiting for a moderator, the oldest submission first.
+    pub async fn list_pending(ps: &mut impl PrimaryStore) -> Result<Vec<Entry>> {
         let entities = ps
-            .query_as_with(Self::select_entry().apply_alias(payload, Self::TABLE_NAME))
+            .query_as_with(
+                Self::select_entry()
+                    .and_where(Expr::col((Self::TABLE_NAME, "status")).eq(EntryStatus::Pending))
+                    .order_by((Self::TABLE_NAME, "submitted_at"), Order::Asc),
+            )
             .fetch_all()
             .await?;
         Ok(entities)
     }
 
+    /// Approve or reject the pending entry `id`, on behalf of the actor of `audit`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::EntityNotFound` if there is no such entry, or if it was already reviewed.
+    pub async fn review(
+        ps: &mut impl PrimaryStore,
+        audit: &AuditContext,
+        id: String,
+        review: EntryReview,
+    ) -> Result<()> {
+        let (status, rejection_reason) = match review {
+            EntryReview::Approve => (EntryStatus::Approved, None),
+            EntryReview::Reject { reason } => (EntryStatus::Rejected, Some(reason)),
+        };
+        let rows_affected = <Self as DbBmc>::update_cond_audited(
+            ps,
+            audit,
+            EntryForReview {
+                status,
+                reviewed_by: audit.actor_id,
+                reviewed_at: Expr::current_timestamp(),
+                rejection_reason,
+            },
+            Self::cond_pkey(id.clone()).and(Expr::col("status").eq(EntryStatus::Pending)),
+        )
+        .await?;
+        if rows_affected == 0 {
+            return Err(Self::not_found_error(id));
+        }
+        Ok(())
+    }
+
+    pub async fn list_added_by<E>(ps: &mut impl PrimaryStore, user_id: Uuid) -> Result<Vec<E>>
+    where
+        E: for<'r> FromRow<'r, SqlxRow> + Send + Unpin + HasFieldNames,
+    {
+        <Self as DbBmc>::get_all_by_expr(ps, Expr::col("added_by").eq(user_id)).await
+    }
+
     pub async fn get_details(ps: &mut impl PrimaryStore, id: String) -> Result<Entry> {
         let maybe_entity = ps
             .query_as_with::<Entry>(Self::select_entry().and_where(Self::cond_pkey(id)))
@@ -111,3 +351,28 @@ impl EntryBmc {
         Ok(maybe_entity)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use sea_query::PostgresQueryBuilder;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn entry_filter_compares_the_submission_time() {
+        let filter: EntryFilter = serde_json::from_value(json!({
+            "title_contains": "",
+            "submitted_after": "2025-01-01T00:00:00Z",
+        }))
+        .unwrap();
+        let sql = Query::select()
+            .column("id")
+            .from(EntryBmc::TABLE_NAME)
+            .cond_where(filter.into_condition().unwrap())
+            .to_string(PostgresQueryBuilder);
+
+        assert!(sql.contains(r#"WHERE "entry"."submitted_at" > "#), "{sql}");
+        assert!(!sql.contains("title"), "{sql}");
+    }
+}
@@ -0,0 +1,295 @@
+use nrs_webapp_core::data::entry::types::idtype::EntryType;
+use sea_query::{Expr, ExprTrait, Func, Order, Query};
+use serde_json::{Value, json};
+use sqlbindable::{FieldNames, HasFieldNames, SelectContext};
+use sqlx::{FromRow, types::Json};
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::model::{
+    Result, SqlxRow,
+    entity::{DbBmc, MAX_BIND_PARAMS},
+    entry::EntryBmc,
+    store::primary_store::PrimaryStore,
+};
+
+/// The snapshots of the content of the entries, one per edit.
+pub struct EntryRevisionBmc;
+
+impl DbBmc for EntryRevisionBmc {
+    const TABLE_NAME: &'static str = "entry_revision";
+    const UNIQUE_KEY: &'static [&'static str] = &["entry_id", "revision"];
+}
+
+#[derive(Debug, Clone, FromRow, FieldNames)]
+pub struct EntryRevisionAuthor {
+    // both are NULL when the author deleted their account
+    #[sqlx(rename = "author.id")]
+    pub id: Option<Uuid>,
+    #[sqlx(rename = "author.username")]
+    pub username: Option<String>,
+}
+
+#[derive(Debug, Clone, FromRow, FieldNames)]
+pub struct EntryRevision {
+    pub entry_id: String,
+    /// Numbered from 1, in the order of the edits of the entry.
+    pub revision: i32,
+    pub title: String,
+    pub entry_type: EntryType,
+    pub entry_info: Json<Value>,
+    #[sqlx(flatten)]
+    #[field(join = "app_user", on = "author_id")]
+    pub author: EntryRevisionAuthor,
+    pub comment: String,
+    pub created_at: OffsetDateTime,
+}
+
+/// A value of the content of an entry that changed between two revisions.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RevisionChange {
+    /// Dotted path of the value, such as `title` or `entry_info.links.mal`.
+    pub path: String,
+    /// `None` when the value was added.
+    pub before: Option<Value>,
+    /// `None` when the value was removed.
+    pub after: Option<Value>,
+}
+
+impl EntryRevision {
+    fn content(&self) -> Value {
+        json!({
+            "title": self.title,
+            "entry_type": self.entry_type,
+            "entry_info": self.entry_info.0,
+        })
+    }
+
+    /// The values changed by this revision since the `previous` one.
+    pub fn changes_since(&self, previous: &EntryRevision) -> Vec<RevisionChange> {
+        let mut changes = Vec::new();
+        json_diff(
+            "",
+            Some(&previous.content()),
+            Some(&self.content()),
+            &mut changes,
+        );
+        changes
+    }
+}
+
+/// Collect the differences between `before` and `after`, descending into the objects present on
+/// both sides; arrays and other values are compared as a whole.
+fn json_diff(
+    path: &str,
+    before: Option<&Value>,
+    after: Option<&Value>,
+    changes: &mut Vec<RevisionChange>,
+) {
+    match (before, after) {
+        (Some(Value::Object(before)), Some(Value::Object(after))) => {
+            let mut keys = before.keys().chain(after.keys()).collect::<Vec<_>>();
+            keys.sort();
+            keys.dedup();
+            for key in keys {
+                let path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+                json_diff(&path, before.get(key), after.get(key), changes);
+            }
+        }
+        (before, after) if before != after => changes.push(RevisionChange {
+            path: path.to_string(),
+            before: before.cloned(),
+            after: after.cloned(),
+        }),
+        _ => {}
+    }
+}
+
+impl EntryRevisionBmc {
+    /// Snapshot the current content of the entry `entry_id` as its next revision.
+    ///
+    /// The entry row should be locked, or just created, in the same transaction, so that
+    /// concurrent edits cannot take the same revision number.
+    pub(super) async fn record(
+        ps: &mut impl PrimaryStore,
+        entry_id: &str,
+        author_id: Option<Uuid>,
+        comment: &str,
+    ) -> Result<i32> {
+        let (latest,) = ps
+            .query_as_with::<(Option<i32>,)>(
+                Query::select()
+                    .expr(Func::max(Expr::col("revision")))
+                    .from(Self::TABLE_NAME)
+                    .and_where(Expr::col("entry_id").eq(entry_id)),
+            )
+            .fetch_one()
+            .await?;
+        let revision = latest.unwrap_or(0) + 1;
+
+        ps.query_with(
+            Query::insert()
+                .into_table(Self::TABLE_NAME)
+                .columns([
+                    "entry_id",
+                    "revision",
+                    "title",
+                    "entry_type",
+                    "entry_info",
+                    "author_id",
+                    "comment",
+                ])
+                .select_from(
+                    Query::select()
+                        .column("id")
+                        .expr(Expr::val(revision))
+                        .columns(["title", "entry_type", "entry_info"])
+                        .expr(Expr::val(author_id))
+                        .expr(Expr::val(comment))
+                        .from(EntryBmc::TABLE_NAME)
+                        .and_where(Expr::col("id").eq(entry_id))
+                        .to_owned(),
+                )
+                .expect("the selected columns match the inserted ones"),
+        )
+        .execute()
+        .await?;
+        Ok(revision)
+    }
+
+    /// Snapshot the content of the just created entries `entry_ids` as their first revision, by
+    /// their submitter, returning their number.
+    pub(super) async fn record_initial(
+        ps: &mut impl PrimaryStore,
+        entry_ids: &[String],
+        comment: &str,
+    ) -> Result<u64> {
+        let mut recorded = 0;
+        // the revision number and the comment are bound along with the IDs
+        for ids in entry_ids.chunks(MAX_BIND_PARAMS - 2) {
+            let mut insert = Query::insert();
+            insert
+                .into_table(Self::TABLE_NAME)
+                .columns([
+                    "entry_id",
+                    "revision",
+                    "title",
+                    "entry_type",
+                    "entry_info",
+                    "author_id",
+                    "comment",
+                ])
+                .select_from(
+                    Query::select()
+                        .column("id")
+                        .expr(Expr::val(1))
+                        .columns(["title", "entry_type", "entry_info", "added_by"])
+                        .expr(Expr::val(comment))
+                        .from(EntryBmc::TABLE_NAME)
+                        .and_where(Expr::col("id").is_in(ids.iter().cloned()))
+                        .to_owned(),
+                )?;
+            recorded += ps.query_with(&insert).execute().await?;
+        }
+        Ok(recorded)
+    }
+
+    fn select_revision() -> sea_query::SelectStatement {
+        let mut query = Query::select();
+        query
+            .from(Self::TABLE_NAME)
+            .select_fields::<EntryRevision>(Self::TABLE_NAME);
+        query
+    }
+
+    /// List the revisions of the entry `entry_id`, newest first.
+    pub async fn list_for_entry(
+        ps: &mut impl PrimaryStore,
+        entry_id: &str,
+    ) -> Result<Vec<EntryRevision>> {
+        let revisions = ps
+            .query_as_with::<EntryRevision>(
+                Self::select_revision()
+                    .and_where(Expr::col((Self::TABLE_NAME, "entry_id")).eq(entry_id))
+                    .order_by((Self::TABLE_NAME, "revision"), Order::Desc),
+            )
+            .fetch_all()
+            .await?;
+        Ok(revisions)
+    }
+
+    pub async fn get_revision(
+        ps: &mut impl PrimaryStore,
+        entry_id: &str,
+        revision: i32,
+    ) -> Result<EntryRevision> {
+        ps.query_as_with::<EntryRevision>(
+            Self::select_revision()
+                .and_where(Expr::col((Self::TABLE_NAME, "entry_id")).eq(entry_id))
+                .and_where(Expr::col((Self::TABLE_NAME, "revision")).eq(revision)),
+        )
+        .fetch_optional()
+        .await?
+        .ok_or_else(|| Self::not_found_error(format!("{entry_id}@{revision}")))
+    }
+
+    pub async fn list_authored_by<E>(ps: &mut impl PrimaryStore, user_id: Uuid) -> Result<Vec<E>>
+    where
+        E: for<'r> FromRow<'r, SqlxRow> + Send + Unpin + HasFieldNames,
+    {
+        <Self as DbBmc>::get_all_by_expr(ps, Expr::col("author_id").eq(user_id)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(path: &str, before: Option<Value>, after: Option<Value>) -> RevisionChange {
+        RevisionChange {
+            path: path.to_string(),
+            before,
+            after,
+        }
+    }
+
+    #[test]
+    fn json_diff_descends_into_objects() {
+        let before = json!({
+            "title": "Old",
+            "entry_info": {"links": {"mal": 1, "anilist": 2}, "tags": ["a"]},
+        });
+        let after = json!({
+            "title": "New",
+            "entry_info": {"links": {"mal": 1}, "tags": ["a", "b"], "year": 2020},
+        });
+
+        let mut changes = Vec::new();
+        json_diff("", Some(&before), Some(&after), &mut changes);
+        assert_eq!(
+            changes,
+            vec![
+                change("entry_info.links.anilist", Some(json!(2)), None),
+                change(
+                    "entry_info.tags",
+                    Some(json!(["a"])),
+                    Some(json!(["a", "b"]))
+                ),
+                change("entry_info.year", None, Some(json!(2020))),
+                change("title", Some(json!("Old")), Some(json!("New"))),
+            ]
+        );
+    }
+
+    #[test]
+    fn json_diff_of_equal_values_is_empty() {
+        let value = json!({"title": "Same", "entry_info": {"a": [1, 2]}});
+        let mut changes = Vec::new();
+        json_diff("", Some(&value), Some(&value), &mut changes);
+        assert!(changes.is_empty());
+    }
+}
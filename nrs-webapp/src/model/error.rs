@@ -13,13 +13,29 @@ pub enum Error {
     #[error("sqlbindable TryIntoExpr: {0}")]
     TryIntoExpr(#[from] sqlbindable::TryIntoExprError),
 
+    #[error("sea-query error: {0}")]
+    SeaQuery(#[from] sea_query::error::Error),
+
     #[error("Entity not found: {name} with ID {id}")]
     EntityNotFound { name: &'static str, id: EntityId },
 
+    #[error("Unknown column {column} of {table}")]
+    UnknownColumn {
+        table: &'static str,
+        column: &'static str,
+    },
+
+    #[error("Invalid page cursor")]
+    InvalidCursor,
+
     // UserBmc
     #[error("User with given email or username already exists")]
     EmailOrUsernameAlreadyExists,
 
+    // EntryBmc
+    #[error("Entry with given ID already exists")]
+    EntryAlreadyExists,
+
     // Token
     #[error("Token is invalid or has expired")]
     InvalidOrExpiredToken,
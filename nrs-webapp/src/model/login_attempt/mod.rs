@@ -0,0 +1,116 @@
+use sea_query::{Expr, ExprTrait, Func, Order, Query};
+use sqlx::FromRow;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use super::Result;
+use crate::model::{entity::DbBmc, store::primary_store::PrimaryStore};
+
+pub struct LoginAttemptBmc;
+
+impl DbBmc for LoginAttemptBmc {
+    const TABLE_NAME: &'static str = "login_attempt";
+}
+
+/// Failed logins of an account or an IP address in the considered window.
+#[derive(Debug, Default, FromRow)]
+pub struct LoginFailureStats {
+    pub failures: i64,
+    pub last_failure_at: Option<OffsetDateTime>,
+}
+
+/// A failed login, kept while it is in the failure window.
+#[derive(Debug, Clone, FromRow)]
+pub struct LoginAttempt {
+    pub ip: String,
+    pub created_at: OffsetDateTime,
+}
+
+impl LoginAttemptBmc {
+    /// Record a failed password login from `ip`, for `user_id` if the username exists.
+    pub async fn record_failure(
+        ps: &mut impl PrimaryStore,
+        user_id: Option<Uuid>,
+        ip: &str,
+    ) -> Result<()> {
+        ps.query_with(
+            Query::insert()
+                .into_table(Self::TABLE_NAME)
+                .columns(["user_id", "ip"])
+                .values_panic([user_id.into(), ip.into()]),
+        )
+        .execute()
+        .await?;
+        Ok(())
+    }
+
+    /// Count the failed logins of `user_id` since `since`.
+    pub async fn failures_for_user(
+        ps: &mut impl PrimaryStore,
+        user_id: Uuid,
+        since: OffsetDateTime,
+    ) -> Result<LoginFailureStats> {
+        Self::failure_stats(ps, Expr::col("user_id").eq(user_id), since).await
+    }
+
+    /// Count the failed logins from `ip` since `since`, for any username.
+    pub async fn failures_for_ip(
+        ps: &mut impl PrimaryStore,
+        ip: &str,
+        since: OffsetDateTime,
+    ) -> Result<LoginFailureStats> {
+        Self::failure_stats(ps, Expr::col("ip").eq(ip), since).await
+    }
+
+    async fn failure_stats(
+        ps: &mut impl PrimaryStore,
+        cond: Expr,
+        since: OffsetDateTime,
+    ) -> Result<LoginFailureStats> {
+        let stats = ps
+            .query_as_with::<LoginFailureStats>(
+                Query::select()
+                    .expr_as(Func::count(Expr::col("id")), "failures")
+                    .expr_as(Func::max(Expr::col("created_at")), "last_failure_at")
+                    .from(Self::TABLE_NAME)
+                    .and_where(cond)
+                    .and_where(Expr::col("created_at").gt(since)),
+            )
+            .fetch_one()
+            .await?;
+        Ok(stats)
+    }
+
+    /// List the failed logins of `user_id` that were not forgotten yet, newest first.
+    pub async fn list_recent_for_user(
+        ps: &mut impl PrimaryStore,
+        user_id: Uuid,
+        limit: u64,
+    ) -> Result<Vec<LoginAttempt>> {
+        let attempts = ps
+            .query_as_with::<LoginAttempt>(
+                Query::select()
+                    .columns(["ip", "created_at"])
+                    .from(Self::TABLE_NAME)
+                    .and_where(Expr::col("user_id").eq(user_id))
+                    .order_by("created_at", Order::Desc)
+                    .limit(limit),
+            )
+            .fetch_all()
+            .await?;
+        Ok(attempts)
+    }
+
+    /// Forget the failed logins of `user_id`, after a successful login or an unlock.
+    pub async fn clear_for_user(ps: &mut impl PrimaryStore, user_id: Uuid) -> Result<u64> {
+        Self::delete_cond(ps, Expr::col("user_id").eq(user_id)).await
+    }
+
+    /// Delete the failed logins older than `before`, which no longer count for anything.
+    pub async fn delete_older_than(
+        ps: &mut impl PrimaryStore,
+        before: OffsetDateTime,
+    ) -> Result<u64> {
+        Self::delete_cond(ps, Expr::col("created_at").lt(before)).await
+    }
+}
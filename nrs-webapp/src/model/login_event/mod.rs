@@ -0,0 +1,75 @@
+use sea_query::{Expr, ExprTrait, Order, Query};
+use sqlbindable::{FieldNames, Fields, HasFieldNames};
+use sqlx::FromRow;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use super::Result;
+use crate::model::{SqlxRow, entity::DbBmc, store::primary_store::PrimaryStore};
+
+/// The successful logins of the users.
+pub struct LoginEventBmc;
+
+impl DbBmc for LoginEventBmc {
+    const TABLE_NAME: &'static str = "login_event";
+}
+
+#[derive(FieldNames, Fields)]
+pub struct LoginEventForCreate {
+    pub user_id: Uuid,
+    /// `"password"`, or the name of the OAuth provider.
+    pub method: String,
+    pub ip: String,
+    pub user_agent: String,
+}
+
+#[derive(Debug, Clone, FromRow, FieldNames)]
+pub struct LoginEvent {
+    pub method: String,
+    pub ip: String,
+    pub user_agent: String,
+    pub created_at: OffsetDateTime,
+}
+
+impl LoginEventBmc {
+    /// Record a successful login.
+    pub async fn record(ps: &mut impl PrimaryStore, event: LoginEventForCreate) -> Result<()> {
+        Self::create(ps, event).await
+    }
+
+    /// List the most recent logins of `user_id`, newest first.
+    pub async fn list_recent_for_user(
+        ps: &mut impl PrimaryStore,
+        user_id: Uuid,
+        limit: u64,
+    ) -> Result<Vec<LoginEvent>> {
+        let events = ps
+            .query_as_with::<LoginEvent>(
+                Query::select()
+                    .columns(LoginEvent::field_names().iter().copied())
+                    .from(Self::TABLE_NAME)
+                    .and_where(Expr::col("user_id").eq(user_id))
+                    .order_by("created_at", Order::Desc)
+                    .limit(limit),
+            )
+            .fetch_all()
+            .await?;
+        Ok(events)
+    }
+
+    /// List all the recorded logins of `user_id`.
+    pub async fn list_for_user<E>(ps: &mut impl PrimaryStore, user_id: Uuid) -> Result<Vec<E>>
+    where
+        E: for<'r> FromRow<'r, SqlxRow> + Send + Unpin + HasFieldNames,
+    {
+        Self::get_all_by_expr(ps, Expr::col("user_id").eq(user_id)).await
+    }
+
+    /// Delete the logins older than `before`.
+    pub async fn delete_older_than(
+        ps: &mut impl PrimaryStore,
+        before: OffsetDateTime,
+    ) -> Result<u64> {
+        Self::delete_cond(ps, Expr::col("created_at").lt(before)).await
+    }
+}
@@ -4,15 +4,22 @@ use crate::{
     auth::external::AuthProviderRegistry,
     config::AppConfig,
     model::store::{Db, new_db_pool, primary_store::PrimaryStore},
+    monitoring::HttpCacheMetrics,
 };
 
+pub mod audit_event;
 pub mod entity;
 pub mod entry;
 mod error;
+pub mod login_attempt;
+pub mod login_event;
 pub mod oauth_links;
+pub mod outbound_mail;
+pub mod rate_limit;
 mod store;
 pub mod token;
 pub mod user;
+pub mod user_role;
 
 use axum::extract::FromRef;
 use axum_extra::extract::cookie::Key;
@@ -35,6 +42,15 @@ pub struct ModelManager {
 #[derive(Clone)]
 pub struct HttpClientWrapper(reqwest_middleware::ClientWithMiddleware);
 
+/// Usage of the database connection pool.
+#[derive(Debug, Clone, Copy)]
+pub struct DbPoolStatus {
+    /// Open connections, idle or in use.
+    pub size: u32,
+    pub idle: usize,
+    pub max_connections: u32,
+}
+
 impl Deref for HttpClientWrapper {
     type Target = reqwest_middleware::ClientWithMiddleware;
 
@@ -114,6 +130,7 @@ impl ModelManager {
                 .timeout(std::time::Duration::from_secs(10))
                 .build()?,
         )
+        .with(HttpCacheMetrics)
         .with(Cache(HttpCache {
             mode: CacheMode::Default,
             manager: http_cache_manager,
@@ -164,6 +181,27 @@ impl ModelManager {
         Ok(tx)
     }
 
+    /// Applies the pending schema migrations, returning how many were applied.
+    ///
+    /// See [`migration::run`](store::migration::run) for the checks done beforehand.
+    pub async fn migrate(&self) -> Result<usize> {
+        store::migration::run(&self.db, true).await
+    }
+
+    /// Checks that the schema is up to date, without applying anything.
+    pub async fn check_migrations(&self) -> Result<()> {
+        store::migration::run(&self.db, false).await.map(|_| ())
+    }
+
+    /// Get the current usage of the database connection pool.
+    pub fn db_pool_status(&self) -> DbPoolStatus {
+        DbPoolStatus {
+            size: self.db.size(),
+            idle: self.db.num_idle(),
+            max_connections: self.db.options().get_max_connections(),
+        }
+    }
+
     pub fn auth_providers(&self) -> &AuthProviderRegistry {
         &self.auth_providers
     }
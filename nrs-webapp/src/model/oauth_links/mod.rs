@@ -1,15 +1,24 @@
 use sea_query::{Expr, ExprTrait, IntoColumnRef, Query, ReturningClause};
-use sqlbindable::{BindContext, FieldNames, Fields, HasFields};
+use sqlbindable::{
+    BindContext, Field, FieldNames, FieldVec, Fields, HasFieldNames, HasFields, TryIntoExprError,
+};
+use sqlx::FromRow;
 use time::OffsetDateTime;
 use uuid::Uuid;
 
 use super::Result;
-use crate::model::{entity::DbBmc, store::primary_store::PrimaryStore};
+use crate::model::{
+    SqlxRow, audit_event::AuditContext, entity::DbBmc, store::primary_store::PrimaryStore,
+};
 
 pub struct OAuthLinkBmc;
 
 impl DbBmc for OAuthLinkBmc {
     const TABLE_NAME: &'static str = "app_user_oauth_link";
+    // revoked links are kept, so a provider can only be linked once per user at a time
+    const UNIQUE_KEY: &'static [&'static str] = &["user_id", "provider", "created_at"];
+    const AUDIT_ID_COLUMNS: &'static [&'static str] = Self::UNIQUE_KEY;
+    const AUDIT_REDACTED_COLUMNS: &'static [&'static str] = &["access_token", "refresh_token"];
 }
 
 #[derive(FieldNames, Fields)]
@@ -22,11 +31,46 @@ pub struct OAuthLinkForCreate {
     pub access_token_expires_at: Option<OffsetDateTime>,
 }
 
-#[derive(FieldNames, Fields)]
+#[derive(FieldNames)]
 pub struct OAuthLinkForUpdate {
     pub access_token: Vec<u8>,
+    // `None` keeps the current refresh token
+    pub refresh_token: Option<Vec<u8>>,
+    // `None` clears the expiry, the previous one does not apply to the new access token
+    pub access_token_expires_at: Option<OffsetDateTime>,
+}
+
+// not derived, since `access_token_expires_at` has to be written even when it is `None`
+impl HasFields for OAuthLinkForUpdate {
+    fn not_none_fields(self) -> std::result::Result<FieldVec, TryIntoExprError> {
+        let mut fields = vec![
+            Field::new("access_token", self.access_token)?,
+            Field::new("access_token_expires_at", self.access_token_expires_at)?,
+        ];
+        if let Some(refresh_token) = self.refresh_token {
+            fields.push(Field::new("refresh_token", refresh_token)?);
+        }
+        Ok(FieldVec(fields))
+    }
+
+    fn all_fields(self) -> std::result::Result<FieldVec, TryIntoExprError> {
+        Ok(FieldVec(vec![
+            Field::new("access_token", self.access_token)?,
+            Field::new("refresh_token", self.refresh_token)?,
+            Field::new("access_token_expires_at", self.access_token_expires_at)?,
+        ]))
+    }
+}
+
+#[derive(Debug, Clone, FieldNames, FromRow)]
+pub struct OAuthLink {
+    pub provider: String,
+    pub provider_user_id: Option<String>,
+    // encrypted with `SymmetricCipher`
+    pub access_token: Option<Vec<u8>>,
     pub refresh_token: Option<Vec<u8>>,
     pub access_token_expires_at: Option<OffsetDateTime>,
+    pub created_at: OffsetDateTime,
 }
 
 impl OAuthLinkBmc {
@@ -52,8 +96,116 @@ impl OAuthLinkBmc {
         Ok(ret.map(|(user_id,)| user_id))
     }
 
+    pub async fn list_for_user<E>(ps: &mut impl PrimaryStore, user_id: Uuid) -> Result<Vec<E>>
+    where
+        E: for<'r> FromRow<'r, SqlxRow> + Send + Unpin + HasFieldNames,
+    {
+        Self::get_all_by_expr(ps, Expr::col("user_id").eq(user_id)).await
+    }
+
+    pub async fn list_active_for_user<E>(
+        ps: &mut impl PrimaryStore,
+        user_id: Uuid,
+    ) -> Result<Vec<E>>
+    where
+        E: for<'r> FromRow<'r, SqlxRow> + Send + Unpin + HasFieldNames,
+    {
+        Self::get_all_by_expr(
+            ps,
+            Expr::col("user_id")
+                .eq(user_id)
+                .and(Expr::col("revoked_at").is_null()),
+        )
+        .await
+    }
+
+    /// Like [`list_active_for_user`](Self::list_active_for_user), for all the users in `user_ids`.
+    pub async fn list_active_for_users<E>(
+        ps: &mut impl PrimaryStore,
+        user_ids: &[Uuid],
+    ) -> Result<Vec<E>>
+    where
+        E: for<'r> FromRow<'r, SqlxRow> + Send + Unpin + HasFieldNames,
+    {
+        Self::get_all_by_expr(
+            ps,
+            Expr::col("user_id")
+                .is_in(user_ids.iter().copied())
+                .and(Expr::col("revoked_at").is_null()),
+        )
+        .await
+    }
+
+    pub async fn get_linked_user(
+        ps: &mut impl PrimaryStore,
+        provider_name: &str,
+        provider_user_id: &str,
+    ) -> Result<Option<Uuid>> {
+        #[derive(FieldNames, FromRow)]
+        struct LinkedUser {
+            user_id: Uuid,
+        }
+
+        let linked: Option<LinkedUser> = Self::get_optional_by_expr(
+            ps,
+            Expr::col("provider")
+                .eq(provider_name)
+                .and(Expr::col("provider_user_id").eq(provider_user_id))
+                .and(Expr::col("revoked_at").is_null()),
+        )
+        .await?;
+        Ok(linked.map(|l| l.user_id))
+    }
+
+    /// Fetch the active link of `user_id` with `provider_name`.
+    pub async fn get_active<E>(
+        ps: &mut impl PrimaryStore,
+        user_id: Uuid,
+        provider_name: &str,
+    ) -> Result<Option<E>>
+    where
+        E: for<'r> FromRow<'r, SqlxRow> + Send + Unpin + HasFieldNames,
+    {
+        Self::get_optional_by_expr(
+            ps,
+            Expr::col("user_id")
+                .eq(user_id)
+                .and(Expr::col("provider").eq(provider_name))
+                .and(Expr::col("revoked_at").is_null()),
+        )
+        .await
+    }
+
+    /// Store the tokens of `update_req` in the active link of `user_id` with `provider_name`,
+    /// if its refresh token is still `refresh_token`.
+    ///
+    /// Returns `false` if the link was revoked, or if its tokens were refreshed concurrently; the
+    /// refresh then happens without holding a lock on the link during the request to the
+    /// provider.
+    pub async fn update_tokens(
+        ps: &mut impl PrimaryStore,
+        user_id: Uuid,
+        provider_name: &str,
+        refresh_token: &[u8],
+        update_req: OAuthLinkForUpdate,
+    ) -> Result<bool> {
+        let num_affected = Self::update_cond(
+            ps,
+            update_req,
+            Expr::col("user_id")
+                .eq(user_id)
+                .and(Expr::col("provider").eq(provider_name))
+                .and(Expr::col("revoked_at").is_null())
+                .and(Expr::col("refresh_token").eq(refresh_token.to_vec())),
+        )
+        .await?;
+
+        Ok(num_affected > 0)
+    }
+
     pub async fn revoke(
         ps: &mut impl PrimaryStore,
+        audit: &AuditContext,
         user_id: Uuid,
         provider_name: &str,
     ) -> Result<()> {
@@ -66,8 +218,9 @@ impl OAuthLinkBmc {
             revoked_at: Expr::current_timestamp(),
         };
 
-        let num_affected = Self::update_cond(
+        let num_affected = Self::update_cond_audited(
             ps,
+            audit,
             payload,
             Expr::col("user_id")
                 .eq(user_id)
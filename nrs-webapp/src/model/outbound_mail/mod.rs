@@ -0,0 +1,275 @@
+use sea_query::{Expr, ExprTrait, LockBehavior, LockType, Order, Query, Value};
+use serde::Deserialize;
+use sqlbindable::{FieldNames, Fields, HasFieldNames, TryIntoExpr, TryIntoExprError};
+use sqlx::FromRow;
+use strum::IntoStaticStr;
+use time::{Duration, OffsetDateTime};
+
+use super::Result;
+use crate::model::{
+    audit_event::AuditContext,
+    entity::{DbBmc, DbBmcWithPkey},
+    store::primary_store::PrimaryStore,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, sqlx::Type, Deserialize, IntoStaticStr)]
+#[sqlx(type_name = "OUTBOUND_MAIL_STATUS")]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum OutboundMailStatus {
+    #[sqlx(rename = "PENDING")]
+    Pending,
+    #[sqlx(rename = "SENT")]
+    Sent,
+    /// Given up after too many failed attempts.
+    #[sqlx(rename = "DEAD")]
+    Dead,
+}
+
+impl OutboundMailStatus {
+    /// Get the SQL enum string of this status: `"PENDING"`, `"SENT"` or `"DEAD"`.
+    pub fn to_enum_string(&self) -> &'static str {
+        match self {
+            OutboundMailStatus::Pending => "PENDING",
+            OutboundMailStatus::Sent => "SENT",
+            OutboundMailStatus::Dead => "DEAD",
+        }
+    }
+}
+
+impl From<OutboundMailStatus> for Expr {
+    fn from(status: OutboundMailStatus) -> Self {
+        Value::String(Some(status.to_enum_string().into())).cast_as("OUTBOUND_MAIL_STATUS")
+    }
+}
+
+impl TryIntoExpr for OutboundMailStatus {
+    fn into_expr(self) -> core::result::Result<Expr, TryIntoExprError> {
+        Ok(self.into())
+    }
+}
+
+pub struct OutboundMailBmc;
+
+impl DbBmc for OutboundMailBmc {
+    const TABLE_NAME: &'static str = "outbound_mail";
+    const AUDIT_REDACTED_COLUMNS: &'static [&'static str] = &["html_body", "text_body"];
+}
+
+impl DbBmcWithPkey for OutboundMailBmc {
+    const PRIMARY_KEY: &'static str = "id";
+    type PkeyType = i64;
+}
+
+#[derive(FieldNames, Fields)]
+pub struct OutboundMailForCreate {
+    pub recipient: String,
+    pub sender: String,
+    pub subject: String,
+    // encrypted with `SymmetricCipher`
+    pub html_body: Vec<u8>,
+    pub text_body: Vec<u8>,
+}
+
+/// A mail claimed by the delivery worker.
+#[derive(Debug, FieldNames, FromRow)]
+pub struct DueOutboundMail {
+    pub id: i64,
+    pub recipient: String,
+    pub sender: String,
+    pub subject: String,
+    pub html_body: Option<Vec<u8>>,
+    pub text_body: Option<Vec<u8>>,
+    /// Delivery attempts, including the one it was claimed for.
+    pub attempts: i32,
+}
+
+/// The delivery status of a mail, as shown to the admins.
+#[derive(Debug, FieldNames, FromRow)]
+pub struct OutboundMailStatusInfo {
+    pub id: i64,
+    pub recipient: String,
+    pub subject: String,
+    pub status: OutboundMailStatus,
+    pub attempts: i32,
+    pub next_attempt_at: OffsetDateTime,
+    pub last_error: Option<String>,
+    pub created_at: OffsetDateTime,
+    pub sent_at: Option<OffsetDateTime>,
+}
+
+// `None` fields are skipped by updates, so the columns are cleared with an explicit `NULL`
+#[derive(FieldNames, Fields)]
+struct OutboundMailSent {
+    status: OutboundMailStatus,
+    html_body: Expr,
+    text_body: Expr,
+    last_error: Expr,
+    sent_at: Expr,
+}
+
+#[derive(FieldNames, Fields)]
+struct OutboundMailRetry {
+    last_error: String,
+    next_attempt_at: OffsetDateTime,
+}
+
+#[derive(FieldNames, Fields)]
+struct OutboundMailDead {
+    status: OutboundMailStatus,
+    last_error: String,
+}
+
+#[derive(FieldNames, Fields)]
+struct OutboundMailRequeue {
+    status: OutboundMailStatus,
+    attempts: i32,
+    next_attempt_at: Expr,
+}
+
+impl OutboundMailBmc {
+    /// Add a mail to the outbox, to be delivered by the worker once the transaction commits.
+    pub async fn enqueue(
+        ps: &mut impl PrimaryStore,
+        create_req: OutboundMailForCreate,
+    ) -> Result<i64> {
+        Self::create_returning_pkey(ps, create_req).await
+    }
+
+    /// Claim up to `limit` pending mails that are due, counting a delivery attempt for each.
+    ///
+    /// The claimed mails are not due again before `lease` has passed, so that other workers skip
+    /// them while they are being sent, and pick them up again if this worker dies before
+    /// recording the outcome.
+    pub async fn claim_due(
+        ps: &mut impl PrimaryStore,
+        limit: u64,
+        lease: Duration,
+    ) -> Result<Vec<DueOutboundMail>> {
+        let now = OffsetDateTime::now_utc();
+        let due_ids = Query::select()
+            .column("id")
+            .from(Self::TABLE_NAME)
+            .and_where(Expr::col("status").eq(OutboundMailStatus::Pending))
+            .and_where(Expr::col("next_attempt_at").lte(now))
+            .order_by("next_attempt_at", Order::Asc)
+            .limit(limit)
+            .lock_with_behavior(LockType::Update, LockBehavior::SkipLocked)
+            .to_owned();
+
+        let mails = ps
+            .query_as_with::<DueOutboundMail>(
+                Query::update()
+                    .table(Self::TABLE_NAME)
+                    .value("attempts", Expr::col("attempts").add(1))
+                    .value("next_attempt_at", now + lease)
+                    .and_where(Expr::col("id").in_subquery(due_ids))
+                    .returning(
+                        Query::returning().columns(DueOutboundMail::field_names().iter().copied()),
+                    ),
+            )
+            .fetch_all()
+            .await?;
+        Ok(mails)
+    }
+
+    /// Record that the mail was delivered, dropping its bodies.
+    pub async fn mark_sent(ps: &mut impl PrimaryStore, id: i64) -> Result<()> {
+        let sent = OutboundMailSent {
+            status: OutboundMailStatus::Sent,
+            html_body: Expr::null(),
+            text_body: Expr::null(),
+            last_error: Expr::null(),
+            sent_at: Expr::current_timestamp(),
+        };
+        Self::update(ps, sent, id).await
+    }
+
+    /// Record a failed delivery, to be attempted again at `next_attempt_at`.
+    pub async fn schedule_retry(
+        ps: &mut impl PrimaryStore,
+        id: i64,
+        error: String,
+        next_attempt_at: OffsetDateTime,
+    ) -> Result<()> {
+        let retry = OutboundMailRetry {
+            last_error: error,
+            next_attempt_at,
+        };
+        Self::update(ps, retry, id).await
+    }
+
+    /// Record a failed delivery after which the mail is given up on.
+    pub async fn mark_dead(ps: &mut impl PrimaryStore, id: i64, error: String) -> Result<()> {
+        let dead = OutboundMailDead {
+            status: OutboundMailStatus::Dead,
+            last_error: error,
+        };
+        Self::update(ps, dead, id).await
+    }
+
+    /// Put a dead mail back in the queue, with a fresh attempt budget.
+    ///
+    /// # Errors
+    ///
+    /// Returns `model::Error::EntityNotFound` if there is no dead mail with this id.
+    pub async fn requeue_dead(
+        ps: &mut impl PrimaryStore,
+        audit: &AuditContext,
+        id: i64,
+    ) -> Result<()> {
+        let requeue = OutboundMailRequeue {
+            status: OutboundMailStatus::Pending,
+            attempts: 0,
+            next_attempt_at: Expr::current_timestamp(),
+        };
+        let rows_affected = Self::update_cond_audited(
+            ps,
+            audit,
+            requeue,
+            Self::cond_pkey(id).and(Expr::col("status").eq(OutboundMailStatus::Dead)),
+        )
+        .await?;
+        if rows_affected == 0 {
+            return Err(Self::not_found_error(id));
+        }
+        Ok(())
+    }
+
+    /// List the most recent mails, optionally only those with `status`, newest first.
+    pub async fn list_recent(
+        ps: &mut impl PrimaryStore,
+        status: Option<OutboundMailStatus>,
+        limit: u64,
+    ) -> Result<Vec<OutboundMailStatusInfo>> {
+        let mut query = Query::select();
+        query
+            .from(Self::TABLE_NAME)
+            .columns(OutboundMailStatusInfo::field_names().iter().copied())
+            .order_by("created_at", Order::Desc)
+            .limit(limit);
+        if let Some(status) = status {
+            query.and_where(Expr::col("status").eq(status));
+        }
+
+        let mails = ps
+            .query_as_with::<OutboundMailStatusInfo>(&query)
+            .fetch_all()
+            .await?;
+        Ok(mails)
+    }
+
+    /// Delete the mails sent before `before`.
+    pub async fn delete_sent_before(
+        ps: &mut impl PrimaryStore,
+        before: OffsetDateTime,
+    ) -> Result<u64> {
+        Self::delete_cond(
+            ps,
+            Expr::col("status")
+                .eq(OutboundMailStatus::Sent)
+                .and(Expr::col("sent_at").lt(before)),
+        )
+        .await
+    }
+}
@@ -0,0 +1,72 @@
+use sea_query::{Expr, ExprTrait, IntoColumnRef, OnConflict, Query, ReturningClause};
+use sqlx::FromRow;
+use time::{Duration, OffsetDateTime};
+
+use super::Result;
+use crate::model::{entity::DbBmc, store::primary_store::PrimaryStore};
+
+pub struct RateLimitBucketBmc;
+
+impl DbBmc for RateLimitBucketBmc {
+    const TABLE_NAME: &'static str = "rate_limit_bucket";
+    const UNIQUE_KEY: &'static [&'static str] = &["key"];
+}
+
+#[derive(Debug, FromRow)]
+pub struct RateLimitBucket {
+    pub window_start: OffsetDateTime,
+    pub hits: i32,
+}
+
+impl RateLimitBucketBmc {
+    /// Count a hit for `key` in its current window of length `period`, starting a new window if
+    /// the previous one is over.
+    ///
+    /// Returns the bucket after the hit, so that the caller can compare `hits` with its quota.
+    pub async fn hit(
+        ps: &mut impl PrimaryStore,
+        key: &str,
+        period: Duration,
+    ) -> Result<RateLimitBucket> {
+        let now = OffsetDateTime::now_utc();
+        let window_over = || Expr::col((Self::TABLE_NAME, "window_start")).lte(now - period);
+
+        let bucket = ps
+            .query_as_with::<RateLimitBucket>(
+                Query::insert()
+                    .into_table(Self::TABLE_NAME)
+                    .columns(["key", "window_start", "hits"])
+                    .values_panic([key.into(), now.into(), 1.into()])
+                    .on_conflict(
+                        OnConflict::column("key")
+                            .value(
+                                "window_start",
+                                Expr::case(window_over(), now)
+                                    .finally(Expr::col((Self::TABLE_NAME, "window_start"))),
+                            )
+                            .value(
+                                "hits",
+                                Expr::case(window_over(), 1)
+                                    .finally(Expr::col((Self::TABLE_NAME, "hits")).add(1)),
+                            )
+                            .to_owned(),
+                    )
+                    .returning(ReturningClause::Columns(vec![
+                        "window_start".into_column_ref(),
+                        "hits".into_column_ref(),
+                    ])),
+            )
+            .fetch_one()
+            .await?;
+        Ok(bucket)
+    }
+
+    /// Delete the buckets whose window ended more than `max_period` ago.
+    pub async fn delete_expired(ps: &mut impl PrimaryStore, max_period: Duration) -> Result<u64> {
+        Self::delete_cond(
+            ps,
+            Expr::col("window_start").lt(OffsetDateTime::now_utc() - max_period),
+        )
+        .await
+    }
+}
@@ -4,6 +4,21 @@ use thiserror::Error;
 pub enum Error {
     #[error("Failed creating DB connection pool: {0}")]
     FailedCreatingDbPool(String),
+
+    #[error("Invalid migration file: {0}")]
+    InvalidMigrationFile(String),
+    #[error("Applied migration {version}-{name} was changed since it was applied")]
+    MigrationChecksumMismatch { version: i64, name: String },
+    #[error("Applied migration {version}-{name} is unknown to this build")]
+    UnknownAppliedMigration { version: i64, name: String },
+    #[error("Migration {version}-{name} is older than the latest applied migration {latest}")]
+    MigrationOutOfOrder {
+        version: i64,
+        name: String,
+        latest: i64,
+    },
+    #[error("Database schema is not up to date, pending migrations: {}", .0.join(", "))]
+    PendingMigrations(Vec<String>),
 }
 
 pub type Result<T> = core::result::Result<T, Error>;
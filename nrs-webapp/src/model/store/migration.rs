@@ -0,0 +1,299 @@
+//! Versioned schema migrations, embedded from `sql/migrations`.
+//!
+//! Each migration is a file named `<version>-<name>.sql`, applied once in version order inside
+//! its own transaction, and recorded with its checksum in the `_schema_migration` table.
+//! Migrations are up-only: a change to the schema is a new migration, and applied migrations
+//! must never be edited.
+
+use include_dir::{Dir, include_dir};
+use sha2::{Digest, Sha256};
+use sqlx::{FromRow, PgConnection};
+
+use super::{Db, Error};
+use crate::model::Result;
+
+static MIGRATIONS_DIR: Dir = include_dir!("$CARGO_MANIFEST_DIR/sql/migrations");
+
+// arbitrary, shared by every instance so that only one of them migrates at a time
+const MIGRATION_LOCK_KEY: i64 = 0x6e72_735f_6d69_6772;
+
+const CREATE_MIGRATION_TABLE: &str = "
+CREATE TABLE IF NOT EXISTS _schema_migration (
+  version BIGINT NOT NULL PRIMARY KEY,
+  name TEXT NOT NULL,
+  checksum BYTEA NOT NULL,
+  applied_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+)";
+
+#[derive(Debug)]
+struct Migration {
+    version: i64,
+    name: &'static str,
+    sql: &'static str,
+    checksum: Vec<u8>,
+}
+
+#[derive(Debug, FromRow)]
+struct AppliedMigration {
+    version: i64,
+    name: String,
+    checksum: Vec<u8>,
+}
+
+/// Parses `<version>-<name>.sql` into its version and name.
+fn parse_file_name(file_name: &str) -> Option<(i64, &str)> {
+    let (version, name) = file_name.strip_suffix(".sql")?.split_once('-')?;
+    let version = version.parse().ok()?;
+    (!name.is_empty()).then_some((version, name))
+}
+
+/// The embedded migrations, in version order.
+fn migrations() -> Result<Vec<Migration>> {
+    let mut migrations = MIGRATIONS_DIR
+        .files()
+        .map(|file| {
+            let file_name = file.path().to_str().unwrap_or_default();
+            let (version, name) = parse_file_name(file_name)
+                .ok_or_else(|| Error::InvalidMigrationFile(file_name.to_string()))?;
+            let sql = file
+                .contents_utf8()
+                .ok_or_else(|| Error::InvalidMigrationFile(file_name.to_string()))?;
+            Ok(Migration {
+                version,
+                name,
+                sql,
+                checksum: Sha256::digest(sql).to_vec(),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    migrations.sort_by_key(|migration| migration.version);
+    if let Some(pair) = migrations
+        .windows(2)
+        .find(|pair| pair[0].version == pair[1].version)
+    {
+        return Err(
+            Error::InvalidMigrationFile(format!("duplicate version {}", pair[0].version)).into(),
+        );
+    }
+    Ok(migrations)
+}
+
+/// Checks the applied migrations against the embedded ones, returning those still to apply.
+///
+/// # Errors
+///
+/// Fails if an applied migration was changed or is unknown to this build, or if a migration
+/// older than the latest applied one was never applied.
+fn pending_migrations(
+    migrations: Vec<Migration>,
+    applied: &[AppliedMigration],
+) -> Result<Vec<Migration>> {
+    for applied in applied {
+        let migration = migrations
+            .iter()
+            .find(|migration| migration.version == applied.version)
+            .ok_or_else(|| Error::UnknownAppliedMigration {
+                version: applied.version,
+                name: applied.name.clone(),
+            })?;
+        if migration.checksum != applied.checksum {
+            return Err(Error::MigrationChecksumMismatch {
+                version: migration.version,
+                name: migration.name.to_string(),
+            }
+            .into());
+        }
+    }
+
+    let latest_applied = applied.iter().map(|applied| applied.version).max();
+    let pending = migrations
+        .into_iter()
+        .filter(|migration| !applied.iter().any(|a| a.version == migration.version))
+        .collect::<Vec<_>>();
+    if let (Some(first), Some(latest)) = (pending.first(), latest_applied)
+        && first.version < latest
+    {
+        return Err(Error::MigrationOutOfOrder {
+            version: first.version,
+            name: first.name.to_string(),
+            latest,
+        }
+        .into());
+    }
+    Ok(pending)
+}
+
+async fn applied_migrations(conn: &mut PgConnection) -> Result<Vec<AppliedMigration>> {
+    sqlx::raw_sql(CREATE_MIGRATION_TABLE)
+        .execute(&mut *conn)
+        .await?;
+    let applied =
+        sqlx::query_as("SELECT version, name, checksum FROM _schema_migration ORDER BY version")
+            .fetch_all(&mut *conn)
+            .await?;
+    Ok(applied)
+}
+
+async fn apply(conn: &mut PgConnection, migration: &Migration) -> Result<()> {
+    tracing::info!(
+        "{:<12} -- Applying migration {}-{}",
+        "MIGRATION",
+        migration.version,
+        migration.name
+    );
+
+    let mut tx = sqlx::Connection::begin(&mut *conn).await?;
+    sqlx::raw_sql(migration.sql).execute(&mut *tx).await?;
+    sqlx::query("INSERT INTO _schema_migration (version, name, checksum) VALUES ($1, $2, $3)")
+        .bind(migration.version)
+        .bind(migration.name)
+        .bind(&migration.checksum)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Brings the schema of `db` up to date, or only checks that it is when `apply_pending` is
+/// `false`.
+///
+/// Returns the number of migrations applied.
+///
+/// # Errors
+///
+/// Besides database errors, fails without applying anything if an applied migration was
+/// changed or is unknown to this build, if a pending migration is older than the latest
+/// applied one, or if there are pending migrations and `apply_pending` is `false`.
+pub async fn run(db: &Db, apply_pending: bool) -> Result<usize> {
+    let migrations = migrations()?;
+
+    let mut conn = db.acquire().await?;
+    sqlx::query("SELECT pg_advisory_lock($1)")
+        .bind(MIGRATION_LOCK_KEY)
+        .execute(&mut *conn)
+        .await?;
+
+    let result = async {
+        let applied = applied_migrations(&mut conn).await?;
+        let pending = pending_migrations(migrations, &applied)?;
+        if !apply_pending && !pending.is_empty() {
+            return Err(Error::PendingMigrations(
+                pending
+                    .iter()
+                    .map(|migration| format!("{}-{}", migration.version, migration.name))
+                    .collect(),
+            )
+            .into());
+        }
+        for migration in &pending {
+            apply(&mut conn, migration).await?;
+        }
+        Ok(pending.len())
+    }
+    .await;
+
+    sqlx::query("SELECT pg_advisory_unlock($1)")
+        .bind(MIGRATION_LOCK_KEY)
+        .execute(&mut *conn)
+        .await?;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn migration(version: i64, sql: &'static str) -> Migration {
+        Migration {
+            version,
+            name: "test",
+            sql,
+            checksum: Sha256::digest(sql).to_vec(),
+        }
+    }
+
+    fn applied(migration: &Migration) -> AppliedMigration {
+        AppliedMigration {
+            version: migration.version,
+            name: migration.name.to_string(),
+            checksum: migration.checksum.clone(),
+        }
+    }
+
+    #[test]
+    fn parse_file_name_requires_version_and_name() {
+        assert_eq!(parse_file_name("0001-prelude.sql"), Some((1, "prelude")));
+        assert_eq!(
+            parse_file_name("0012-add-user-table.sql"),
+            Some((12, "add-user-table"))
+        );
+        assert_eq!(parse_file_name("prelude.sql"), None);
+        assert_eq!(parse_file_name("0001-.sql"), None);
+        assert_eq!(parse_file_name("0001-prelude.txt"), None);
+    }
+
+    #[test]
+    fn embedded_migrations_are_valid() {
+        let migrations = migrations().unwrap();
+        assert!(!migrations.is_empty());
+        assert!(
+            migrations
+                .windows(2)
+                .all(|pair| pair[0].version < pair[1].version)
+        );
+    }
+
+    #[test]
+    fn pending_migrations_skips_applied_ones() {
+        let applied = [applied(&migration(1, "SELECT 1"))];
+        let pending = pending_migrations(
+            vec![migration(1, "SELECT 1"), migration(2, "SELECT 2")],
+            &applied,
+        )
+        .unwrap();
+        assert_eq!(
+            pending.iter().map(|m| m.version).collect::<Vec<_>>(),
+            vec![2]
+        );
+    }
+
+    #[test]
+    fn pending_migrations_rejects_drift() {
+        let applied = [applied(&migration(1, "SELECT 1"))];
+
+        let changed = pending_migrations(vec![migration(1, "SELECT 42")], &applied);
+        assert!(matches!(
+            changed,
+            Err(crate::model::Error::Store(
+                Error::MigrationChecksumMismatch { version: 1, .. }
+            ))
+        ));
+
+        let unknown = pending_migrations(vec![migration(2, "SELECT 2")], &applied);
+        assert!(matches!(
+            unknown,
+            Err(crate::model::Error::Store(Error::UnknownAppliedMigration {
+                version: 1,
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn pending_migrations_rejects_out_of_order() {
+        let applied = [applied(&migration(2, "SELECT 2"))];
+        let result = pending_migrations(
+            vec![migration(1, "SELECT 1"), migration(2, "SELECT 2")],
+            &applied,
+        );
+        assert!(matches!(
+            result,
+            Err(crate::model::Error::Store(Error::MigrationOutOfOrder {
+                version: 1,
+                latest: 2,
+                ..
+            }))
+        ));
+    }
+}
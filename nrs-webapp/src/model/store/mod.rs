@@ -3,6 +3,7 @@ use sqlx::postgres::PgPoolOptions;
 use crate::config::AppConfig;
 
 mod error;
+pub mod migration;
 pub mod primary_store;
 pub use error::{Error, Result};
 
@@ -15,23 +15,28 @@ pub enum TokenPurpose {
     EmailVerification,
     #[sqlx(rename = "PASSWORD_RESET")]
     PasswordReset,
+    #[sqlx(rename = "ACCOUNT_UNLOCK")]
+    AccountUnlock,
 }
 
 impl TokenPurpose {
     /// Get the SQL enum string corresponding to this token purpose.
     ///
-    /// Returns the uppercase enum identifier used in the database: `"EMAIL_VERIFICATION"` or `"PASSWORD_RESET"`.
+    /// Returns the uppercase enum identifier used in the database: `"EMAIL_VERIFICATION"`,
+    /// `"PASSWORD_RESET"` or `"ACCOUNT_UNLOCK"`.
     ///
     /// # Examples
     ///
     /// ```
     /// assert_eq!(TokenPurpose::EmailVerification.to_enum_string(), "EMAIL_VERIFICATION");
     /// assert_eq!(TokenPurpose::PasswordReset.to_enum_string(), "PASSWORD_RESET");
+    /// assert_eq!(TokenPurpose::AccountUnlock.to_enum_string(), "ACCOUNT_UNLOCK");
     /// ```
     pub fn to_enum_string(&self) -> &'static str {
         match self {
             TokenPurpose::EmailVerification => "EMAIL_VERIFICATION",
             TokenPurpose::PasswordReset => "PASSWORD_RESET",
+            TokenPurpose::AccountUnlock => "ACCOUNT_UNLOCK",
         }
     }
 }
@@ -70,6 +75,7 @@ pub struct UserOneTimeTokenBmc;
 
 impl DbBmc for UserOneTimeTokenBmc {
     const TABLE_NAME: &'static str = "user_one_time_token";
+    const UNIQUE_KEY: &'static [&'static str] = &["token_hash"];
 }
 
 #[derive(Debug, Clone, FieldNames, Fields)]
@@ -1,10 +1,12 @@
-use sea_query::{Expr, ExprTrait};
-use sqlbindable::{FieldNames, Fields, HasFields};
+use sea_query::{Expr, ExprTrait, Order, Query, extension::postgres::PgExpr};
+use sqlbindable::{FieldNames, Fields, HasFieldNames, HasFields};
 use sqlx::FromRow;
+use time::OffsetDateTime;
 use uuid::Uuid;
 
 use crate::model::{
     Error, Result, SqlxRow,
+    audit_event::AuditContext,
     entity::{DbBmc, DbBmcWithPkey},
     store::primary_store::PrimaryStore,
 };
@@ -13,6 +15,8 @@ pub struct UserBmc;
 
 impl DbBmc for UserBmc {
     const TABLE_NAME: &'static str = "app_user";
+    // the email is personal data that must go away with a deleted account
+    const AUDIT_REDACTED_COLUMNS: &'static [&'static str] = &["email", "password_hash"];
 }
 
 impl DbBmcWithPkey for UserBmc {
@@ -37,6 +41,23 @@ struct UserResetPassword {
     pub password_hash: String,
 }
 
+#[derive(FieldNames, Fields)]
+struct UserLock {
+    pub locked_until: OffsetDateTime,
+}
+
+// `None` fields are skipped by updates, so the column is cleared with an explicit `NULL`
+#[derive(FieldNames, Fields)]
+struct UserUnlock {
+    pub locked_until: Expr,
+}
+
+// cleared with an explicit `NULL` like `UserUnlock`
+#[derive(FieldNames, Fields)]
+struct UserSetDisabled {
+    pub disabled_at: Expr,
+}
+
 impl Default for UserMarkEmailVerified {
     /// Creates a `UserMarkEmailVerified` where `email_verified_at` is set to the current timestamp.
     ///
@@ -69,15 +90,16 @@ impl UserBmc {
     ///     email: "alice@example.com".into(),
     ///     password_hash: "hash".into(),
     /// };
-    /// let user_id = UserBmc::create_user(mm, req).await?;
+    /// let user_id = UserBmc::create_user(mm, &AuditContext::default(), req).await?;
     /// assert!(!user_id.is_empty());
     /// # Ok(()) }
     /// ```
     pub async fn create_user(
         mm: &mut impl PrimaryStore,
+        audit: &AuditContext,
         create_req: UserForCreate,
     ) -> Result<Uuid> {
-        <Self as DbBmcWithPkey>::create_returning_pkey(mm, create_req)
+        <Self as DbBmcWithPkey>::create_returning_pkey_audited(mm, audit, create_req)
             .await
             .map_err(|e| match e {
                 Error::Sqlx(sqlx::Error::Database(db_err)) if db_err.is_unique_violation() => {
@@ -148,11 +170,21 @@ impl UserBmc {
     /// # async fn example() -> Result<(), Box<dyn std::error::Error>> {
     /// let mut mm = /* obtain a PrimaryStore implementor */ unimplemented!();
     /// let id = Uuid::new_v4();
-    /// mark_email_verified(&mut mm, id).await?;
+    /// mark_email_verified(&mut mm, &AuditContext::default(), id).await?;
     /// # Ok(()) }
     /// ```
-    pub async fn mark_email_verified(mm: &mut impl PrimaryStore, user_id: Uuid) -> Result<()> {
-        <Self as DbBmcWithPkey>::update(mm, UserMarkEmailVerified::default(), user_id).await
+    pub async fn mark_email_verified(
+        mm: &mut impl PrimaryStore,
+        audit: &AuditContext,
+        user_id: Uuid,
+    ) -> Result<()> {
+        <Self as DbBmcWithPkey>::update_audited(
+            mm,
+            audit,
+            UserMarkEmailVerified::default(),
+            user_id,
+        )
+        .await
     }
 
     /// Update a user's stored password hash.
@@ -174,14 +206,167 @@ impl UserBmc {
     /// # async fn example(mm: &mut impl PrimaryStore) -> Result<(), Box<dyn std::error::Error>> {
     /// let user_id = "user-123".to_string();
     /// let password_hash = "new_hashed_password".to_string();
-    /// UserBmc::reset_password(mm, user_id, password_hash).await?;
+    /// UserBmc::reset_password(mm, &AuditContext::default(), user_id, password_hash).await?;
     /// # Ok(()) }
     /// ```
     pub async fn reset_password(
         mm: &mut impl PrimaryStore,
+        audit: &AuditContext,
         user_id: Uuid,
         password_hash: String,
     ) -> Result<()> {
-        <Self as DbBmcWithPkey>::update(mm, UserResetPassword { password_hash }, user_id).await
+        <Self as DbBmcWithPkey>::update_audited(
+            mm,
+            audit,
+            UserResetPassword { password_hash },
+            user_id,
+        )
+        .await
+    }
+
+    /// Locks password logins of the user until `until`.
+    pub async fn lock_until(
+        mm: &mut impl PrimaryStore,
+        audit: &AuditContext,
+        user_id: Uuid,
+        until: OffsetDateTime,
+    ) -> Result<()> {
+        <Self as DbBmcWithPkey>::update_audited(
+            mm,
+            audit,
+            UserLock {
+                locked_until: until,
+            },
+            user_id,
+        )
+        .await
+    }
+
+    /// Lifts the login lockout of the user, if any.
+    pub async fn unlock(
+        mm: &mut impl PrimaryStore,
+        audit: &AuditContext,
+        user_id: Uuid,
+    ) -> Result<()> {
+        let unlock = UserUnlock {
+            locked_until: Expr::null(),
+        };
+        <Self as DbBmcWithPkey>::update_audited(mm, audit, unlock, user_id).await
+    }
+
+    /// Disables or re-enables the account of the user.
+    ///
+    /// A disabled user can no longer log in, and their existing sessions are refused by
+    /// `mw_req_session`.
+    pub async fn set_disabled(
+        mm: &mut impl PrimaryStore,
+        audit: &AuditContext,
+        user_id: Uuid,
+        disabled: bool,
+    ) -> Result<()> {
+        let update = UserSetDisabled {
+            disabled_at: if disabled {
+                Expr::current_timestamp()
+            } else {
+                Expr::null()
+            },
+        };
+        <Self as DbBmcWithPkey>::update_audited(mm, audit, update, user_id).await
+    }
+
+    /// Whether the user exists and their account is not disabled.
+    pub async fn is_active(mm: &mut impl PrimaryStore, user_id: Uuid) -> Result<bool> {
+        let (active,) = mm
+            .query_as_with::<(bool,)>(
+                Query::select().expr(Expr::exists(
+                    Query::select()
+                        .expr(Expr::val(1))
+                        .from(Self::TABLE_NAME)
+                        .and_where(Expr::col("id").eq(user_id))
+                        .and_where(Expr::col("disabled_at").is_null())
+                        .to_owned(),
+                )),
+            )
+            .fetch_one()
+            .await?;
+        Ok(active)
+    }
+
+    /// Lists the users whose username or email contains `search`, ignoring case, or all users
+    /// without a search, the most recently registered first.
+    pub async fn search<E>(
+        mm: &mut impl PrimaryStore,
+        search: Option<&str>,
+        limit: u64,
+    ) -> Result<Vec<E>>
+    where
+        E: for<'r> FromRow<'r, SqlxRow> + Unpin + Send + HasFieldNames,
+    {
+        let mut query = Query::select();
+        query
+            .from(Self::TABLE_NAME)
+            .columns(E::field_names().iter().copied())
+            .order_by("created_at", Order::Desc)
+            .limit(limit);
+        if let Some(search) = search {
+            let pattern = contains_pattern(search);
+            // LIKE is not supported on the nondeterministic `case_insensitive` collation
+            query.and_where(
+                Expr::cust(r#""username" COLLATE "C""#)
+                    .ilike(pattern.as_str())
+                    .or(Expr::cust(r#""email" COLLATE "C""#).ilike(pattern.as_str())),
+            );
+        }
+        let users = mm.query_as_with::<E>(&query).fetch_all().await?;
+        Ok(users)
+    }
+
+    /// Permanently deletes the user identified by `user_id`.
+    ///
+    /// One-time tokens and OAuth links are removed through `ON DELETE CASCADE`, while entries
+    /// added by the user are kept and have their `added_by` reference set to `NULL`.
+    ///
+    /// # Returns
+    ///
+    /// `Ok(())` if the user was deleted, `Err(Error::EntityNotFound)` if no such user exists.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # async fn example(mm: &mut impl PrimaryStore, user_id: Uuid) -> Result<()> {
+    /// UserBmc::delete_user(mm, &AuditContext::default(), user_id).await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn delete_user(
+        mm: &mut impl PrimaryStore,
+        audit: &AuditContext,
+        user_id: Uuid,
+    ) -> Result<()> {
+        <Self as DbBmcWithPkey>::delete_audited(mm, audit, user_id).await
+    }
+}
+
+/// A `LIKE` pattern matching the strings containing `search`, with its wildcards escaped.
+fn contains_pattern(search: &str) -> String {
+    let mut pattern = String::with_capacity(search.len() + 2);
+    pattern.push('%');
+    for c in search.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    pattern
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn contains_pattern_escapes_wildcards() {
+        assert_eq!(contains_pattern("alice"), "%alice%");
+        assert_eq!(contains_pattern("50%_a\\b"), "%50\\%\\_a\\\\b%");
     }
 }
@@ -0,0 +1,182 @@
+use sea_query::{Expr, ExprTrait, OnConflict, Query, Value};
+use serde::Deserialize;
+use sqlbindable::{BindContext, FieldNames, Fields, HasFields, TryIntoExpr, TryIntoExprError};
+use sqlx::{FromRow, types::Json};
+use strum::IntoStaticStr;
+use uuid::Uuid;
+
+use super::Result;
+use crate::model::{
+    audit_event::{AuditAction, AuditContext, AuditEventBmc, RowSnapshot},
+    entity::DbBmc,
+    store::primary_store::PrimaryStore,
+};
+
+/// A role granted to a user.
+///
+/// Roles are ordered: each role includes the permissions of the roles below it, so that an
+/// admin can do everything a moderator can, and a moderator everything an editor can.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, sqlx::Type, Deserialize, IntoStaticStr,
+)]
+#[sqlx(type_name = "USER_ROLE")]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum Role {
+    /// Edits entries.
+    #[sqlx(rename = "EDITOR")]
+    Editor,
+    /// Moderates the content and the edits of the other users.
+    #[sqlx(rename = "MODERATOR")]
+    Moderator,
+    /// Manages the users and the service.
+    #[sqlx(rename = "ADMIN")]
+    Admin,
+}
+
+impl Role {
+    /// Every role, from the least to the most privileged.
+    pub const ALL: [Role; 3] = [Role::Editor, Role::Moderator, Role::Admin];
+
+    /// Get the SQL enum string of this role: `"ADMIN"`, `"MODERATOR"` or `"EDITOR"`.
+    pub fn to_enum_string(&self) -> &'static str {
+        match self {
+            Role::Admin => "ADMIN",
+            Role::Moderator => "MODERATOR",
+            Role::Editor => "EDITOR",
+        }
+    }
+
+    /// Whether this role grants the permissions of `other`.
+    pub fn includes(self, other: Role) -> bool {
+        self >= other
+    }
+}
+
+impl From<Role> for Expr {
+    fn from(role: Role) -> Self {
+        Value::String(Some(role.to_enum_string().into())).cast_as("USER_ROLE")
+    }
+}
+
+impl TryIntoExpr for Role {
+    fn into_expr(self) -> core::result::Result<Expr, TryIntoExprError> {
+        Ok(self.into())
+    }
+}
+
+pub struct UserRoleBmc;
+
+impl DbBmc for UserRoleBmc {
+    const TABLE_NAME: &'static str = "app_user_role";
+    const AUDIT_ID_COLUMNS: &'static [&'static str] = &["user_id"];
+    const UNIQUE_KEY: &'static [&'static str] = &["user_id", "role"];
+}
+
+#[derive(FieldNames, Fields)]
+struct UserRoleForCreate {
+    user_id: Uuid,
+    role: Role,
+    granted_by: Option<Uuid>,
+}
+
+#[derive(FieldNames, FromRow)]
+struct UserRoleRow {
+    role: Role,
+}
+
+impl UserRoleBmc {
+    /// List the roles granted to `user_id`.
+    pub async fn list_for_user(ps: &mut impl PrimaryStore, user_id: Uuid) -> Result<Vec<Role>> {
+        let rows: Vec<UserRoleRow> =
+            Self::get_all_by_expr(ps, Expr::col("user_id").eq(user_id)).await?;
+        Ok(rows.into_iter().map(|row| row.role).collect())
+    }
+
+    /// Whether any user has been granted `role`.
+    pub async fn any_with_role(ps: &mut impl PrimaryStore, role: Role) -> Result<bool> {
+        let row = ps
+            .query_as_with::<(bool,)>(
+                Query::select().expr(Expr::exists(
+                    Query::select()
+                        .expr(Expr::val(1))
+                        .from(Self::TABLE_NAME)
+                        .and_where(Expr::col("role").eq(role))
+                        .to_owned(),
+                )),
+            )
+            .fetch_one()
+            .await?;
+        Ok(row.0)
+    }
+
+    /// Grant `role` to `user_id` on behalf of the actor of `audit`.
+    ///
+    /// Returns `false` if the user already had the role, including when it was granted
+    /// concurrently, e.g. by another instance bootstrapping the same admin.
+    pub async fn grant(
+        ps: &mut impl PrimaryStore,
+        audit: &AuditContext,
+        user_id: Uuid,
+        role: Role,
+    ) -> Result<bool> {
+        let create_req = UserRoleForCreate {
+            user_id,
+            role,
+            granted_by: audit.actor_id,
+        };
+        let created = ps
+            .query_as_with::<(Json<RowSnapshot>,)>(
+                Query::insert()
+                    .into_table(Self::TABLE_NAME)
+                    .bind(create_req.not_none_fields()?)
+                    .on_conflict(
+                        OnConflict::columns(Self::UNIQUE_KEY.iter().copied())
+                            .do_nothing()
+                            .to_owned(),
+                    )
+                    .returning(Query::returning().expr(Self::row_snapshot())),
+            )
+            .fetch_optional()
+            .await?;
+        let Some((Json(after),)) = created else {
+            return Ok(false);
+        };
+        AuditEventBmc::record::<Self>(ps, audit, AuditAction::Create, None, Some(&after)).await?;
+        Ok(true)
+    }
+
+    /// Revoke `role` from `user_id`.
+    ///
+    /// Returns `false` if the user did not have the role.
+    pub async fn revoke(
+        ps: &mut impl PrimaryStore,
+        audit: &AuditContext,
+        user_id: Uuid,
+        role: Role,
+    ) -> Result<bool> {
+        let rows_affected = Self::delete_cond_audited(
+            ps,
+            audit,
+            Expr::col("user_id")
+                .eq(user_id)
+                .and(Expr::col("role").eq(role)),
+        )
+        .await?;
+        Ok(rows_affected > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn roles_include_the_less_privileged_ones() {
+        assert!(Role::Admin.includes(Role::Moderator));
+        assert!(Role::Admin.includes(Role::Editor));
+        assert!(Role::Moderator.includes(Role::Moderator));
+        assert!(!Role::Moderator.includes(Role::Admin));
+        assert!(!Role::Editor.includes(Role::Moderator));
+    }
+}
@@ -0,0 +1,154 @@
+//! Prometheus metrics of the service, served at `/metrics`.
+//!
+//! The metrics are recorded with the `metrics` macros, which do nothing until
+//! [`install_recorder`] is called, so tests and tools do not need to set anything up.
+
+use std::{sync::OnceLock, time::Duration};
+
+use async_trait::async_trait;
+use axum::http::{Extensions, Method, StatusCode};
+use metrics::{
+    Unit, counter, describe_counter, describe_gauge, describe_histogram, gauge, histogram,
+};
+use metrics_exporter_prometheus::{Matcher, PrometheusBuilder, PrometheusHandle};
+use reqwest_middleware::{Middleware, Next};
+
+use crate::model::ModelManager;
+
+const HTTP_REQUESTS: &str = "http_requests_total";
+const HTTP_REQUEST_DURATION: &str = "http_request_duration_seconds";
+const DB_POOL_CONNECTIONS: &str = "db_pool_connections";
+const DB_POOL_MAX_CONNECTIONS: &str = "db_pool_max_connections";
+const HTTP_CLIENT_CACHE_REQUESTS: &str = "http_client_cache_requests_total";
+const MAIL_DELIVERIES: &str = "mail_deliveries_total";
+const RATE_LIMIT_REJECTIONS: &str = "rate_limit_rejections_total";
+
+const HTTP_REQUEST_DURATION_BUCKETS: &[f64] = &[
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+// set by `http_cache_reqwest::Cache` to `HIT` or `MISS`
+const CACHE_STATUS_HEADER: &str = "x-cache";
+
+static HANDLE: OnceLock<PrometheusHandle> = OnceLock::new();
+
+/// Installs the global recorder collecting the metrics for [`render`].
+///
+/// # Errors
+///
+/// Returns an error if a recorder was already installed.
+pub fn install_recorder() -> anyhow::Result<()> {
+    let handle = PrometheusBuilder::new()
+        .set_buckets_for_metric(
+            Matcher::Full(HTTP_REQUEST_DURATION.into()),
+            HTTP_REQUEST_DURATION_BUCKETS,
+        )?
+        .install_recorder()?;
+    // the recorder is global, so the handle can only be set once too
+    let _ = HANDLE.set(handle);
+
+    describe_counter!(HTTP_REQUESTS, "Handled HTTP requests, by route and status.");
+    describe_histogram!(
+        HTTP_REQUEST_DURATION,
+        Unit::Seconds,
+        "Time taken to handle HTTP requests, by route and status."
+    );
+    describe_gauge!(
+        DB_POOL_CONNECTIONS,
+        "Open database connections, idle or in use."
+    );
+    describe_gauge!(
+        DB_POOL_MAX_CONNECTIONS,
+        "Maximum number of database connections of the pool."
+    );
+    describe_counter!(
+        HTTP_CLIENT_CACHE_REQUESTS,
+        "Outgoing HTTP requests, by whether they were served from the cache."
+    );
+    describe_counter!(MAIL_DELIVERIES, "Mail delivery attempts, by outcome.");
+    describe_counter!(
+        RATE_LIMIT_REJECTIONS,
+        "Requests rejected by a rate-limit policy, by policy."
+    );
+    Ok(())
+}
+
+/// Renders the metrics in the Prometheus text format, or `None` if no recorder is installed.
+///
+/// The gauges sampling the state of `mm`, such as the database pool usage, are updated first.
+pub fn render(mm: &ModelManager) -> Option<String> {
+    let handle = HANDLE.get()?;
+
+    let pool = mm.db_pool_status();
+    gauge!(DB_POOL_CONNECTIONS, "state" => "idle").set(pool.idle as f64);
+    gauge!(DB_POOL_CONNECTIONS, "state" => "in_use")
+        .set(pool.size.saturating_sub(pool.idle as u32) as f64);
+    gauge!(DB_POOL_MAX_CONNECTIONS).set(pool.max_connections as f64);
+
+    Some(handle.render())
+}
+
+/// Records a handled HTTP request.
+///
+/// `route` must be the route pattern rather than the actual path, to keep the number of label
+/// values bounded.
+pub fn record_request(method: &Method, route: &str, status: StatusCode, duration: Duration) {
+    let labels = [
+        ("method", method.to_string()),
+        ("route", route.to_string()),
+        ("status", status.as_u16().to_string()),
+    ];
+    counter!(HTTP_REQUESTS, &labels).increment(1);
+    histogram!(HTTP_REQUEST_DURATION, &labels).record(duration.as_secs_f64());
+}
+
+/// The outcome of a mail delivery attempt.
+#[derive(Debug, Clone, Copy)]
+pub enum MailDeliveryOutcome {
+    Sent,
+    /// Failed, and will be attempted again.
+    Retried,
+    /// Failed for the last time.
+    Dead,
+}
+
+/// Records a mail delivery attempt.
+pub fn record_mail_delivery(outcome: MailDeliveryOutcome) {
+    let outcome = match outcome {
+        MailDeliveryOutcome::Sent => "sent",
+        MailDeliveryOutcome::Retried => "retried",
+        MailDeliveryOutcome::Dead => "dead",
+    };
+    counter!(MAIL_DELIVERIES, "outcome" => outcome).increment(1);
+}
+
+/// Records a request rejected by the rate-limit `policy`.
+pub fn record_rate_limit_rejection(policy: &str) {
+    counter!(RATE_LIMIT_REJECTIONS, "policy" => policy.to_string()).increment(1);
+}
+
+/// Counts the requests of the `http_client` by cache status, using the header the cache
+/// middleware adds to the responses.
+///
+/// It must be added before the cache middleware, so that it sees the final responses.
+pub struct HttpCacheMetrics;
+
+#[async_trait]
+impl Middleware for HttpCacheMetrics {
+    async fn handle(
+        &self,
+        req: reqwest::Request,
+        extensions: &mut Extensions,
+        next: Next<'_>,
+    ) -> reqwest_middleware::Result<reqwest::Response> {
+        let resp = next.run(req, extensions).await?;
+        let result = match resp.headers().get(CACHE_STATUS_HEADER) {
+            Some(value) if value == "HIT" => "hit",
+            Some(_) => "miss",
+            // the cache did not handle the request
+            None => return Ok(resp),
+        };
+        counter!(HTTP_CLIENT_CACHE_REQUESTS, "result" => result).increment(1);
+        Ok(resp)
+    }
+}
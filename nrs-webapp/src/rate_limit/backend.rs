@@ -0,0 +1,126 @@
+use std::{collections::HashMap, time::Duration};
+
+use async_trait::async_trait;
+use governor::{
+    DefaultKeyedRateLimiter, Quota, RateLimiter,
+    clock::{Clock, DefaultClock},
+};
+
+use crate::{
+    Result,
+    model::{ModelManager, rate_limit::RateLimitBucketBmc},
+    rate_limit::RateLimitPolicy,
+};
+
+/// Storage for the rate-limit counters.
+#[async_trait]
+pub trait RateLimitBackend: Send + Sync {
+    /// Counts a request for `key` against `policy`.
+    ///
+    /// Returns `None` if the request is allowed, or how long to wait before retrying if the
+    /// quota of the policy is exhausted.
+    async fn hit(
+        &self,
+        mm: &ModelManager,
+        policy: &RateLimitPolicy,
+        key: &str,
+    ) -> Result<Option<Duration>>;
+
+    /// Forgets the counters that no longer limit anything.
+    async fn cleanup(&self, mm: &ModelManager) -> Result<()>;
+}
+
+/// Per-instance counters, using a GCRA limiter per policy.
+pub struct InMemoryBackend {
+    limiters: HashMap<String, DefaultKeyedRateLimiter<String>>,
+}
+
+impl InMemoryBackend {
+    pub fn new<'a>(policies: impl IntoIterator<Item = &'a RateLimitPolicy>) -> Self {
+        let limiters = policies
+            .into_iter()
+            .map(|policy| {
+                let quota = Quota::with_period(policy.period / policy.max_requests.get())
+                    .expect("non-zero period")
+                    .allow_burst(policy.max_requests);
+                (policy.name.clone(), RateLimiter::keyed(quota))
+            })
+            .collect();
+        Self { limiters }
+    }
+}
+
+#[async_trait]
+impl RateLimitBackend for InMemoryBackend {
+    async fn hit(
+        &self,
+        _mm: &ModelManager,
+        policy: &RateLimitPolicy,
+        key: &str,
+    ) -> Result<Option<Duration>> {
+        let Some(limiter) = self.limiters.get(&policy.name) else {
+            return Ok(None);
+        };
+        Ok(limiter
+            .check_key(&key.to_string())
+            .err()
+            .map(|not_until| not_until.wait_time_from(DefaultClock::default().now())))
+    }
+
+    async fn cleanup(&self, _mm: &ModelManager) -> Result<()> {
+        for limiter in self.limiters.values() {
+            limiter.retain_recent();
+            limiter.shrink_to_fit();
+        }
+        Ok(())
+    }
+}
+
+/// Fixed-window counters in the `rate_limit_bucket` table, shared by every instance.
+pub struct PostgresBackend {
+    max_period: Duration,
+}
+
+impl PostgresBackend {
+    pub fn new<'a>(policies: impl IntoIterator<Item = &'a RateLimitPolicy>) -> Self {
+        let max_period = policies
+            .into_iter()
+            .map(|policy| policy.period)
+            .max()
+            .unwrap_or_default();
+        Self { max_period }
+    }
+}
+
+#[async_trait]
+impl RateLimitBackend for PostgresBackend {
+    async fn hit(
+        &self,
+        mm: &ModelManager,
+        policy: &RateLimitPolicy,
+        key: &str,
+    ) -> Result<Option<Duration>> {
+        let period = time::Duration::try_from(policy.period).expect("period out of range");
+        let bucket =
+            RateLimitBucketBmc::hit(&mut mm.clone(), &format!("{}:{}", policy.name, key), period)
+                .await?;
+
+        if bucket.hits as u32 <= policy.max_requests.get() {
+            return Ok(None);
+        }
+
+        let retry_after = bucket.window_start + period - time::OffsetDateTime::now_utc();
+        Ok(Some(retry_after.try_into().unwrap_or_default()))
+    }
+
+    async fn cleanup(&self, mm: &ModelManager) -> Result<()> {
+        let max_period = time::Duration::try_from(self.max_period).expect("period out of range");
+        let deleted = RateLimitBucketBmc::delete_expired(&mut mm.clone(), max_period).await?;
+        tracing::debug!(
+            "{:<12} -- Deleted {} expired rate-limit buckets",
+            "RATE-LIMIT",
+            deleted
+        );
+        Ok(())
+    }
+}
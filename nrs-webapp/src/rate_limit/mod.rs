@@ -0,0 +1,166 @@
+use std::{
+    collections::HashMap,
+    num::NonZeroU32,
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
+
+use axum::http::Method;
+
+use crate::{
+    Error, Result,
+    config::{AppConfig, RateLimitBackendKind, RateLimitKeyKind},
+    model::ModelManager,
+    monitoring,
+    rate_limit::backend::{InMemoryBackend, PostgresBackend, RateLimitBackend},
+};
+
+mod backend;
+
+pub use crate::config::rate_limit_policy as policy;
+
+const CLEANUP_INTERVAL: Duration = Duration::from_secs(600);
+
+#[derive(Debug, Clone)]
+pub struct RateLimitPolicy {
+    pub name: String,
+    pub max_requests: NonZeroU32,
+    pub period: Duration,
+    pub key: RateLimitKeyKind,
+}
+
+/// Applies the rate-limit policies from `AppConfig::RATE_LIMIT` using the configured backend.
+pub struct RateLimiter {
+    backend: Box<dyn RateLimitBackend>,
+    policies: HashMap<String, Arc<RateLimitPolicy>>,
+    routes: HashMap<String, Vec<Arc<RateLimitPolicy>>>,
+}
+
+impl RateLimiter {
+    pub fn from_config() -> Self {
+        let config = &AppConfig::get().RATE_LIMIT;
+
+        let policies: HashMap<_, _> = config
+            .policies
+            .iter()
+            .map(|(name, policy)| {
+                let policy = RateLimitPolicy {
+                    name: name.clone(),
+                    max_requests: policy.max_requests,
+                    period: Duration::from_secs(policy.period_secs.get()),
+                    key: policy.key,
+                };
+                (name.clone(), Arc::new(policy))
+            })
+            .collect();
+
+        // the config loader already made sure that every route policy exists
+        let routes = config
+            .routes
+            .iter()
+            .map(|(route, names)| {
+                let route_policies = names
+                    .iter()
+                    .filter_map(|name| policies.get(name).cloned())
+                    .collect();
+                (route.clone(), route_policies)
+            })
+            .collect();
+
+        let backend: Box<dyn RateLimitBackend> = match config.backend {
+            RateLimitBackendKind::Memory => {
+                Box::new(InMemoryBackend::new(policies.values().map(Arc::as_ref)))
+            }
+            RateLimitBackendKind::Postgres => {
+                Box::new(PostgresBackend::new(policies.values().map(Arc::as_ref)))
+            }
+        };
+
+        Self {
+            backend,
+            policies,
+            routes,
+        }
+    }
+
+    pub fn get_from_config() -> &'static Self {
+        static INSTANCE: OnceLock<RateLimiter> = OnceLock::new();
+        INSTANCE.get_or_init(Self::from_config)
+    }
+
+    /// The policies bound to the route matched by `method` and `matched_path`.
+    pub fn route_policies(&self, method: &Method, matched_path: &str) -> &[Arc<RateLimitPolicy>] {
+        self.routes
+            .get(&format!("{} {}", method, matched_path))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// Counts a request for `key` against `policy`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::RateLimitExceeded` with the time to wait when the quota is exhausted.
+    /// Unknown policies never limit anything.
+    pub async fn check_policy(
+        &self,
+        mm: &ModelManager,
+        policy: &RateLimitPolicy,
+        key: &str,
+    ) -> Result<()> {
+        match self.backend.hit(mm, policy, key).await? {
+            None => Ok(()),
+            Some(retry_after) => {
+                tracing::debug!(
+                    "{:<12} -- Rate limit {} exceeded, retry after {:?}",
+                    "RATE-LIMIT",
+                    policy.name,
+                    retry_after
+                );
+                monitoring::record_rate_limit_rejection(&policy.name);
+                Err(Error::RateLimitExceeded {
+                    policy: policy.name.clone(),
+                    retry_after,
+                })
+            }
+        }
+    }
+
+    /// Periodically removes stale counters from the backend.
+    pub fn spawn_cleanup(&'static self, mm: ModelManager) {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(CLEANUP_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(err) = self.backend.cleanup(&mm).await {
+                    tracing::warn!(
+                        "{:<12} -- Failed to clean up rate-limit counters: {}",
+                        "RATE-LIMIT",
+                        err
+                    );
+                }
+            }
+        });
+    }
+}
+
+/// Counts a request against the policy named `policy`, keyed by `key` (e.g. a lowercased username
+/// or email address).
+///
+/// # Examples
+///
+/// ```no_run
+/// rate_limit::check(&mm, policy::LOGIN_USERNAME, &username.to_lowercase()).await?;
+/// ```
+pub async fn check(mm: &ModelManager, policy: &str, key: &str) -> Result<()> {
+    let limiter = RateLimiter::get_from_config();
+    let Some(policy) = limiter.policies.get(policy) else {
+        tracing::warn!(
+            "{:<12} -- Unknown rate-limit policy {}",
+            "RATE-LIMIT",
+            policy
+        );
+        return Ok(());
+    };
+    limiter.check_policy(mm, policy, key).await
+}
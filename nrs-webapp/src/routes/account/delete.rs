@@ -0,0 +1,113 @@
+use always_send::FutureExt;
+use axum::{
+    Router,
+    extract::State,
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use axum_extra::extract::SignedCookieJar;
+use axum_htmx::{HxRedirect, HxRequest};
+use nrs_webapp_frontend::{maybe_document, views::pages::account::delete::delete_account};
+use serde::Deserialize;
+use sqlbindable::FieldNames;
+use sqlx::FromRow;
+use validator::Validate;
+
+use crate::{
+    Error, Result,
+    auth::{
+        self, error::LoginError, external::revoke_link_tokens, remove_auth_cookie, session::Session,
+    },
+    crypt::password_hash::PasswordHasher,
+    extract::{doc_props::DocProps, with_rejection::WRVForm},
+    model::{
+        ModelManager,
+        audit_event::AuditContext,
+        entity::DbBmcWithPkey,
+        oauth_links::{OAuthLink, OAuthLinkBmc},
+        user::UserBmc,
+    },
+    toast_on_page_load,
+    toasts::ConstToast,
+};
+
+pub fn router() -> Router<ModelManager> {
+    Router::new().route("/", get(page).post(submit))
+}
+
+async fn page(
+    hx_req: HxRequest,
+    DocProps(props): DocProps,
+    _session: Session,
+) -> impl IntoResponse {
+    tracing::debug!("{:<12} -- GET account::delete", "ROUTE");
+    maybe_document(hx_req, props, delete_account())
+}
+
+#[derive(Deserialize, Validate)]
+struct DeleteAccountPayload {
+    #[validate(length(max = 50))]
+    password: String,
+}
+
+#[derive(FieldNames, FromRow)]
+struct DeleteAccountUser {
+    password_hash: String,
+}
+
+/// Handle the account deletion form: re-authenticate the user with their password, delete the
+/// account and log them off.
+///
+/// The password check and the deletion run in the same transaction. Entries added by the user
+/// are kept with their submitter set to `NULL`, everything else tied to the account is removed
+/// by the database cascades. Tokens of the linked OAuth providers are revoked at the providers
+/// once the deletion is committed.
+///
+/// # Returns
+///
+/// - `Ok(Response)` — an HTMX redirect to the home page with an "account deleted" toast, with the
+///   auth cookie removed.
+/// - `Err(Error::Auth(LoginError::InvalidCredentials))` — when the password does not match.
+///
+/// # Examples
+///
+/// ```no_run
+/// // POST /account/delete with `password=<current password>`
+/// // -> HX-Redirect: /?toast=AccountDeleted
+/// ```
+async fn submit(
+    session: Session,
+    State(mm): State<ModelManager>,
+    jar: SignedCookieJar,
+    audit: AuditContext,
+    WRVForm(DeleteAccountPayload { password }): WRVForm<DeleteAccountPayload>,
+) -> Result<Response> {
+    tracing::debug!("{:<12} -- POST account::delete", "ROUTE");
+
+    let mut tx = mm.tx().await?;
+
+    let user: DeleteAccountUser = UserBmc::get(&mut tx, session.user_id).always_send().await?;
+    if !PasswordHasher::get_from_config().verify_password(&password, &user.password_hash)? {
+        return Err(Error::Auth(auth::Error::Login(
+            LoginError::InvalidCredentials,
+        )));
+    }
+
+    let links: Vec<OAuthLink> = OAuthLinkBmc::list_active_for_user(&mut tx, session.user_id)
+        .always_send()
+        .await?;
+
+    UserBmc::delete_user(&mut tx, &audit, session.user_id)
+        .always_send()
+        .await?;
+    tx.commit().await?;
+
+    tracing::info!("{:<12} -- Deleted account {}", "ACCOUNT", session.user_id);
+
+    for link in &links {
+        revoke_link_tokens(&mm, link).await;
+    }
+
+    let url = format!("/?{}", toast_on_page_load!(ConstToast::AccountDeleted));
+    Ok((HxRedirect(url), remove_auth_cookie(jar)).into_response())
+}
@@ -0,0 +1,143 @@
+use axum::{
+    Json, Router,
+    extract::State,
+    http::header,
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use nrs_webapp_core::data::entry::types::idtype::EntryType;
+use serde::Serialize;
+use serde_with::serde_as;
+use sqlbindable::FieldNames;
+use sqlx::{FromRow, types::Json as SqlxJson};
+use time::{OffsetDateTime, format_description::well_known::Rfc3339};
+use uuid::Uuid;
+
+use crate::{
+    Result,
+    auth::session::Session,
+    model::{
+        ModelManager,
+        entity::DbBmcWithPkey,
+        entry::{EntryBmc, EntryStatus, revision::EntryRevisionBmc},
+        login_event::LoginEventBmc,
+        oauth_links::OAuthLinkBmc,
+        user::UserBmc,
+    },
+};
+
+pub fn router() -> Router<ModelManager> {
+    Router::new().route("/", get(export))
+}
+
+#[serde_as]
+#[derive(Serialize)]
+struct AccountExport {
+    #[serde_as(as = "Rfc3339")]
+    exported_at: OffsetDateTime,
+    profile: ExportedProfile,
+    oauth_links: Vec<ExportedOAuthLink>,
+    entries: Vec<ExportedEntry>,
+    entry_revisions: Vec<ExportedEntryRevision>,
+    logins: Vec<ExportedLogin>,
+}
+
+#[serde_as]
+#[derive(Serialize, FieldNames, FromRow)]
+struct ExportedProfile {
+    id: Uuid,
+    username: String,
+    email: String,
+    #[serde_as(as = "Option<Rfc3339>")]
+    email_verified_at: Option<OffsetDateTime>,
+    #[serde_as(as = "Rfc3339")]
+    created_at: OffsetDateTime,
+    #[serde_as(as = "Rfc3339")]
+    updated_at: OffsetDateTime,
+}
+
+// access and refresh tokens are deliberately left out: they are secrets shared with the
+// provider, not personal data
+#[serde_as]
+#[derive(Serialize, FieldNames, FromRow)]
+struct ExportedOAuthLink {
+    provider: String,
+    provider_user_id: Option<String>,
+    #[serde_as(as = "Rfc3339")]
+    created_at: OffsetDateTime,
+    #[serde_as(as = "Option<Rfc3339>")]
+    revoked_at: Option<OffsetDateTime>,
+}
+
+#[serde_as]
+#[derive(Serialize, FieldNames, FromRow)]
+struct ExportedEntry {
+    id: String,
+    title: String,
+    entry_type: EntryType,
+    entry_info: SqlxJson<serde_json::Value>,
+    status: EntryStatus,
+    #[serde_as(as = "Rfc3339")]
+    submitted_at: OffsetDateTime,
+    rejection_reason: Option<String>,
+}
+
+// the content of the revisions belongs to the entries; only the authorship is personal
+#[serde_as]
+#[derive(Serialize, FieldNames, FromRow)]
+struct ExportedEntryRevision {
+    entry_id: String,
+    revision: i32,
+    comment: String,
+    #[serde_as(as = "Rfc3339")]
+    created_at: OffsetDateTime,
+}
+
+#[serde_as]
+#[derive(Serialize, FieldNames, FromRow)]
+struct ExportedLogin {
+    method: String,
+    ip: String,
+    user_agent: String,
+    #[serde_as(as = "Rfc3339")]
+    created_at: OffsetDateTime,
+}
+
+/// Export the personal data of the logged in user as a downloadable JSON document.
+///
+/// The export contains the user's profile, their OAuth provider links (without any tokens), the
+/// entries they added, the entry revisions they authored and their recorded logins.
+///
+/// # Examples
+///
+/// ```no_run
+/// // GET /account/export
+/// // -> 200 OK, Content-Disposition: attachment; filename="nrs-account-export.json"
+/// ```
+async fn export(session: Session, State(mut mm): State<ModelManager>) -> Result<Response> {
+    tracing::debug!("{:<12} -- GET account::export", "ROUTE");
+
+    let profile: ExportedProfile = UserBmc::get(&mut mm, session.user_id).await?;
+    let oauth_links = OAuthLinkBmc::list_for_user(&mut mm, session.user_id).await?;
+    let entries = EntryBmc::list_added_by(&mut mm, session.user_id).await?;
+    let entry_revisions = EntryRevisionBmc::list_authored_by(&mut mm, session.user_id).await?;
+    let logins = LoginEventBmc::list_for_user(&mut mm, session.user_id).await?;
+
+    let export = AccountExport {
+        exported_at: OffsetDateTime::now_utc(),
+        profile,
+        oauth_links,
+        entries,
+        entry_revisions,
+        logins,
+    };
+
+    Ok((
+        [(
+            header::CONTENT_DISPOSITION,
+            "attachment; filename=\"nrs-account-export.json\"",
+        )],
+        Json(export),
+    )
+        .into_response())
+}
@@ -0,0 +1,100 @@
+mod delete;
+mod export;
+mod oauth;
+
+use axum::{
+    Router,
+    extract::State,
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use axum_htmx::HxRequest;
+use nrs_webapp_frontend::{
+    maybe_document,
+    views::pages::account::overview::{AccountOverview, AccountProvider, account_page},
+};
+use sqlbindable::FieldNames;
+use sqlx::FromRow;
+use time::OffsetDateTime;
+
+use crate::{
+    Result,
+    auth::session::Session,
+    extract::doc_props::DocProps,
+    model::{
+        ModelManager,
+        entity::DbBmcWithPkey,
+        oauth_links::{OAuthLink, OAuthLinkBmc},
+        user::UserBmc,
+    },
+};
+
+/// Constructs a Router exposing the account management endpoints for the logged in user.
+///
+/// The returned `Router<ModelManager>` serves the account overview at "/" and nests the personal
+/// data export under "/export", account deletion under "/delete" and OAuth provider management
+/// under "/oauth". Every handler requires a `Session`.
+///
+/// # Examples
+///
+/// ```no_run
+/// let account_router = router();
+/// ```
+pub fn router() -> Router<ModelManager> {
+    Router::new()
+        .route("/", get(page))
+        .nest("/export", export::router())
+        .nest("/delete", delete::router())
+        .nest("/oauth", oauth::router())
+}
+
+#[derive(FieldNames, FromRow)]
+struct AccountUser {
+    username: String,
+    email: String,
+    email_verified_at: Option<OffsetDateTime>,
+    created_at: OffsetDateTime,
+}
+
+/// Render the account overview page of the logged in user.
+///
+/// # Examples
+///
+/// ```no_run
+/// // Called by the router; shown here for illustration only.
+/// // let resp = page(hx_req, DocProps(props), session, State(mm)).await;
+/// ```
+async fn page(
+    hx_req: HxRequest,
+    DocProps(props): DocProps,
+    session: Session,
+    State(mut mm): State<ModelManager>,
+) -> Result<Response> {
+    tracing::debug!("{:<12} -- GET account", "ROUTE");
+
+    let user: AccountUser = UserBmc::get(&mut mm, session.user_id).await?;
+    let links: Vec<OAuthLink> =
+        OAuthLinkBmc::list_active_for_user(&mut mm, session.user_id).await?;
+    let providers = mm
+        .auth_providers()
+        .providers()
+        .map(|p| AccountProvider {
+            name: p.name().to_string(),
+            display_name: p.display_name().to_string(),
+            linked_since: links
+                .iter()
+                .find(|l| l.provider == p.name())
+                .map(|l| l.created_at.date().to_string()),
+        })
+        .collect();
+
+    let account = AccountOverview {
+        username: user.username,
+        email: user.email,
+        email_verified: user.email_verified_at.is_some(),
+        created_at: user.created_at.date().to_string(),
+        providers,
+    };
+
+    Ok(maybe_document(hx_req, props, account_page(&account)).into_response())
+}
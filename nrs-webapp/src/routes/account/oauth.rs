@@ -0,0 +1,114 @@
+use always_send::FutureExt;
+use axum::{
+    Router,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::post,
+};
+use axum_htmx::HxRedirect;
+use serde::Deserialize;
+use sqlbindable::FieldNames;
+use sqlx::FromRow;
+use time::OffsetDateTime;
+use validator::Validate;
+
+use crate::{
+    Error, Result,
+    auth::{
+        self,
+        external::{self, revoke_link_tokens},
+        session::Session,
+    },
+    extract::with_rejection::WRVForm,
+    model::{
+        ModelManager,
+        audit_event::AuditContext,
+        entity::DbBmcWithPkey,
+        oauth_links::{OAuthLink, OAuthLinkBmc},
+        user::UserBmc,
+    },
+    toast_on_page_load,
+    toasts::ConstToast,
+};
+
+pub fn router() -> Router<ModelManager> {
+    Router::new().route("/unlink", post(unlink))
+}
+
+#[derive(Deserialize, Validate)]
+struct UnlinkPayload {
+    #[validate(length(max = 50))]
+    provider: String,
+}
+
+#[derive(FieldNames, FromRow)]
+struct UnlinkUser {
+    email_verified_at: Option<OffsetDateTime>,
+}
+
+/// Unlink an OAuth provider from the logged in user's account and revoke its tokens.
+///
+/// Password login is only possible once the email address is verified, so an unverified user
+/// cannot unlink their last provider. The link is revoked on our side first; revoking the tokens
+/// at the provider happens after the commit and never fails the request.
+///
+/// # Returns
+///
+/// - `Ok(Response)` — an HTMX redirect to the account page with an "account unlinked" toast.
+/// - `Err(Error::Auth(auth::Error::ExternalAuth(external::Error::ProviderNotLinked(_))))` — when
+///   the provider is not linked to the account.
+/// - `Err(Error::Auth(auth::Error::LastLoginMethod))` — when the provider is the only way left to
+///   log in.
+///
+/// # Examples
+///
+/// ```no_run
+/// // POST /account/oauth/unlink with `provider=github`
+/// // -> HX-Redirect: /account?toast=OAuthAccountUnlinked
+/// ```
+async fn unlink(
+    session: Session,
+    State(mm): State<ModelManager>,
+    audit: AuditContext,
+    WRVForm(UnlinkPayload { provider }): WRVForm<UnlinkPayload>,
+) -> Result<Response> {
+    tracing::debug!("{:<12} -- POST account::oauth::unlink", "ROUTE");
+
+    let mut tx = mm.tx().await?;
+
+    let user: UnlinkUser = UserBmc::get(&mut tx, session.user_id).always_send().await?;
+    let links: Vec<OAuthLink> = OAuthLinkBmc::list_active_for_user(&mut tx, session.user_id)
+        .always_send()
+        .await?;
+
+    let Some(link) = links.iter().find(|l| l.provider == provider).cloned() else {
+        return Err(Error::Auth(auth::Error::ExternalAuth(
+            external::Error::ProviderNotLinked(provider),
+        )));
+    };
+
+    if user.email_verified_at.is_none() && links.len() == 1 {
+        return Err(Error::Auth(auth::Error::LastLoginMethod));
+    }
+
+    OAuthLinkBmc::revoke(&mut tx, &audit, session.user_id, &provider)
+        .always_send()
+        .await?;
+    tx.commit().await?;
+
+    tracing::info!(
+        "{:<12} -- Unlinked {} from account {}",
+        "ACCOUNT",
+        provider,
+        session.user_id
+    );
+
+    revoke_link_tokens(&mm, &link).await;
+
+    let url = format!(
+        "/account?{}",
+        toast_on_page_load!(ConstToast::OAuthAccountUnlinked)
+    );
+    Ok((HxRedirect(url), StatusCode::NO_CONTENT).into_response())
+}
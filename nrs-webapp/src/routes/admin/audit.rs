@@ -0,0 +1,173 @@
+use axum::{
+    Router,
+    extract::State,
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use axum_htmx::HxRequest;
+use nrs_webapp_frontend::{
+    maybe_document,
+    views::pages::admin::audit_log::{AuditFieldChange, AuditLogEntry, audit_log_page},
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::{
+    Result,
+    auth::session::{Admin, RequireRole},
+    extract::{doc_props::DocProps, with_rejection::WRQuery},
+    model::{
+        ModelManager,
+        audit_event::{AuditEvent, AuditEventBmc, AuditEventFilter, RowSnapshot},
+    },
+    routes::format_timestamp,
+};
+
+const PAGE_SIZE: u64 = 100;
+
+pub fn router() -> Router<ModelManager> {
+    Router::new().route("/", get(page))
+}
+
+#[derive(Deserialize)]
+struct AuditLogQuery {
+    entity_table: Option<String>,
+    entity_id: Option<String>,
+    actor_id: Option<Uuid>,
+}
+
+/// Render a JSON value compactly, without the quotes around strings.
+fn format_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(value) => value.clone(),
+        value => value.to_string(),
+    }
+}
+
+/// Pair the fields of the `before` and `after` snapshots of an event.
+fn field_changes(
+    before: Option<&RowSnapshot>,
+    after: Option<&RowSnapshot>,
+) -> Vec<AuditFieldChange> {
+    let mut fields = before
+        .into_iter()
+        .chain(after)
+        .flat_map(|row| row.keys())
+        .collect::<Vec<_>>();
+    fields.sort();
+    fields.dedup();
+
+    fields
+        .into_iter()
+        .map(|field| AuditFieldChange {
+            field: field.clone(),
+            before: before.and_then(|row| row.get(field)).map(format_value),
+            after: after.and_then(|row| row.get(field)).map(format_value),
+        })
+        .collect()
+}
+
+fn to_entry(event: AuditEvent) -> AuditLogEntry {
+    let entity_href = format!(
+        "/admin/audit?entity_table={}&entity_id={}",
+        urlencoding::encode(&event.entity_table),
+        urlencoding::encode(&event.entity_id)
+    );
+    AuditLogEntry {
+        id: event.id,
+        created_at: format_timestamp(event.created_at),
+        action: event.action.into(),
+        changes: field_changes(
+            event.before.as_ref().map(|row| &row.0),
+            event.after.as_ref().map(|row| &row.0),
+        ),
+        entity_table: event.entity_table,
+        entity_id: event.entity_id,
+        entity_href,
+        actor: event
+            .actor
+            .username
+            .or_else(|| event.actor_id.map(|id| id.to_string())),
+        actor_href: event
+            .actor_id
+            .map(|id| format!("/admin/audit?actor_id={id}")),
+        request_id: event.request_id.map(|id| id.to_string()),
+        ip: event.ip,
+    }
+}
+
+/// Render the most recent changes recorded in the audit log, optionally only those of an entity
+/// or made by a user.
+///
+/// # Examples
+///
+/// ```no_run
+/// // GET /admin/audit?entity_table=app_user&entity_id=<uuid>
+/// // GET /admin/audit?actor_id=<uuid>
+/// ```
+async fn page(
+    hx_req: HxRequest,
+    DocProps(props): DocProps,
+    _admin: RequireRole<Admin>,
+    State(mut mm): State<ModelManager>,
+    WRQuery(query): WRQuery<AuditLogQuery>,
+) -> Result<Response> {
+    tracing::debug!("{:<12} -- GET admin::audit", "ROUTE");
+
+    let filter_description = match (&query.entity_table, &query.entity_id, query.actor_id) {
+        (Some(table), Some(id), _) => Some(format!("changes of {table} {id}")),
+        (Some(table), None, _) => Some(format!("changes of {table}")),
+        (None, _, Some(actor_id)) => Some(format!("changes made by {actor_id}")),
+        _ => None,
+    };
+
+    let events = AuditEventBmc::list_recent(
+        &mut mm,
+        AuditEventFilter {
+            entity_table: query.entity_table,
+            entity_id: query.entity_id,
+            actor_id: query.actor_id,
+        },
+        PAGE_SIZE,
+    )
+    .await?
+    .into_iter()
+    .map(to_entry)
+    .collect::<Vec<_>>();
+
+    Ok(maybe_document(
+        hx_req,
+        props,
+        audit_log_page(filter_description.as_deref(), &events),
+    )
+    .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn field_changes_pairs_before_and_after() {
+        let before = json!({"title": "Old", "locked_until": null});
+        let after = json!({"title": "New", "locked_until": 3});
+
+        let changes = field_changes(before.as_object(), after.as_object());
+        let changes = changes
+            .iter()
+            .map(|c| (c.field.as_str(), c.before.as_deref(), c.after.as_deref()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            changes,
+            vec![
+                ("locked_until", Some("null"), Some("3")),
+                ("title", Some("Old"), Some("New")),
+            ]
+        );
+
+        let created = field_changes(None, after.as_object());
+        assert!(created.iter().all(|c| c.before.is_none()));
+    }
+}
@@ -0,0 +1,109 @@
+use always_send::FutureExt;
+use axum::{
+    Router,
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use axum_htmx::{HxRedirect, HxRequest};
+use nrs_webapp_frontend::{
+    maybe_document,
+    views::pages::admin::mail_queue::{MailQueueEntry, mail_queue_page},
+};
+use serde::Deserialize;
+
+use crate::{
+    Result,
+    auth::session::{Admin, RequireRole},
+    extract::{doc_props::DocProps, with_rejection::WRQuery},
+    mail::outbox,
+    model::{
+        ModelManager,
+        audit_event::AuditContext,
+        outbound_mail::{OutboundMailBmc, OutboundMailStatus},
+    },
+    routes::format_timestamp,
+};
+
+const PAGE_SIZE: u64 = 100;
+
+pub fn router() -> Router<ModelManager> {
+    Router::new()
+        .route("/", get(page))
+        .route("/{id}/retry", post(retry))
+}
+
+#[derive(Deserialize)]
+struct MailQueueQuery {
+    status: Option<OutboundMailStatus>,
+}
+
+/// Render the delivery status of the most recent outgoing emails, optionally only those with the
+/// `status` given in the query.
+///
+/// # Examples
+///
+/// ```no_run
+/// // GET /admin/mail?status=dead
+/// ```
+async fn page(
+    hx_req: HxRequest,
+    DocProps(props): DocProps,
+    _admin: RequireRole<Admin>,
+    State(mut mm): State<ModelManager>,
+    WRQuery(MailQueueQuery { status }): WRQuery<MailQueueQuery>,
+) -> Result<Response> {
+    tracing::debug!("{:<12} -- GET admin::mail", "ROUTE");
+
+    let mails = OutboundMailBmc::list_recent(&mut mm, status, PAGE_SIZE)
+        .await?
+        .into_iter()
+        .map(|mail| MailQueueEntry {
+            id: mail.id,
+            recipient: mail.recipient,
+            subject: mail.subject,
+            status: mail.status.into(),
+            attempts: mail.attempts,
+            created_at: format_timestamp(mail.created_at),
+            next_attempt_at: (mail.status == OutboundMailStatus::Pending)
+                .then(|| format_timestamp(mail.next_attempt_at)),
+            sent_at: mail.sent_at.map(format_timestamp),
+            last_error: mail.last_error,
+        })
+        .collect::<Vec<_>>();
+
+    let status_filter = status.map(<&'static str>::from);
+    Ok(maybe_document(hx_req, props, mail_queue_page(status_filter, &mails)).into_response())
+}
+
+/// Queue a dead email again, with a fresh attempt budget, and reload the list of dead emails.
+///
+/// # Returns
+///
+/// - `Ok(Response)` — an HTMX redirect to `/admin/mail?status=pending`.
+/// - `Err(Error::Model(model::Error::EntityNotFound { .. }))` — when there is no dead email
+///   with this id.
+async fn retry(
+    admin: RequireRole<Admin>,
+    State(mm): State<ModelManager>,
+    audit: AuditContext,
+    Path(id): Path<i64>,
+) -> Result<Response> {
+    tracing::debug!("{:<12} -- POST admin::mail_retry {}", "ROUTE", id);
+
+    let mut tx = mm.tx().await?;
+    OutboundMailBmc::requeue_dead(&mut tx, &audit, id)
+        .always_send()
+        .await?;
+    tx.commit().await?;
+    outbox::wake_worker();
+
+    tracing::info!(
+        "{:<12} -- Mail {} queued again by {}",
+        "OUTBOX",
+        id,
+        admin.0.user_id
+    );
+
+    Ok((HxRedirect("/admin/mail?status=pending".into()), ()).into_response())
+}
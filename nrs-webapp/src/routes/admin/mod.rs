@@ -0,0 +1,18 @@
+mod audit;
+mod mail;
+mod users;
+
+use axum::Router;
+
+use crate::model::ModelManager;
+
+/// Constructs the Router of the admin pages, with the user management under "/users", the
+/// outgoing email queue under "/mail" and the audit log under "/audit".
+///
+/// Every handler requires the admin role, through `RequireRole<Admin>`.
+pub fn router() -> Router<ModelManager> {
+    Router::new()
+        .nest("/users", users::router())
+        .nest("/mail", mail::router())
+        .nest("/audit", audit::router())
+}
@@ -0,0 +1,464 @@
+use always_send::FutureExt;
+use axum::{
+    Router,
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use axum_client_ip::ClientIp;
+use axum_extra::{TypedHeader, headers::UserAgent};
+use axum_htmx::{HxRedirect, HxRequest};
+use nrs_webapp_frontend::{
+    maybe_document,
+    views::{
+        email::Locale,
+        pages::admin::users::{
+            AdminFailedLogin, AdminLogin, AdminOAuthLink, AdminUserDetails, AdminUserRow,
+            user_details_page, user_list_page,
+        },
+    },
+};
+use serde::Deserialize;
+use sqlbindable::{FieldNames, Fields};
+use sqlx::FromRow;
+use time::OffsetDateTime;
+use uuid::Uuid;
+
+use crate::{
+    Error, Result, auth,
+    auth::session::{Admin, RequireRole},
+    extract::{doc_props::DocProps, with_rejection::WRQuery},
+    mail::outbox,
+    model::{
+        ModelManager,
+        audit_event::AuditContext,
+        entity::DbBmcWithPkey,
+        login_attempt::LoginAttemptBmc,
+        login_event::LoginEventBmc,
+        oauth_links::{OAuthLink, OAuthLinkBmc},
+        user::UserBmc,
+        user_role::{Role, UserRoleBmc},
+    },
+    routes::{auth::forgot_password::queue_password_reset, format_timestamp},
+    toast_on_page_load,
+    toasts::ConstToast,
+};
+
+const PAGE_SIZE: u64 = 100;
+const RECENT_LOGINS: u64 = 20;
+
+pub fn router() -> Router<ModelManager> {
+    Router::new()
+        .route("/", get(list))
+        .route("/{id}", get(details))
+        .route("/{id}/verify-email", post(verify_email))
+        .route("/{id}/password-reset", post(password_reset))
+        .route("/{id}/disable", post(disable))
+        .route("/{id}/enable", post(enable))
+        .route("/{id}/grant/{role}", post(grant_role))
+        .route("/{id}/revoke/{role}", post(revoke_role))
+}
+
+#[derive(Debug, FromRow, FieldNames, Fields)]
+struct AdminUser {
+    id: Uuid,
+    username: String,
+    email: String,
+    email_verified_at: Option<OffsetDateTime>,
+    locked_until: Option<OffsetDateTime>,
+    disabled_at: Option<OffsetDateTime>,
+    created_at: OffsetDateTime,
+}
+
+impl AdminUser {
+    fn status(&self) -> &'static str {
+        if self.disabled_at.is_some() {
+            "disabled"
+        } else if self
+            .locked_until
+            .is_some_and(|until| until > OffsetDateTime::now_utc())
+        {
+            "locked"
+        } else {
+            "active"
+        }
+    }
+}
+
+#[derive(FromRow, FieldNames)]
+struct UserProvider {
+    user_id: Uuid,
+    provider: String,
+}
+
+#[derive(Deserialize)]
+struct UserListQuery {
+    q: Option<String>,
+}
+
+fn details_redirect(id: Uuid) -> Response {
+    (HxRedirect(format!("/admin/users/{id}")), ()).into_response()
+}
+
+/// Render the most recently registered users, optionally only those whose username or email
+/// contains the `q` of the query.
+///
+/// # Examples
+///
+/// ```no_run
+/// // GET /admin/users?q=alice
+/// ```
+async fn list(
+    hx_req: HxRequest,
+    DocProps(props): DocProps,
+    _admin: RequireRole<Admin>,
+    State(mut mm): State<ModelManager>,
+    WRQuery(UserListQuery { q }): WRQuery<UserListQuery>,
+) -> Result<Response> {
+    tracing::debug!("{:<12} -- GET admin::users", "ROUTE");
+
+    let search = q.as_deref().map(str::trim).filter(|q| !q.is_empty());
+    let users: Vec<AdminUser> = UserBmc::search(&mut mm, search, PAGE_SIZE).await?;
+    let user_ids = users.iter().map(|user| user.id).collect::<Vec<_>>();
+    let providers: Vec<UserProvider> =
+        OAuthLinkBmc::list_active_for_users(&mut mm, &user_ids).await?;
+
+    let rows = users
+        .into_iter()
+        .map(|user| AdminUserRow {
+            id: user.id.to_string(),
+            status: user.status(),
+            providers: providers
+                .iter()
+                .filter(|link| link.user_id == user.id)
+                .map(|link| link.provider.clone())
+                .collect(),
+            created_at: format_timestamp(user.created_at),
+            email_verified: user.email_verified_at.is_some(),
+            username: user.username,
+            email: user.email,
+        })
+        .collect::<Vec<_>>();
+
+    Ok(maybe_document(hx_req, props, user_list_page(search, &rows)).into_response())
+}
+
+/// Render the account of a user, with their linked accounts, roles and recent logins.
+///
+/// # Returns
+///
+/// - `Ok(Response)` — the page of the user.
+/// - `Err(Error::Model(model::Error::EntityNotFound { .. }))` — when there is no such user.
+async fn details(
+    hx_req: HxRequest,
+    DocProps(props): DocProps,
+    _admin: RequireRole<Admin>,
+    State(mut mm): State<ModelManager>,
+    Path(id): Path<Uuid>,
+) -> Result<Response> {
+    tracing::debug!("{:<12} -- GET admin::user {}", "ROUTE", id);
+
+    let user: AdminUser = UserBmc::get(&mut mm, id).await?;
+    let roles = UserRoleBmc::list_for_user(&mut mm, id).await?;
+    let links: Vec<OAuthLink> = OAuthLinkBmc::list_active_for_user(&mut mm, id).await?;
+    let logins = LoginEventBmc::list_recent_for_user(&mut mm, id, RECENT_LOGINS).await?;
+    let failed_logins = LoginAttemptBmc::list_recent_for_user(&mut mm, id, RECENT_LOGINS).await?;
+
+    let details = AdminUserDetails {
+        id: user.id.to_string(),
+        email_verified_at: user.email_verified_at.map(format_timestamp),
+        created_at: format_timestamp(user.created_at),
+        locked_until: user
+            .locked_until
+            .filter(|until| *until > OffsetDateTime::now_utc())
+            .map(format_timestamp),
+        disabled_at: user.disabled_at.map(format_timestamp),
+        username: user.username,
+        email: user.email,
+        roles: roles.into_iter().map(<&'static str>::from).collect(),
+        all_roles: Role::ALL.into_iter().map(<&'static str>::from).collect(),
+        oauth_links: links
+            .into_iter()
+            .map(|link| AdminOAuthLink {
+                provider: link.provider,
+                linked_since: format_timestamp(link.created_at),
+            })
+            .collect(),
+        logins: logins
+            .into_iter()
+            .map(|login| AdminLogin {
+                created_at: format_timestamp(login.created_at),
+                method: login.method,
+                ip: login.ip,
+                user_agent: login.user_agent,
+            })
+            .collect(),
+        failed_logins: failed_logins
+            .into_iter()
+            .map(|failure| AdminFailedLogin {
+                created_at: format_timestamp(failure.created_at),
+                ip: failure.ip,
+            })
+            .collect(),
+    };
+
+    Ok(maybe_document(hx_req, props, user_details_page(&details)).into_response())
+}
+
+/// Mark the email of a user as verified, without sending them a verification link.
+///
+/// # Returns
+///
+/// - `Ok(Response)` — an HTMX redirect to the page of the user.
+/// - `Err(Error::Model(model::Error::EntityNotFound { .. }))` — when there is no such user.
+async fn verify_email(
+    admin: RequireRole<Admin>,
+    State(mut mm): State<ModelManager>,
+    audit: AuditContext,
+    Path(id): Path<Uuid>,
+) -> Result<Response> {
+    tracing::debug!("{:<12} -- POST admin::user_verify_email {}", "ROUTE", id);
+
+    UserBmc::mark_email_verified(&mut mm, &audit, id).await?;
+
+    tracing::info!(
+        "{:<12} -- Email of {} marked verified by {}",
+        "ADMIN",
+        id,
+        admin.0.user_id
+    );
+
+    Ok(details_redirect(id))
+}
+
+/// Email a password reset link to a user, whether or not they asked for one.
+///
+/// Unlike the forgot-password form, this is not rate limited and also works for unverified
+/// emails.
+///
+/// # Returns
+///
+/// - `Ok(Response)` — an HTMX redirect to the page of the user, with a toast.
+/// - `Err(Error::Model(model::Error::EntityNotFound { .. }))` — when there is no such user.
+async fn password_reset(
+    admin: RequireRole<Admin>,
+    State(mm): State<ModelManager>,
+    ClientIp(ip_addr): ClientIp,
+    TypedHeader(user_agent): TypedHeader<UserAgent>,
+    Path(id): Path<Uuid>,
+) -> Result<Response> {
+    tracing::debug!("{:<12} -- POST admin::user_password_reset {}", "ROUTE", id);
+
+    let mut tx = mm.tx().await?;
+    let user: AdminUser = UserBmc::get(&mut tx, id).always_send().await?;
+    // the language of the user is unknown outside of their requests
+    queue_password_reset(
+        &mut tx,
+        user.id,
+        &user.email,
+        &user.username,
+        ip_addr.to_string(),
+        user_agent.to_string(),
+        Locale::default(),
+    )
+    .always_send()
+    .await?;
+    tx.commit().await?;
+    outbox::wake_worker();
+
+    tracing::info!(
+        "{:<12} -- Password reset link sent to {} by {}",
+        "ADMIN",
+        id,
+        admin.0.user_id
+    );
+
+    let url = format!(
+        "/admin/users/{id}?{}",
+        toast_on_page_load!(ConstToast::PasswordResetLinkSent)
+    );
+    Ok((HxRedirect(url), ()).into_response())
+}
+
+async fn set_disabled(
+    admin: RequireRole<Admin>,
+    mut mm: ModelManager,
+    audit: AuditContext,
+    id: Uuid,
+    disabled: bool,
+) -> Result<Response> {
+    // an admin disabling themselves could leave the service without any
+    if id == admin.0.user_id {
+        return Err(Error::Auth(auth::Error::NotAllowed));
+    }
+
+    UserBmc::set_disabled(&mut mm, &audit, id, disabled).await?;
+
+    tracing::info!(
+        "{:<12} -- Account {} {} by {}",
+        "ADMIN",
+        id,
+        if disabled { "disabled" } else { "enabled" },
+        admin.0.user_id
+    );
+
+    Ok(details_redirect(id))
+}
+
+/// Disable the account of a user: they are logged out and can no longer log in.
+///
+/// # Returns
+///
+/// - `Ok(Response)` — an HTMX redirect to the page of the user.
+/// - `Err(Error::Auth(auth::Error::NotAllowed))` — when admins try to disable their own account.
+/// - `Err(Error::Model(model::Error::EntityNotFound { .. }))` — when there is no such user.
+async fn disable(
+    admin: RequireRole<Admin>,
+    State(mm): State<ModelManager>,
+    audit: AuditContext,
+    Path(id): Path<Uuid>,
+) -> Result<Response> {
+    tracing::debug!("{:<12} -- POST admin::user_disable {}", "ROUTE", id);
+    set_disabled(admin, mm, audit, id, true).await
+}
+
+/// Enable the disabled account of a user again.
+///
+/// # Returns
+///
+/// - `Ok(Response)` — an HTMX redirect to the page of the user.
+/// - `Err(Error::Model(model::Error::EntityNotFound { .. }))` — when there is no such user.
+async fn enable(
+    admin: RequireRole<Admin>,
+    State(mm): State<ModelManager>,
+    audit: AuditContext,
+    Path(id): Path<Uuid>,
+) -> Result<Response> {
+    tracing::debug!("{:<12} -- POST admin::user_enable {}", "ROUTE", id);
+    set_disabled(admin, mm, audit, id, false).await
+}
+
+fn ensure_role_change_allowed(admin_id: Uuid, id: Uuid, role: Role, granted: bool) -> Result<()> {
+    // an admin revoking their own role could leave the service without any
+    if !granted && role == Role::Admin && id == admin_id {
+        return Err(Error::Auth(auth::Error::NotAllowed));
+    }
+    Ok(())
+}
+
+async fn set_role(
+    admin: RequireRole<Admin>,
+    mm: ModelManager,
+    audit: AuditContext,
+    id: Uuid,
+    role: Role,
+    granted: bool,
+) -> Result<Response> {
+    ensure_role_change_allowed(admin.0.user_id, id, role, granted)?;
+
+    let mut tx = mm.tx().await?;
+    // fails with `EntityNotFound` instead of a foreign key violation for unknown users
+    let _user: AdminUser = UserBmc::get(&mut tx, id).always_send().await?;
+    let changed = if granted {
+        UserRoleBmc::grant(&mut tx, &audit, id, role)
+            .always_send()
+            .await?
+    } else {
+        UserRoleBmc::revoke(&mut tx, &audit, id, role)
+            .always_send()
+            .await?
+    };
+    tx.commit().await?;
+
+    if changed {
+        tracing::info!(
+            "{:<12} -- Role {} of {} {} by {}",
+            "ADMIN",
+            <&'static str>::from(role),
+            id,
+            if granted { "granted" } else { "revoked" },
+            admin.0.user_id
+        );
+    }
+
+    Ok(details_redirect(id))
+}
+
+/// Grant a role to a user. Granting a role the user already has does nothing.
+///
+/// # Returns
+///
+/// - `Ok(Response)` — an HTMX redirect to the page of the user.
+/// - `Err(Error::Model(model::Error::EntityNotFound { .. }))` — when there is no such user.
+async fn grant_role(
+    admin: RequireRole<Admin>,
+    State(mm): State<ModelManager>,
+    audit: AuditContext,
+    Path((id, role)): Path<(Uuid, Role)>,
+) -> Result<Response> {
+    tracing::debug!("{:<12} -- POST admin::user_grant_role {}", "ROUTE", id);
+    set_role(admin, mm, audit, id, role, true).await
+}
+
+/// Revoke a role from a user. Revoking a role the user does not have does nothing.
+///
+/// # Returns
+///
+/// - `Ok(Response)` — an HTMX redirect to the page of the user.
+/// - `Err(Error::Auth(auth::Error::NotAllowed))` — when admins try to revoke their own admin role.
+/// - `Err(Error::Model(model::Error::EntityNotFound { .. }))` — when there is no such user.
+async fn revoke_role(
+    admin: RequireRole<Admin>,
+    State(mm): State<ModelManager>,
+    audit: AuditContext,
+    Path((id, role)): Path<(Uuid, Role)>,
+) -> Result<Response> {
+    tracing::debug!("{:<12} -- POST admin::user_revoke_role {}", "ROUTE", id);
+    set_role(admin, mm, audit, id, role, false).await
+}
+
+#[cfg(test)]
+mod tests {
+    use hypertext::Renderable;
+    use nrs_webapp_frontend::views::pages::admin::users::user_details_page;
+
+    use super::*;
+
+    #[test]
+    fn admins_cannot_revoke_their_own_admin_role() {
+        let admin_id = Uuid::new_v4();
+        let user_id = Uuid::new_v4();
+
+        assert!(ensure_role_change_allowed(admin_id, admin_id, Role::Admin, false).is_err());
+        assert!(ensure_role_change_allowed(admin_id, admin_id, Role::Editor, false).is_ok());
+        assert!(ensure_role_change_allowed(admin_id, admin_id, Role::Admin, true).is_ok());
+        assert!(ensure_role_change_allowed(admin_id, user_id, Role::Admin, false).is_ok());
+    }
+
+    #[test]
+    fn user_details_page_grants_and_revokes_roles() {
+        let details = AdminUserDetails {
+            id: "0193c6a4-7d1e-7000-8000-000000000000".into(),
+            username: "alice".into(),
+            email: "alice@example.com".into(),
+            email_verified_at: None,
+            created_at: "2025-01-01 12:00:00".into(),
+            locked_until: None,
+            disabled_at: None,
+            roles: vec![Role::Editor.into()],
+            all_roles: Role::ALL.into_iter().map(<&'static str>::from).collect(),
+            oauth_links: vec![],
+            logins: vec![],
+            failed_logins: vec![],
+        };
+        let html = user_details_page(&details).render().into_inner();
+
+        let action = |path: &str| {
+            format!(r#"hx-post="/admin/users/0193c6a4-7d1e-7000-8000-000000000000/{path}""#)
+        };
+        assert!(html.contains(&action("revoke/editor")), "{html}");
+        assert!(html.contains(&action("grant/moderator")), "{html}");
+        assert!(html.contains(&action("grant/admin")), "{html}");
+        assert!(!html.contains(&action("grant/editor")), "{html}");
+    }
+}
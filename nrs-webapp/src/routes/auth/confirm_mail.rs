@@ -1,4 +1,4 @@
-use std::{net::IpAddr, str::FromStr, sync::OnceLock};
+use std::{net::IpAddr, str::FromStr};
 
 use always_send::FutureExt;
 use axum::{
@@ -11,9 +11,10 @@ use axum::{
 use axum_client_ip::ClientIp;
 use axum_extra::{TypedHeader, headers::UserAgent};
 use axum_htmx::{HxPushUrl, HxRedirect, HxRequest};
-use governor::{DefaultKeyedRateLimiter, Quota, RateLimiter};
-use nonzero_ext::nonzero;
-use nrs_webapp_frontend::{maybe_document, views::pages::auth::confirm_email::confirm_mail};
+use nrs_webapp_frontend::{
+    maybe_document,
+    views::{email::Locale, pages::auth::confirm_email::confirm_mail},
+};
 use serde::Deserialize;
 use sqlbindable::{FieldNames, Fields};
 use sqlx::prelude::FromRow;
@@ -21,19 +22,22 @@ use time::OffsetDateTime;
 use uuid::Uuid;
 
 use crate::{
-    Error, Result,
+    Result,
     config::AppConfig,
     crypt::token::{Token, TokenHasher},
     extract::{
         doc_props::DocProps,
+        locale::PreferredLocale,
         with_rejection::{WRForm, WRQuery},
     },
-    mail::send_email_verification_mail,
+    mail::{outbox, queue_email_verification_mail},
     model::{
         ModelManager,
+        audit_event::AuditContext,
         token::{TokenPurpose, UserOneTimeTokenBmc, UserOneTimeTokenCreateReq},
         user::UserBmc,
     },
+    rate_limit::{self, policy},
     routes::auth::mask_username_for_log,
     toast_on_page_load,
     toasts::ConstToast,
@@ -73,7 +77,7 @@ pub fn router() -> Router<ModelManager> {
 /// let ip_addr: IpAddr = "127.0.0.1".parse().unwrap();
 /// let user_agent = /* UserAgent */ unimplemented!();
 ///
-/// let resp: Response = redirect_to_confirm_mail_page(mm, username, ip_addr, user_agent);
+/// let resp: Response = redirect_to_confirm_mail_page(mm, username, ip_addr, user_agent, Locale::En);
 /// assert_eq!(resp.status(), StatusCode::NO_CONTENT);
 /// ```
 pub fn redirect_to_confirm_mail_page(
@@ -81,13 +85,14 @@ pub fn redirect_to_confirm_mail_page(
     username: String,
     ip_addr: IpAddr,
     user_agent: UserAgent,
+    locale: Locale,
 ) -> (HxRedirect, StatusCode) {
     let url = format!(
         "/auth/confirmmail?username={}",
         urlencoding::encode(&username)
     );
 
-    tokio::spawn(send_confirm_mail(mm, username, ip_addr, user_agent));
+    tokio::spawn(send_confirm_mail(mm, username, ip_addr, user_agent, locale));
     (HxRedirect(url), StatusCode::NO_CONTENT)
 }
 
@@ -143,6 +148,7 @@ struct ConfirmSubmitPayload {
 /// ```
 async fn confirm_submit(
     State(mm): State<ModelManager>,
+    audit: AuditContext,
     WRQuery(ConfirmSubmitPayload { token }): WRQuery<ConfirmSubmitPayload>,
 ) -> Result<impl IntoResponse> {
     tracing::debug!("{:<12} -- GET auth::confirm_submit", "ROUTE");
@@ -158,7 +164,7 @@ async fn confirm_submit(
     .always_send()
     .await?;
 
-    UserBmc::mark_email_verified(&mut tx, user_id)
+    UserBmc::mark_email_verified(&mut tx, &audit, user_id)
         .always_send()
         .await?;
     tx.commit().await?;
@@ -181,18 +187,19 @@ async fn confirm_submit(
 /// # use tokio;
 /// # async fn example(mm: crate::model::ModelManager, username: String, ip_addr: IpAddr, ua: crate::http::UserAgent) {
 /// // Equivalent effect: spawn the background email send task.
-/// tokio::spawn(crate::routes::auth::confirm_mail::send_confirm_mail(mm, username, ip_addr, ua));
+/// tokio::spawn(crate::routes::auth::confirm_mail::send_confirm_mail(mm, username, ip_addr, ua, Locale::En));
 /// # }
 /// ```
 async fn resend_mail(
     State(mm): State<ModelManager>,
     ClientIp(ip_addr): ClientIp,
     TypedHeader(user_agent): TypedHeader<UserAgent>,
+    PreferredLocale(locale): PreferredLocale,
     WRForm(ConfirmPagePayload { username }): WRForm<ConfirmPagePayload>,
 ) -> impl IntoResponse {
     tracing::debug!("{:<12} -- POST auth::confirm_mail_resend", "ROUTE");
 
-    tokio::spawn(send_confirm_mail(mm, username, ip_addr, user_agent));
+    tokio::spawn(send_confirm_mail(mm, username, ip_addr, user_agent, locale));
     (HxPushUrl("false".into()), StatusCode::NO_CONTENT)
 }
 
@@ -207,6 +214,7 @@ async fn resend_mail(
 /// - `username`: Username identifying the account to which the confirmation email should be sent.
 /// - `ip_addr`: Client IP address associated with the request that triggered the email.
 /// - `user_agent`: User agent string associated with the request.
+/// - `locale`: Language the email is written in.
 ///
 /// # Examples
 ///
@@ -219,15 +227,17 @@ async fn resend_mail(
 /// // let user_agent = /* UserAgent */ ;
 ///
 /// // Spawn the async send without awaiting its result:
-/// tokio::spawn(send_confirm_mail(mm, username, ip_addr, user_agent));
+/// tokio::spawn(send_confirm_mail(mm, username, ip_addr, user_agent, locale));
 /// ```
 async fn send_confirm_mail(
     mm: ModelManager,
     username: String,
     ip_addr: IpAddr,
     user_agent: UserAgent,
+    locale: Locale,
 ) {
-    if let Err(err) = send_confirm_email_inner(mm, username, ip_addr, user_agent.to_string()).await
+    if let Err(err) =
+        send_confirm_email_inner(mm, username, ip_addr, user_agent.to_string(), locale).await
     {
         tracing::error!(
             "{:<12} -- send_confirm_mail -- Error sending confirm email: {}",
@@ -246,14 +256,14 @@ struct UserIdEmail {
 
 /// Sends an email verification token to the given username if that user exists and their email is not yet verified.
 ///
-/// This function enforces a per-username rate limit, generates and stores a one-time verification token with an expiry,
-/// and attempts to deliver a verification email to the user's address. If no unverified user is found for the given
+/// This function enforces the `confirm-email` rate-limit policy per username, generates and stores a one-time verification token with an expiry,
+/// and queues a verification email to the user's address in the same transaction. If no unverified user is found for the given
 /// username the function completes successfully without sending mail.
 ///
 /// # Errors
 ///
-/// Returns an error if rate limiting prevents the request, token generation or hashing fails, database operations fail,
-/// or email delivery fails.
+/// Returns an error if rate limiting prevents the request, token generation or hashing fails, or database operations
+/// fail.
 ///
 /// # Examples
 ///
@@ -268,7 +278,7 @@ struct UserIdEmail {
 /// let user_agent = "example-agent".to_string();
 ///
 /// // Attempt to send a confirmation email (may return an application Error).
-/// let _ = nrs_webapp::routes::auth::confirm_mail::send_confirm_email_inner(mm, username, ip_addr, user_agent).await?;
+/// let _ = nrs_webapp::routes::auth::confirm_mail::send_confirm_email_inner(mm, username, ip_addr, user_agent, Locale::En).await?;
 /// # Ok(()) }
 /// ```
 async fn send_confirm_email_inner(
@@ -276,21 +286,15 @@ async fn send_confirm_email_inner(
     username: String,
     ip_addr: IpAddr,
     user_agent: String,
+    locale: Locale,
 ) -> Result<()> {
-    static RATE_LIMITER: OnceLock<DefaultKeyedRateLimiter<String>> = OnceLock::new();
-
     tracing::debug!(
         "{:<12} -- send_confirm_email -- username: {}",
         "FOR-DEV-ONLY",
         mask_username_for_log(&username)
     );
 
-    RATE_LIMITER
-        .get_or_init(|| RateLimiter::keyed(Quota::per_minute(nonzero!(1u32))))
-        .check_key(&username.to_lowercase())
-        .map_err(|_| Error::RateLimitExceeded {
-            service: "confirm-email",
-        })?;
+    rate_limit::check(&mm, policy::CONFIRM_EMAIL, &username.to_lowercase()).await?;
 
     let confirm_token = Token::generate()?;
     let confirm_token_hash = TokenHasher::get_from_config().hash(&confirm_token);
@@ -323,9 +327,12 @@ async fn send_confirm_email_inner(
         .always_send()
         .await?;
 
-        send_email_verification_mail(&email, &username, &confirm_token).await?;
+        queue_email_verification_mail(&mut tx, &email, &username, &confirm_token, locale)
+            .always_send()
+            .await?;
 
         tx.commit().await?;
+        outbox::wake_worker();
     } else {
         tracing::debug!(
             "{:<12} -- send_confirm_email -- No unverified user found with username: {}",
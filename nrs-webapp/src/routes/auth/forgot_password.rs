@@ -1,4 +1,4 @@
-use std::{net::IpAddr, str::FromStr, sync::OnceLock};
+use std::{net::IpAddr, str::FromStr};
 
 use always_send::FutureExt;
 use axum::{
@@ -10,21 +10,22 @@ use axum::{
 use axum_client_ip::ClientIp;
 use axum_extra::{TypedHeader, headers::UserAgent};
 use axum_htmx::{HxPushUrl, HxRequest};
-use governor::{DefaultKeyedRateLimiter, Quota, RateLimiter};
-use nonzero_ext::nonzero;
 use nrs_webapp_frontend::{
     maybe_document,
-    views::pages::auth::forgot_pass::{forgot_pass, forgot_pass_sent, reset_pass},
+    views::{
+        email::Locale,
+        pages::auth::forgot_pass::{forgot_pass, forgot_pass_sent, reset_pass},
+    },
 };
 use serde::Deserialize;
 use sqlbindable::{FieldNames, Fields};
-use sqlx::prelude::FromRow;
+use sqlx::{Postgres, Transaction, prelude::FromRow};
 use time::OffsetDateTime;
 use uuid::Uuid;
 use validator::Validate;
 
 use crate::{
-    Error, Result,
+    Result,
     config::AppConfig,
     crypt::{
         password_hash::PasswordHasher,
@@ -32,14 +33,17 @@ use crate::{
     },
     extract::{
         doc_props::DocProps,
+        locale::PreferredLocale,
         with_rejection::{WRQuery, WRVForm},
     },
-    mail::send_password_reset_mail,
+    mail::{outbox, queue_password_reset_mail},
     model::{
         ModelManager,
+        audit_event::AuditContext,
         token::{TokenPurpose, UserOneTimeTokenBmc, UserOneTimeTokenCreateReq},
         user::UserBmc,
     },
+    rate_limit::{self, policy},
     routes::auth::mask_email_for_log,
     toast_on_page_load,
     toasts::ConstToast,
@@ -137,11 +141,14 @@ async fn email_submit(
     State(mm): State<ModelManager>,
     ClientIp(ip_addr): ClientIp,
     TypedHeader(user_agent): TypedHeader<UserAgent>,
+    PreferredLocale(locale): PreferredLocale,
     WRVForm(EmailSubmitPayload { email }): WRVForm<EmailSubmitPayload>,
 ) -> impl IntoResponse {
     tracing::debug!("{:<12} -- POST auth::forgot_pass", "ROUTE");
 
-    tokio::spawn(send_reset_password_link(mm, email, ip_addr, user_agent));
+    tokio::spawn(send_reset_password_link(
+        mm, email, ip_addr, user_agent, locale,
+    ));
     maybe_document(HxRequest(true), props, forgot_pass_sent())
 }
 
@@ -152,7 +159,7 @@ struct ResetPasswordSubmitPayload {
     password: String,
 }
 
-/// Handle a password-reset form submission: validate and consume the reset token, update the user's password inside a transaction, commit, and redirect the client to the login page with a toast indicating they must sign in again.
+/// Handle a password-reset form submission: validate and consume the reset token, update the user's password and lift any login lockout inside a transaction, commit, and redirect the client to the login page with a toast indicating they must sign in again.
 ///
 /// On success, returns a response that pushes the login URL to the client and redirects to the login page with a toast informing the user to log in again.
 ///
@@ -167,6 +174,7 @@ struct ResetPasswordSubmitPayload {
 /// ```
 async fn reset_submit(
     State(mm): State<ModelManager>,
+    audit: AuditContext,
     WRVForm(ResetPasswordSubmitPayload { token, password }): WRVForm<ResetPasswordSubmitPayload>,
 ) -> Result<impl IntoResponse> {
     tracing::debug!("{:<12} -- POST auth::forgot_pass::reset", "ROUTE");
@@ -183,7 +191,11 @@ async fn reset_submit(
     .await?;
 
     let password_hash = PasswordHasher::get_from_config().encrypt_password(&password)?;
-    UserBmc::reset_password(&mut tx, user_id, password_hash)
+    UserBmc::reset_password(&mut tx, &audit, user_id, password_hash)
+        .always_send()
+        .await?;
+    // proving access to the mailbox is enough to lift a lockout as well
+    UserBmc::unlock(&mut tx, &audit, user_id)
         .always_send()
         .await?;
 
@@ -206,7 +218,7 @@ async fn reset_submit(
 /// let mm = /* obtain ModelManager */ unimplemented!();
 /// let ip: IpAddr = "127.0.0.1".parse().unwrap();
 /// let ua = /* construct a UserAgent */ unimplemented!();
-/// send_reset_password_link(mm, "alice@example.com".to_string(), ip, ua).await;
+/// send_reset_password_link(mm, "alice@example.com".to_string(), ip, ua, Locale::En).await;
 /// # }
 /// ```
 async fn send_reset_password_link(
@@ -214,9 +226,10 @@ async fn send_reset_password_link(
     email: String,
     ip_addr: IpAddr,
     user_agent: UserAgent,
+    locale: Locale,
 ) {
     if let Err(err) =
-        send_reset_password_link_inner(mm, email, ip_addr, user_agent.to_string()).await
+        send_reset_password_link_inner(mm, email, ip_addr, user_agent.to_string(), locale).await
     {
         tracing::error!(
             "{:<12} -- send_reset_password_link -- Error sending reset password link: {}",
@@ -226,6 +239,40 @@ async fn send_reset_password_link(
     }
 }
 
+/// Create a password-reset token for the user and queue the email with the reset link, in `tx`.
+///
+/// `request_ip` and `user_agent` are those of the request asking for the reset, which is not
+/// always made by the user: admins can send the link too.
+pub(in crate::routes) async fn queue_password_reset(
+    tx: &mut Transaction<'static, Postgres>,
+    user_id: Uuid,
+    email: &str,
+    username: &str,
+    request_ip: String,
+    user_agent: String,
+    locale: Locale,
+) -> Result<()> {
+    let reset_token = Token::generate()?;
+    UserOneTimeTokenBmc::create_token(
+        tx,
+        UserOneTimeTokenCreateReq {
+            user_id,
+            purpose: TokenPurpose::PasswordReset,
+            token_hash: TokenHasher::get_from_config().hash(&reset_token),
+            expires_at: OffsetDateTime::now_utc()
+                + AppConfig::get().password_reset_expiry_duration(),
+            request_ip: Some(request_ip),
+            user_agent: Some(user_agent),
+        },
+    )
+    .always_send()
+    .await?;
+
+    queue_password_reset_mail(tx, email, username, &reset_token, locale)
+        .always_send()
+        .await
+}
+
 #[derive(Debug, FromRow, FieldNames, Fields)]
 struct UserIdNameEmailVerifiedAt {
     id: Uuid,
@@ -235,9 +282,9 @@ struct UserIdNameEmailVerifiedAt {
 
 /// Send a password-reset link to a verified user identified by `email`.
 ///
-/// This function enforces a per-email rate limit (5 requests per minute), generates a one-time
-/// password-reset token, stores a hashed token record with metadata in the database, commits the
-/// transaction, and sends the reset email. If no user with a verified email is found, no email is
+/// This function enforces the `password-reset` rate-limit policy per email address, generates a one-time
+/// password-reset token with [`queue_password_reset`], which also queues the reset email, and
+/// commits the transaction. If no user with a verified email is found, no email is
 /// sent and the function returns `Ok(())`.
 ///
 /// # Errors
@@ -251,6 +298,7 @@ struct UserIdNameEmailVerifiedAt {
 /// - `email`: Recipient email address to look up and (if verified) to send the reset link to.
 /// - `ip_addr`: Request IP address to record with the one-time token metadata.
 /// - `user_agent`: Request user-agent string to record with the one-time token metadata.
+/// - `locale`: Language the email is written in.
 ///
 /// # Returns
 ///
@@ -266,7 +314,7 @@ struct UserIdNameEmailVerifiedAt {
 /// let user_agent = "example-agent/1.0".to_string();
 ///
 /// // Call from an async context
-/// send_reset_password_link_inner(mm, email, ip_addr, user_agent).await?;
+/// send_reset_password_link_inner(mm, email, ip_addr, user_agent, Locale::En).await?;
 /// # Ok(())
 /// # }
 /// ```
@@ -275,24 +323,15 @@ async fn send_reset_password_link_inner(
     email: String,
     ip_addr: IpAddr,
     user_agent: String,
+    locale: Locale,
 ) -> Result<()> {
-    static RATE_LIMITER: OnceLock<DefaultKeyedRateLimiter<String>> = OnceLock::new();
-
     tracing::debug!(
         "{:<12} -- send_reset_password_link -- email: {}",
         "FOR-DEV-ONLY",
         mask_email_for_log(&email)
     );
 
-    RATE_LIMITER
-        .get_or_init(|| RateLimiter::keyed(Quota::per_minute(nonzero!(5u32))))
-        .check_key(&email.to_lowercase())
-        .map_err(|_| Error::RateLimitExceeded {
-            service: "password-reset",
-        })?;
-
-    let confirm_token = Token::generate()?;
-    let confirm_token_hash = TokenHasher::get_from_config().hash(&confirm_token);
+    rate_limit::check(&mm, policy::PASSWORD_RESET, &email.to_lowercase()).await?;
 
     let mut tx = mm.tx().await?;
 
@@ -302,24 +341,20 @@ async fn send_reset_password_link_inner(
         email_verified_at: Some(_),
     }) = UserBmc::get_by_email(&mut tx, &email).always_send().await?
     {
-        UserOneTimeTokenBmc::create_token(
+        queue_password_reset(
             &mut tx,
-            UserOneTimeTokenCreateReq {
-                user_id: id,
-                purpose: TokenPurpose::PasswordReset,
-                token_hash: confirm_token_hash,
-                expires_at: OffsetDateTime::now_utc()
-                    + AppConfig::get().password_reset_expiry_duration(),
-                request_ip: Some(ip_addr.to_string()),
-                user_agent: Some(user_agent),
-            },
+            id,
+            &email,
+            &username,
+            ip_addr.to_string(),
+            user_agent,
+            locale,
         )
         .always_send()
         .await?;
 
         tx.commit().await?;
-
-        send_password_reset_mail(&email, &username, &confirm_token).await?;
+        outbox::wake_worker();
     } else {
         tracing::debug!(
             "{:<12} -- send_reset_password_link -- No verified user found with email: {}",
@@ -7,7 +7,10 @@ use axum::{
 use axum_client_ip::ClientIp;
 use axum_extra::{TypedHeader, extract::SignedCookieJar, headers::UserAgent};
 use axum_htmx::{HxRedirect, HxRequest};
-use nrs_webapp_frontend::{maybe_document, views::pages::auth::login::login};
+use nrs_webapp_frontend::{
+    maybe_document,
+    views::pages::auth::login::{LoginProvider, login},
+};
 use serde::Deserialize;
 use sqlbindable::{FieldNames, Fields};
 use sqlx::FromRow;
@@ -17,10 +20,20 @@ use validator::Validate;
 
 use crate::{
     Error, Result,
-    auth::{self, add_auth_cookie, error::LoginError},
+    auth::{
+        self, add_auth_cookie,
+        error::LoginError,
+        lockout::{self, LoginTarget},
+    },
     crypt::{password_hash::PasswordHasher, session_token::SessionToken},
-    extract::{doc_props::DocProps, with_rejection::WRVForm},
-    model::{ModelManager, user::UserBmc},
+    extract::{doc_props::DocProps, locale::PreferredLocale, with_rejection::WRVForm},
+    model::{
+        ModelManager,
+        audit_event::AuditContext,
+        login_event::{LoginEventBmc, LoginEventForCreate},
+        user::UserBmc,
+    },
+    rate_limit::{self, policy},
     routes::auth::{confirm_mail::redirect_to_confirm_mail_page, mask_username_for_log},
 };
 
@@ -48,13 +61,25 @@ pub fn router() -> Router<ModelManager> {
 /// ```
 /// # async fn example() {
 /// // Typical handler invocation within an async context:
-/// let resp = page(hx_req, DocProps(props)).await;
+/// let resp = page(hx_req, DocProps(props), State(mm)).await;
 /// // `resp` can be converted into an HTTP response to send to the client.
 /// # }
 /// ```
-async fn page(hx_req: HxRequest, DocProps(props): DocProps) -> impl IntoResponse {
+async fn page(
+    hx_req: HxRequest,
+    DocProps(props): DocProps,
+    State(mm): State<ModelManager>,
+) -> Response {
     tracing::debug!("{:<12} -- GET auth::login", "ROUTE");
-    maybe_document(hx_req, props, login())
+    let providers: Vec<_> = mm
+        .auth_providers()
+        .providers()
+        .map(|p| LoginProvider {
+            name: p.name().to_string(),
+            display_name: p.display_name().to_string(),
+        })
+        .collect();
+    maybe_document(hx_req, props, login(&providers)).into_response()
 }
 
 #[derive(Deserialize, Validate)]
@@ -68,8 +93,23 @@ struct LoginPayload {
 #[derive(FieldNames, Fields, FromRow)]
 struct LoginUser {
     id: Uuid,
+    username: String,
+    email: String,
     password_hash: String,
     email_verified_at: Option<OffsetDateTime>,
+    locked_until: Option<OffsetDateTime>,
+    disabled_at: Option<OffsetDateTime>,
+}
+
+impl LoginUser {
+    fn lockout_target(&self) -> LoginTarget<'_> {
+        LoginTarget {
+            user_id: self.id,
+            username: &self.username,
+            email: &self.email,
+            locked_until: self.locked_until,
+        }
+    }
 }
 
 /// Handle POST submissions to the login endpoint.
@@ -88,6 +128,12 @@ struct LoginUser {
 /// - `Err(Error::Auth(LoginError::InvalidCredentials))` — when the username/password
 ///   combination is invalid. Other errors from downstream operations (database access,
 ///   or hashing) are propagated as `Err`.
+/// - `Err(Error::Auth(LoginError::AccountLocked))` — when the credentials are valid but the
+///   account is locked after too many failed logins, or when a failure locks it.
+/// - `Err(Error::Auth(LoginError::AccountDisabled))` — when the credentials are valid but an
+///   admin disabled the account.
+/// - `Err(Error::RateLimitExceeded)` — when too many attempts were made for the username, or the
+///   progressive delay after the previous failures has not elapsed.
 ///
 /// # Examples
 ///
@@ -106,6 +152,8 @@ async fn submit(
     jar: SignedCookieJar,
     ClientIp(ip_addr): ClientIp,
     TypedHeader(user_agent): TypedHeader<UserAgent>,
+    PreferredLocale(locale): PreferredLocale,
+    audit: AuditContext,
     WRVForm(LoginPayload { username, password }): WRVForm<LoginPayload>,
 ) -> Result<Response> {
     tracing::debug!(
@@ -114,7 +162,13 @@ async fn submit(
         mask_username_for_log(&username)
     );
 
+    rate_limit::check(&mm, policy::LOGIN_USERNAME, &username.to_lowercase()).await?;
+
     let user: Option<LoginUser> = UserBmc::get_by_username(&mut mm, &username).await?;
+    let target = user.as_ref().map(LoginUser::lockout_target);
+    let ip = ip_addr.to_string();
+    lockout::check_login_allowed(&mut mm, target.as_ref(), &ip).await?;
+
     let password_hash: &str = user
         .as_ref()
         .map(|u| u.password_hash.as_str())
@@ -123,22 +177,48 @@ async fn submit(
     let check_result =
         PasswordHasher::get_from_config().verify_password(&password, password_hash)?;
 
-    let user = match (check_result, user) {
+    let user = match (check_result, &user) {
         (true, Some(user)) => user,
         _ => {
-            return Err(Error::Auth(auth::Error::Login(
-                LoginError::InvalidCredentials,
-            )));
+            let locked =
+                lockout::record_failure(&mm, &audit, target.as_ref(), &ip, user_agent.as_str())
+                    .await?;
+            let login_error = if locked {
+                LoginError::AccountLocked
+            } else {
+                LoginError::InvalidCredentials
+            };
+            return Err(Error::Auth(auth::Error::Login(login_error)));
         }
     };
+    if let Some(target) = &target {
+        lockout::ensure_not_locked(target)?;
+    }
+    if user.disabled_at.is_some() {
+        return Err(Error::Auth(auth::Error::Login(LoginError::AccountDisabled)));
+    }
+    lockout::record_success(&mut mm, user.id).await?;
 
     if user.email_verified_at.is_some() {
+        LoginEventBmc::record(
+            &mut mm,
+            LoginEventForCreate {
+                user_id: user.id,
+                method: "password".into(),
+                ip,
+                user_agent: user_agent.to_string(),
+            },
+        )
+        .await?;
         Ok((
             HxRedirect("/".into()),
             add_auth_cookie(jar, SessionToken::new(user.id)),
         )
             .into_response())
     } else {
-        Ok(redirect_to_confirm_mail_page(mm, username, ip_addr, user_agent).into_response())
+        Ok(
+            redirect_to_confirm_mail_page(mm, username, ip_addr, user_agent, locale)
+                .into_response(),
+        )
     }
 }
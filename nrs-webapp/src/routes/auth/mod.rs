@@ -1,9 +1,10 @@
 mod confirm_mail;
-mod forgot_password;
+pub(super) mod forgot_password;
 mod login;
 mod logoff;
 mod oauth;
 mod register;
+mod unlock;
 
 use axum::Router;
 
@@ -11,8 +12,9 @@ use crate::model::ModelManager;
 
 /// Constructs a Router exposing all authentication-related endpoints.
 ///
-/// The returned `Router<ModelManager>` nests the sub-routers for login, register, logoff, email confirmation, and password recovery
-/// under "/login", "/register", "/logoff", "/confirmmail", and "/forgotpass" respectively. State is inherited from the parent router.
+/// The returned `Router<ModelManager>` nests the sub-routers for login, register, logoff, email confirmation, password recovery,
+/// OAuth and account unlock under "/login", "/register", "/logoff", "/confirmmail", "/forgotpass", "/oauth" and "/unlock"
+/// respectively. State is inherited from the parent router.
 ///
 /// # Examples
 ///
@@ -28,6 +30,7 @@ pub fn router() -> Router<ModelManager> {
         .nest("/confirmmail", confirm_mail::router())
         .nest("/forgotpass", forgot_password::router())
         .nest("/oauth", oauth::router())
+        .nest("/unlock", unlock::router())
 }
 
 pub(crate) fn mask_email_for_log(email: &str) -> String {
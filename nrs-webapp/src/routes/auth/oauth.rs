@@ -2,7 +2,7 @@ use always_send::FutureExt;
 use anyhow::Context;
 use axum::{
     Router,
-    extract::{Path, Query, State},
+    extract::{FromRequestParts, Path, Query, State},
     response::{IntoResponse, Redirect, Response},
     routing::{get, post},
 };
@@ -22,6 +22,7 @@ use nrs_webapp_frontend::{
 };
 use oauth2::CsrfToken;
 use serde::Deserialize;
+use uuid::Uuid;
 
 use crate::{
     Error, Result,
@@ -30,27 +31,33 @@ use crate::{
         external::{
             UserIdentity,
             auth_url::{AuthFlowState, AuthorizeUrl},
+            exch_code::TokenResponse,
+            token_manager::encrypt_tokens,
         },
         get_auth_flow_state_cookie, get_temp_tokens_cookie, remove_auth_flow_state_cookie,
         remove_temp_tokens_cookie,
+        session::Session,
     },
     config::AppConfig,
-    crypt::{
-        password_hash::PasswordHasher, session_token::SessionToken, symmetric::SymmetricCipher,
-    },
-    extract::with_rejection::WRVForm,
+    crypt::{password_hash::PasswordHasher, session_token::SessionToken},
+    extract::{doc_props::DocProps, locale::PreferredLocale, with_rejection::WRVForm},
     model::{
+        audit_event::AuditContext,
         entity::DbBmc,
-        oauth_links::{OAuthLinkBmc, OAuthLinkForCreate, OAuthLinkForUpdate},
+        login_event::{LoginEventBmc, LoginEventForCreate},
+        oauth_links::{OAuthLink, OAuthLinkBmc, OAuthLinkForCreate, OAuthLinkForUpdate},
         user::{UserBmc, UserForCreate},
     },
     routes::auth::{confirm_mail::redirect_to_confirm_mail_page, register::RegisterPayload},
+    toast_on_page_load,
+    toasts::ConstToast,
 };
 use crate::{auth, model::ModelManager};
 
 pub fn router() -> Router<ModelManager> {
     Router::new()
         .route("/authorize/{provider}", get(authorize_handler))
+        .route("/link/{provider}", get(link_handler))
         .route("/callback/{provider}", get(callback_handler))
         .route("/register", post(register_handler))
 }
@@ -76,9 +83,33 @@ async fn authorize_handler(
         provider
     );
 
-    let provider = mm.auth_providers().get(&provider).ok_or_else(|| {
+    start_auth_flow(&mm, &provider, secret_jar, None).await
+}
+
+async fn link_handler(
+    session: Session,
+    Path(provider): Path<String>,
+    secret_jar: PrivateCookieJar,
+    State(mm): State<ModelManager>,
+) -> Result<impl IntoResponse> {
+    tracing::debug!(
+        "{:<12} -- GET auth::oauth::link_handler {}",
+        "ROUTE",
+        provider
+    );
+
+    start_auth_flow(&mm, &provider, secret_jar, Some(session.user_id)).await
+}
+
+async fn start_auth_flow(
+    mm: &ModelManager,
+    provider: &str,
+    secret_jar: PrivateCookieJar,
+    link_user_id: Option<Uuid>,
+) -> Result<impl IntoResponse + use<>> {
+    let provider = mm.auth_providers().get(provider).ok_or_else(|| {
         Error::Auth(auth::Error::ExternalAuth(
-            auth::external::Error::ProviderNotFound(provider.clone()),
+            auth::external::Error::ProviderNotFound(provider.to_string()),
         ))
     })?;
 
@@ -91,10 +122,11 @@ async fn authorize_handler(
         redirect_uri
     );
 
-    let AuthorizeUrl { url, state } = provider
-        .authorize_url(&mm, redirect_uri)
+    let AuthorizeUrl { url, mut state } = provider
+        .authorize_url(mm, redirect_uri)
         .await
         .map_err(auth::Error::ExternalAuth)?;
+    state.link_user_id = link_user_id;
 
     Ok((
         add_auth_flow_state_cookie(secret_jar, &state)?,
@@ -102,6 +134,18 @@ async fn authorize_handler(
     ))
 }
 
+/// What the handlers finishing an OAuth flow extract besides their own parameters: the cookie
+/// jars of the flow, and who the request comes from.
+#[derive(FromRequestParts)]
+#[from_request(state(ModelManager))]
+struct FlowRequest {
+    jar: SignedCookieJar,
+    secret_jar: PrivateCookieJar,
+    ip: ClientIp,
+    user_agent: TypedHeader<UserAgent>,
+    audit: AuditContext,
+}
+
 #[derive(Deserialize)]
 struct CallbackQueryParams {
     code: String,
@@ -109,8 +153,15 @@ struct CallbackQueryParams {
 }
 
 async fn callback_handler(
-    jar: SignedCookieJar,
-    secret_jar: PrivateCookieJar,
+    DocProps(props): DocProps,
+    session: Option<Session>,
+    FlowRequest {
+        jar,
+        secret_jar,
+        ip: ClientIp(ip_addr),
+        user_agent: TypedHeader(user_agent),
+        audit,
+    }: FlowRequest,
     Query(CallbackQueryParams { code, state }): Query<CallbackQueryParams>,
     Path(provider_name): Path<String>,
     State(mut mm): State<ModelManager>,
@@ -125,6 +176,7 @@ async fn callback_handler(
         csrf_state,
         nonce,
         pkce_verifier,
+        link_user_id,
     } = get_auth_flow_state_cookie(&secret_jar).ok_or_else(|| {
         auth::Error::ExternalAuth(auth::external::Error::AuthFlowStateCookieNotFound)
     })?;
@@ -155,21 +207,36 @@ async fn callback_handler(
         id,
         username,
         email,
-        email_verified,
+        verified_emails,
         ..
     } = provider
         .fetch_identity(&mm, id_token, nonce, &tokens.access_token, redirect_uri)
         .await
         .map_err(auth::Error::ExternalAuth)?;
 
-    let cipher = SymmetricCipher::get_from_config();
-    let encrypted_access_token = cipher.encrypt(tokens.access_token.secret().as_bytes())?;
-    let encrypted_refresh_token = tokens
-        .refresh_token
-        .as_ref()
-        .map(|refresh_token| cipher.encrypt(refresh_token.secret().as_bytes()))
-        .transpose()?;
+    let provider_display_name = provider.display_name().to_string();
+
+    if let Some(link_user_id) = link_user_id {
+        if session.map(|s| s.user_id) != Some(link_user_id) {
+            return Err(Error::Auth(auth::Error::ExternalAuth(
+                auth::external::Error::LinkSessionMismatch,
+            )));
+        }
+
+        link_account(&mm, &audit, link_user_id, &provider_name, &id, &tokens).await?;
+
+        let url = format!(
+            "/account?{}",
+            toast_on_page_load!(ConstToast::OAuthAccountLinked)
+        );
+        return Ok((
+            remove_auth_flow_state_cookie(secret_jar),
+            Redirect::to(&url),
+        )
+            .into_response());
+    }
 
+    let (encrypted_access_token, encrypted_refresh_token) = encrypt_tokens(&tokens)?;
     let user_id = OAuthLinkBmc::update_link(
         &mut mm,
         &provider_name,
@@ -183,6 +250,21 @@ async fn callback_handler(
     .await?;
 
     if let Some(user_id) = user_id {
+        if !UserBmc::is_active(&mut mm, user_id).await? {
+            return Err(Error::Auth(auth::Error::Login(
+                auth::error::LoginError::AccountDisabled,
+            )));
+        }
+        LoginEventBmc::record(
+            &mut mm,
+            LoginEventForCreate {
+                user_id,
+                method: provider_name,
+                ip: ip_addr.to_string(),
+                user_agent: user_agent.to_string(),
+            },
+        )
+        .await?;
         Ok((
             remove_auth_flow_state_cookie(secret_jar),
             add_auth_cookie(jar, SessionToken::new(user_id)),
@@ -195,28 +277,122 @@ async fn callback_handler(
                 remove_auth_flow_state_cookie(secret_jar),
                 TempTokensCookie {
                     tokens,
-                    email: email.clone(),
-                    email_verified,
+                    verified_emails: verified_emails.clone(),
                     subject: id,
                     provider_name,
                 },
             ),
-            maybe_document(
-                HxRequest(false),
-                DocumentProps::default(),
-                register(RegisterScreen::OAuth { username, email }),
+            oauth_register_page(
+                props,
+                RegisterScreen::OAuth {
+                    provider_display_name,
+                    username,
+                    email,
+                    verified_emails,
+                },
             ),
         )
             .into_response())
     }
 }
 
+/// The register page of a new user of a provider, as a whole document since the callback is a
+/// top-level navigation.
+///
+/// `props` has to be those of the request, for the CSRF token of the form and the CSP nonce of
+/// its scripts.
+fn oauth_register_page(props: DocumentProps, screen: RegisterScreen) -> impl IntoResponse {
+    maybe_document(HxRequest(false), props, register(screen))
+}
+
+/// Links the provider identity `provider_user_id` to the existing account `user_id`.
+///
+/// Linking the same identity again only refreshes the stored tokens. An identity that is
+/// already linked to another account, or a second account of a provider the user already
+/// linked, is rejected.
+async fn link_account(
+    mm: &ModelManager,
+    audit: &AuditContext,
+    user_id: Uuid,
+    provider_name: &str,
+    provider_user_id: &str,
+    tokens: &TokenResponse,
+) -> Result<()> {
+    let (access_token, refresh_token) = encrypt_tokens(tokens)?;
+    let mut tx = mm.tx().await?;
+
+    match OAuthLinkBmc::get_linked_user(&mut tx, provider_name, provider_user_id)
+        .always_send()
+        .await?
+    {
+        Some(owner) if owner != user_id => {
+            return Err(Error::Auth(auth::Error::ExternalAuth(
+                auth::external::Error::IdentityAlreadyLinked,
+            )));
+        }
+        Some(_) => {
+            OAuthLinkBmc::update_link(
+                &mut tx,
+                provider_name,
+                provider_user_id,
+                OAuthLinkForUpdate {
+                    access_token,
+                    refresh_token,
+                    access_token_expires_at: tokens.expires_at,
+                },
+            )
+            .always_send()
+            .await?;
+        }
+        None => {
+            let links: Vec<OAuthLink> = OAuthLinkBmc::list_active_for_user(&mut tx, user_id)
+                .always_send()
+                .await?;
+            if links.iter().any(|l| l.provider == provider_name) {
+                return Err(Error::Auth(auth::Error::ExternalAuth(
+                    auth::external::Error::ProviderAlreadyLinked(provider_name.to_string()),
+                )));
+            }
+
+            OAuthLinkBmc::create_audited(
+                &mut tx,
+                audit,
+                OAuthLinkForCreate {
+                    user_id,
+                    provider: provider_name.to_string(),
+                    provider_user_id: Some(provider_user_id.to_string()),
+                    access_token,
+                    refresh_token,
+                    access_token_expires_at: tokens.expires_at,
+                },
+            )
+            .always_send()
+            .await?;
+        }
+    }
+
+    tx.commit().always_send().await?;
+
+    tracing::info!(
+        "{:<12} -- Linked {} account to user {}",
+        "OAUTH",
+        provider_name,
+        user_id
+    );
+
+    Ok(())
+}
+
 async fn register_handler(
-    jar: SignedCookieJar,
-    secret_jar: PrivateCookieJar,
+    FlowRequest {
+        jar,
+        secret_jar,
+        ip: ClientIp(ip_addr),
+        user_agent: TypedHeader(user_agent),
+        audit,
+    }: FlowRequest,
     State(mm): State<ModelManager>,
-    ClientIp(ip_addr): ClientIp,
-    TypedHeader(user_agent): TypedHeader<UserAgent>,
+    PreferredLocale(locale): PreferredLocale,
     WRVForm(RegisterPayload {
         username,
         email,
@@ -227,18 +403,21 @@ async fn register_handler(
 
     let TempTokensCookie {
         tokens,
-        email: email_cookie,
-        email_verified,
+        verified_emails,
         subject,
         provider_name,
     } = get_temp_tokens_cookie(&secret_jar).ok_or(auth::Error::ExternalAuth(
         auth::external::Error::TempTokenCookieNotFound,
     ))?;
 
-    // make sure email == email_cookie (if email_cookie exists)
-    if let Some(email_cookie) = email_cookie
-        && email_cookie != email
-    {
+    // when the provider returned verified addresses, the user has to pick one of them; otherwise
+    // any address is accepted, but it goes through email verification. The providers do not
+    // always keep the case the user typed, and the database compares addresses regardless of it.
+    let email_lowercase = email.to_lowercase();
+    let email_verified = verified_emails
+        .iter()
+        .any(|verified| verified.to_lowercase() == email_lowercase);
+    if !verified_emails.is_empty() && !email_verified {
         return Err(Error::Auth(auth::Error::ExternalAuth(
             auth::external::Error::EmailMismatch,
         )));
@@ -250,6 +429,7 @@ async fn register_handler(
 
     let user_id = UserBmc::create_user(
         &mut tx,
+        &audit,
         UserForCreate {
             username: username.clone(),
             email,
@@ -260,24 +440,19 @@ async fn register_handler(
     .await?;
 
     if email_verified {
-        UserBmc::mark_email_verified(&mut tx, user_id)
+        UserBmc::mark_email_verified(&mut tx, &audit, user_id)
             .always_send()
             .await?;
     }
 
-    let cipher = SymmetricCipher::get_from_config();
-    let encrypted_access_token = cipher.encrypt(tokens.access_token.secret().as_bytes())?;
-    let encrypted_refresh_token = tokens
-        .refresh_token
-        .as_ref()
-        .map(|refresh_token| cipher.encrypt(refresh_token.secret().as_bytes()))
-        .transpose()?;
+    let (encrypted_access_token, encrypted_refresh_token) = encrypt_tokens(&tokens)?;
 
-    OAuthLinkBmc::create(
+    OAuthLinkBmc::create_audited(
         &mut tx,
+        &audit,
         OAuthLinkForCreate {
             user_id,
-            provider: provider_name,
+            provider: provider_name.clone(),
             provider_user_id: Some(subject),
             access_token: encrypted_access_token,
             refresh_token: encrypted_refresh_token,
@@ -287,6 +462,20 @@ async fn register_handler(
     .always_send()
     .await?;
 
+    if email_verified {
+        LoginEventBmc::record(
+            &mut tx,
+            LoginEventForCreate {
+                user_id,
+                method: provider_name,
+                ip: ip_addr.to_string(),
+                user_agent: user_agent.to_string(),
+            },
+        )
+        .always_send()
+        .await?;
+    }
+
     tx.commit().always_send().await?;
 
     if email_verified {
@@ -299,8 +488,45 @@ async fn register_handler(
     } else {
         Ok((
             remove_temp_tokens_cookie(secret_jar),
-            redirect_to_confirm_mail_page(mm, username, ip_addr, user_agent),
+            redirect_to_confirm_mail_page(mm, username, ip_addr, user_agent, locale),
         )
             .into_response())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use axum::{body::to_bytes, extract::FromRequestParts, http::Request};
+
+    use super::*;
+    use crate::middleware::{mw_csrf::CsrfToken, mw_sec_headers::CspNonce};
+
+    #[tokio::test]
+    async fn oauth_register_page_carries_csrf_token_and_nonce() {
+        let (mut parts, ()) = Request::builder()
+            .uri("/auth/oauth/callback/github?code=c&state=s")
+            .extension(CsrfToken("csrf-token-value".into()))
+            .extension(CspNonce("csp-nonce-value".into()))
+            .body(())
+            .unwrap()
+            .into_parts();
+        let DocProps(props) = DocProps::from_request_parts(&mut parts, &()).await.unwrap();
+
+        let response = oauth_register_page(
+            props,
+            RegisterScreen::OAuth {
+                provider_display_name: "GitHub".into(),
+                username: Some("alice".into()),
+                email: None,
+                verified_emails: vec!["alice@example.com".into()],
+            },
+        )
+        .into_response();
+        let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let page = String::from_utf8(body.to_vec()).unwrap();
+
+        assert!(page.contains("/auth/oauth/register"));
+        assert!(page.contains("csrf-token-value"));
+        assert!(page.contains(r#"nonce="csp-nonce-value""#));
+    }
+}
@@ -1,3 +1,4 @@
+use always_send::FutureExt;
 use axum::{Router, extract::State, response::IntoResponse, routing::get};
 use axum_client_ip::ClientIp;
 use axum_extra::{TypedHeader, headers::UserAgent};
@@ -12,9 +13,10 @@ use validator::Validate;
 use crate::{
     Result,
     crypt::password_hash::PasswordHasher,
-    extract::{doc_props::DocProps, with_rejection::WRVForm},
+    extract::{doc_props::DocProps, locale::PreferredLocale, with_rejection::WRVForm},
     model::{
         ModelManager,
+        audit_event::AuditContext,
         user::{UserBmc, UserForCreate},
     },
     routes::auth::{
@@ -83,9 +85,11 @@ pub(super) struct RegisterPayload {
 /// ```
 async fn submit(
     HxRequest(_hx_req): HxRequest,
-    State(mut mm): State<ModelManager>,
+    State(mm): State<ModelManager>,
     ClientIp(ip_addr): ClientIp,
     TypedHeader(user_agent): TypedHeader<UserAgent>,
+    PreferredLocale(locale): PreferredLocale,
+    audit: AuditContext,
     WRVForm(RegisterPayload {
         username,
         email,
@@ -101,17 +105,21 @@ async fn submit(
 
     let password_hash = PasswordHasher::get_from_config().encrypt_password(&password)?;
 
+    let mut tx = mm.tx().await?;
     let _ = UserBmc::create_user(
-        &mut mm,
+        &mut tx,
+        &audit,
         UserForCreate {
             username: username.clone(),
             email,
             password_hash,
         },
     )
+    .always_send()
     .await?;
+    tx.commit().await?;
 
     Ok(redirect_to_confirm_mail_page(
-        mm, username, ip_addr, user_agent,
+        mm, username, ip_addr, user_agent, locale,
     ))
 }
@@ -0,0 +1,66 @@
+use std::str::FromStr;
+
+use axum::{
+    Router,
+    extract::State,
+    response::{IntoResponse, Redirect},
+    routing::get,
+};
+use axum_htmx::HxPushUrl;
+use serde::Deserialize;
+
+use crate::{
+    Result,
+    auth::lockout,
+    crypt::token::Token,
+    extract::with_rejection::WRQuery,
+    model::{ModelManager, audit_event::AuditContext},
+    toast_on_page_load,
+    toasts::ConstToast,
+};
+
+/// Creates the router for the account unlock link sent by email when an account gets locked.
+///
+/// # Examples
+///
+/// ```
+/// let _router = nrs_webapp::routes::auth::unlock::router();
+/// ```
+pub fn router() -> Router<ModelManager> {
+    Router::new().route("/", get(unlock_submit))
+}
+
+#[derive(Deserialize)]
+struct UnlockPayload {
+    token: String,
+}
+
+/// Consume an account unlock token, lift the lockout of its account and redirect to the login
+/// page.
+///
+/// # Errors
+///
+/// Returns `model::Error::InvalidOrExpiredToken` when the token is unknown, already used or
+/// expired.
+///
+/// # Examples
+///
+/// ```no_run
+/// // GET /auth/unlock?token=<token from the email>
+/// // -> redirect to /auth/login?toast=AccountUnlocked
+/// ```
+async fn unlock_submit(
+    State(mm): State<ModelManager>,
+    audit: AuditContext,
+    WRQuery(UnlockPayload { token }): WRQuery<UnlockPayload>,
+) -> Result<impl IntoResponse> {
+    tracing::debug!("{:<12} -- GET auth::unlock", "ROUTE");
+
+    lockout::unlock_account(&mm, &audit, &Token::from_str(&token)?).await?;
+
+    let url = format!(
+        "/auth/login?{}",
+        toast_on_page_load!(ConstToast::AccountUnlocked)
+    );
+    Ok((HxPushUrl("/auth/login".into()), Redirect::to(&url)))
+}
@@ -0,0 +1,56 @@
+use axum::{
+    Router,
+    body::Bytes,
+    extract::DefaultBodyLimit,
+    http::{HeaderMap, StatusCode, header},
+    routing::post,
+};
+
+use crate::model::ModelManager;
+
+// reports are small, anything larger is not a genuine report
+const MAX_REPORT_SIZE: usize = 16 * 1024;
+
+/// Creates the router receiving the Content-Security-Policy violation reports sent by browsers.
+///
+/// # Examples
+///
+/// ```
+/// let _router = nrs_webapp::routes::csp_report::router();
+/// ```
+pub fn router() -> Router<ModelManager> {
+    Router::new()
+        .route("/", post(report))
+        .layer(DefaultBodyLimit::max(MAX_REPORT_SIZE))
+}
+
+/// Logs a Content-Security-Policy violation report.
+///
+/// Both the legacy `report-uri` format (`application/csp-report`) and the Reporting API format
+/// (`application/reports+json`) are accepted; the body is logged as JSON without further
+/// interpretation. Bodies that are not JSON are ignored.
+///
+/// # Examples
+///
+/// ```no_run
+/// // POST /csp-report with `{"csp-report": {"violated-directive": "script-src", ...}}`
+/// // -> 204 No Content
+/// ```
+async fn report(headers: HeaderMap, body: Bytes) -> StatusCode {
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+
+    match serde_json::from_slice::<serde_json::Value>(&body) {
+        Ok(report) => tracing::warn!(
+            "{:<12} -- CSP violation ({}): {}",
+            "CSP-REPORT",
+            content_type,
+            report
+        ),
+        Err(err) => tracing::debug!("{:<12} -- Invalid CSP report: {}", "CSP-REPORT", err),
+    }
+
+    StatusCode::NO_CONTENT
+}
@@ -1,19 +1,164 @@
-use axum::{Router, routing::get};
+use axum::{
+    Router,
+    extract::{OriginalUri, Path},
+    http::header::CONTENT_TYPE,
+    response::{Html, IntoResponse, Response},
+    routing::get,
+};
+use axum_htmx::{HxRedirect, HxRequest};
+use nrs_webapp_frontend::{
+    maybe_document,
+    views::{
+        email::{Locale, template_samples},
+        pages::dev::{
+            mail_preview::mail_preview_page,
+            mailbox::{MailboxEntry, MailboxMessage, mailbox_message_page, mailbox_page},
+        },
+    },
+};
+use serde::Deserialize;
+use serde_with::{DisplayFromStr, serde_as};
 use std::{convert::Infallible, time::Duration};
+use time::OffsetDateTime;
 
 use axum::response::{Sse, sse::Event};
 use tokio::time::interval;
 use tokio_stream::{StreamExt, wrappers::IntervalStream};
 
+use crate::{
+    Error, Result,
+    extract::{doc_props::DocProps, with_rejection::WRQuery},
+    mail::capture_mailer,
+};
+
 pub fn dev_router<S: Clone + Send + Sync + 'static>() -> Router<S> {
-    Router::new().route(
-        "/livereload",
-        get(|| async {
-            let interval = interval(Duration::from_secs(20));
-            let stream = IntervalStream::new(interval)
-                .map(|_| Ok::<_, Infallible>(Event::default().event("ping")));
-
-            Sse::new(stream)
-        }),
-    )
+    Router::new()
+        .route(
+            "/livereload",
+            get(|| async {
+                let interval = interval(Duration::from_secs(20));
+                let stream = IntervalStream::new(interval)
+                    .map(|_| Ok::<_, Infallible>(Event::default().event("ping")));
+
+                Sse::new(stream)
+            }),
+        )
+        .route("/mail-preview", get(mail_preview_index))
+        .route("/mail-preview/{template}", get(mail_preview))
+        .route("/mailbox", get(mailbox).delete(clear_mailbox))
+        .route("/mailbox/{id}", get(mailbox_message))
+}
+
+async fn mail_preview_index(hx_req: HxRequest, DocProps(props): DocProps) -> Response {
+    tracing::debug!("{:<12} -- GET dev::mail_preview_index", "ROUTE");
+
+    let templates = template_samples().map(|(name, _)| name);
+    maybe_document(hx_req, props, mail_preview_page(&templates)).into_response()
+}
+
+#[derive(Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum PreviewFormat {
+    #[default]
+    Html,
+    Text,
+}
+
+#[serde_as]
+#[derive(Deserialize)]
+struct MailPreviewQuery {
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    #[serde(default)]
+    locale: Option<Locale>,
+    #[serde(default)]
+    format: PreviewFormat,
+}
+
+/// Renders the email `template` with sample data, in the `locale` and `format` (`html` or
+/// `text`) given in the query.
+///
+/// # Examples
+///
+/// ```no_run
+/// // GET /__dev_only/mail-preview/password_reset?locale=fr&format=text
+/// ```
+async fn mail_preview(
+    OriginalUri(uri): OriginalUri,
+    Path(template): Path<String>,
+    WRQuery(MailPreviewQuery { locale, format }): WRQuery<MailPreviewQuery>,
+) -> Result<Response> {
+    tracing::debug!("{:<12} -- GET dev::mail_preview -- {}", "ROUTE", template);
+
+    let (_, sample) = template_samples()
+        .into_iter()
+        .find(|(name, _)| *name == template)
+        .ok_or(Error::PageNotFound { uri })?;
+    let email = sample.render(locale.unwrap_or_default());
+
+    Ok(match format {
+        PreviewFormat::Html => Html(email.html.into_inner()).into_response(),
+        PreviewFormat::Text => (
+            [(CONTENT_TYPE, "text/plain; charset=utf-8")],
+            format!("Subject: {}\n\n{}", email.subject, email.text),
+        )
+            .into_response(),
+    })
+}
+
+fn format_timestamp(timestamp: OffsetDateTime) -> String {
+    format!("{} {}", timestamp.date(), timestamp.time())
+}
+
+/// List the mails kept by the capture mailer, newest first.
+async fn mailbox(hx_req: HxRequest, DocProps(props): DocProps) -> Response {
+    tracing::debug!("{:<12} -- GET dev::mailbox", "ROUTE");
+
+    let mails = capture_mailer()
+        .mails()
+        .into_iter()
+        .map(|mail| MailboxEntry {
+            id: mail.id,
+            to: mail.to,
+            subject: mail.email.subject,
+            captured_at: format_timestamp(mail.captured_at),
+        })
+        .collect::<Vec<_>>();
+    maybe_document(hx_req, props, mailbox_page(&mails)).into_response()
+}
+
+/// Render the captured mail `id`, with both its HTML and plain-text bodies.
+///
+/// # Examples
+///
+/// ```no_run
+/// // GET /__dev_only/mailbox/3
+/// ```
+async fn mailbox_message(
+    hx_req: HxRequest,
+    DocProps(props): DocProps,
+    OriginalUri(uri): OriginalUri,
+    Path(id): Path<u64>,
+) -> Result<Response> {
+    tracing::debug!("{:<12} -- GET dev::mailbox_message -- {}", "ROUTE", id);
+
+    let mail = capture_mailer()
+        .get(id)
+        .ok_or(Error::PageNotFound { uri })?;
+    let message = MailboxMessage {
+        id: mail.id,
+        from: &mail.from,
+        to: &mail.to,
+        subject: &mail.email.subject,
+        captured_at: format_timestamp(mail.captured_at),
+        html: mail.email.html.as_inner(),
+        text: &mail.email.text,
+    };
+    Ok(maybe_document(hx_req, props, mailbox_message_page(&message)).into_response())
+}
+
+async fn clear_mailbox() -> impl IntoResponse {
+    tracing::debug!("{:<12} -- DELETE dev::mailbox", "ROUTE");
+
+    capture_mailer().clear();
+    (HxRedirect("/__dev_only/mailbox".into()), ())
 }
@@ -1,39 +1,138 @@
+mod revision;
+mod submit;
+
 use crate::Result;
+use crate::auth::session::Session;
 use crate::extract::doc_props::DocProps;
-use crate::model::entity::ListPayload;
+use crate::extract::with_rejection::{WRQuery, WRVQuery};
+use crate::model::entity::DbBmc;
+use crate::model::entity::keyset::{Cursor, KeysetPayload};
 use crate::model::entry::alias::EntryAliasBmc;
-use crate::model::entry::{Entry, EntryBmc};
-use axum::extract::State;
+use crate::model::entry::{Entry, EntryBmc, EntryFilter, EntryStatus};
+use crate::model::user_role::Role;
+use axum::extract::{RawQuery, State};
 use axum::response::{IntoResponse, Redirect, Response};
+use axum::routing::post;
 use axum::{Router, extract::Path, routing::get};
 use axum_htmx::{HxRedirect, HxRequest};
 use nrs_webapp_frontend::maybe_document;
 use nrs_webapp_frontend::views::pages::entry::details::{EntryDetails, entry_details_page};
-use nrs_webapp_frontend::views::pages::entry::list::{EntryListEntry, entry_list_page};
+use nrs_webapp_frontend::views::pages::entry::list::{
+    EntryListEntry, EntryListFilter, EntryListPager, entry_list_page,
+};
 use reqwest::StatusCode;
+use sea_query::Order;
+use serde::Deserialize;
+use url::form_urlencoded;
 
 use crate::model::ModelManager;
 
 pub fn router() -> Router<ModelManager> {
     Router::new()
         .route("/", get(get_all))
+        .route("/new", get(submit::submit_page).post(submit::submit))
         .route("/{id}", get(get_by_id))
+        .route(
+            "/{id}/edit",
+            get(revision::edit_page).post(revision::edit_submit),
+        )
+        .route("/{id}/history", get(revision::history))
+        .route("/{id}/rollback", post(revision::rollback))
 }
 
+/// Fail with `EntityNotFound` unless the user of `session` may see `entry`: everyone once it is
+/// approved, otherwise only its submitter and the moderators.
+pub(super) async fn ensure_visible(
+    mm: &mut ModelManager,
+    session: Option<&Session>,
+    entry: &Entry,
+) -> Result<()> {
+    let visible = entry.status == EntryStatus::Approved
+        || match session {
+            Some(session) => {
+                entry.added_by.id == Some(session.user_id)
+                    || session.has_role(mm, Role::Moderator).await?
+            }
+            None => false,
+        };
+    if !visible {
+        return Err(EntryBmc::not_found_error(entry.id.clone()).into());
+    }
+    Ok(())
+}
+
+const PAGE_SIZE: u64 = 10;
+
+#[derive(Deserialize)]
+pub struct EntryListQuery {
+    cursor: Option<String>,
+}
+
+/// Render a page of the approved entries matching the filter of the query, sorted by title.
+///
+/// The links to the neighbouring pages keep the filter.
+///
+/// # Returns
+///
+/// - `Ok(Response)` — the page of entries.
+/// - `Err(Error::Model(model::Error::InvalidCursor))` — when the cursor of the query is invalid.
+/// - `Err(Error::Rejection(RejectionError::Validation(..)))` — when the filter of the query is
+///   invalid.
+///
+/// # Examples
+///
+/// ```no_run
+/// // GET /entry?title_contains=bebop&entry_type_in=Anime,Manga
+/// // GET /entry?cursor=<cursor of the previous or next page>
+/// ```
 pub async fn get_all(
     hx_request: HxRequest,
     DocProps(props): DocProps,
+    session: Option<Session>,
     State(mut mm): State<ModelManager>,
+    WRQuery(EntryListQuery { cursor }): WRQuery<EntryListQuery>,
+    WRVQuery(filter): WRVQuery<EntryFilter>,
+    RawQuery(query): RawQuery,
 ) -> Result<Response> {
     tracing::debug!("{:<12} -- GET entry::get_all", "ROUTE");
 
-    let payload = ListPayload {
-        offset: Some(0),
-        limit: Some(10),
-        ..Default::default()
+    let payload = KeysetPayload {
+        order_by: vec![("title", Order::Asc)],
+        cursor: cursor.as_deref().map(str::parse::<Cursor>).transpose()?,
+        limit: PAGE_SIZE,
+        with_total: true,
+    };
+
+    let list_filter = EntryListFilter {
+        title_contains: filter.title_contains.clone(),
+        entry_type: match filter.entry_type_in.as_deref() {
+            Some([entry_type]) => Some(*entry_type),
+            _ => None,
+        },
     };
 
-    let entries: Vec<Entry> = EntryBmc::list_entries(&mut mm, payload).await?;
+    let page = EntryBmc::list_entries(&mut mm, filter, payload).await?;
+    // the filter parameters of the query, as they came
+    let filter_query = form_urlencoded::parse(query.as_deref().unwrap_or_default().as_bytes())
+        .filter(|(key, _)| key != "cursor")
+        .fold(
+            &mut form_urlencoded::Serializer::new(String::new()),
+            |query, (key, value)| query.append_pair(&key, &value),
+        )
+        .finish();
+    let page_href = |cursor: Cursor| {
+        let mut query = form_urlencoded::Serializer::new(filter_query.clone());
+        format!(
+            "/entry?{}",
+            query.append_pair("cursor", &cursor.to_string()).finish()
+        )
+    };
+    let pager = EntryListPager {
+        prev_href: page.prev.map(page_href),
+        next_href: page.next.map(page_href),
+        total: page.total,
+    };
+    let entries = page.items;
     let entries = entries
         .into_iter()
         .map(|e| EntryListEntry {
@@ -44,13 +143,19 @@ pub async fn get_all(
         })
         .collect::<Vec<_>>();
 
-    Ok(maybe_document(hx_request, props, entry_list_page(&entries)).into_response())
+    Ok(maybe_document(
+        hx_request,
+        props,
+        entry_list_page(&entries, &list_filter, &pager, session.is_some()),
+    )
+    .into_response())
 }
 
 pub async fn get_by_id(
     hx_request: HxRequest,
     DocProps(props): DocProps,
     Path(id): Path<String>,
+    session: Option<Session>,
     State(mut mm): State<ModelManager>,
 ) -> Result<Response> {
     tracing::debug!("{:<12} -- GET entry::get_by_id {}", "ROUTE", id);
@@ -73,13 +178,21 @@ pub async fn get_by_id(
     }
 
     let entry = EntryBmc::get_details(&mut mm, id).await?;
+    ensure_visible(&mut mm, session.as_ref(), &entry).await?;
+    let can_edit = match &session {
+        Some(session) => session.has_role(&mut mm, Role::Editor).await?,
+        None => false,
+    };
     let entry_details = EntryDetails {
         id: entry.id,
         title: entry.title,
         entry_type: entry.entry_type,
-        added_by_id: entry.added_by.id.to_string(),
+        added_by_id: entry.added_by.id.map(|id| id.to_string()),
         added_by_username: entry.added_by.username,
         info_json: format!("{:#}", entry.entry_info.0),
+        status: entry.status.into(),
+        rejection_reason: entry.rejection_reason,
+        can_edit,
     };
 
     Ok(maybe_document(hx_request, props, entry_details_page(&entry_details)).into_response())
@@ -0,0 +1,254 @@
+use always_send::FutureExt;
+use axum::{
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+};
+use axum_htmx::{HxRedirect, HxRequest};
+use nrs_webapp_core::data::entry::types::idtype::EntryType;
+use nrs_webapp_frontend::{
+    maybe_document,
+    views::pages::entry::{
+        edit::{EntryEditForm, entry_edit_page},
+        history::{EntryRevisionView, RevisionChangeView, entry_history_page},
+    },
+};
+use serde::Deserialize;
+use sqlx::types::Json;
+use validator::Validate;
+
+use crate::{
+    Error, Result, auth,
+    auth::session::{Editor, RequireRole, Session},
+    extract::{
+        doc_props::DocProps,
+        with_rejection::{WRForm, WRVForm},
+    },
+    model::{
+        ModelManager,
+        audit_event::AuditContext,
+        entry::{Entry, EntryBmc, EntryForUpdate, revision::EntryRevisionBmc},
+        user_role::Role,
+    },
+    routes::{entry::ensure_visible, format_timestamp},
+    validate::entry::validate_entry_info,
+};
+
+/// Whether the user of `session` may roll back `entry`: its submitter, if they are still an
+/// editor, or a moderator.
+async fn can_rollback(mm: &mut ModelManager, session: &Session, entry: &Entry) -> Result<bool> {
+    Ok(
+        (entry.added_by.id == Some(session.user_id) && session.has_role(mm, Role::Editor).await?)
+            || session.has_role(mm, Role::Moderator).await?,
+    )
+}
+
+fn history_redirect(id: &str) -> Response {
+    (HxRedirect(format!("/entry/{id}/history")), ()).into_response()
+}
+
+/// Render the revisions of an entry, newest first, each with its changes since the previous one.
+///
+/// # Examples
+///
+/// ```no_run
+/// // GET /entry/<id>/history
+/// ```
+pub(super) async fn history(
+    hx_req: HxRequest,
+    DocProps(props): DocProps,
+    Path(id): Path<String>,
+    session: Option<Session>,
+    State(mut mm): State<ModelManager>,
+) -> Result<Response> {
+    tracing::debug!("{:<12} -- GET entry::history {}", "ROUTE", id);
+
+    let entry = EntryBmc::get_details(&mut mm, id.clone()).await?;
+    ensure_visible(&mut mm, session.as_ref(), &entry).await?;
+    let revisions = EntryRevisionBmc::list_for_entry(&mut mm, &id).await?;
+    let views = revisions
+        .iter()
+        .enumerate()
+        .map(|(index, revision)| EntryRevisionView {
+            revision: revision.revision,
+            created_at: format_timestamp(revision.created_at),
+            author: revision.author.username.clone(),
+            comment: revision.comment.clone(),
+            changes: revisions
+                .get(index + 1)
+                .map(|previous| revision.changes_since(previous))
+                .unwrap_or_default()
+                .into_iter()
+                .map(|change| RevisionChangeView {
+                    path: change.path,
+                    before: change.before.map(|value| value.to_string()),
+                    after: change.after.map(|value| value.to_string()),
+                })
+                .collect(),
+        })
+        .collect::<Vec<_>>();
+
+    let can_rollback = match &session {
+        Some(session) => can_rollback(&mut mm, session, &entry).await?,
+        None => false,
+    };
+    Ok(maybe_document(
+        hx_req,
+        props,
+        entry_history_page(&entry.id, &entry.title, &views, can_rollback),
+    )
+    .into_response())
+}
+
+/// Render the form editing the content of an entry.
+pub(super) async fn edit_page(
+    hx_req: HxRequest,
+    DocProps(props): DocProps,
+    editor: RequireRole<Editor>,
+    Path(id): Path<String>,
+    State(mut mm): State<ModelManager>,
+) -> Result<Response> {
+    tracing::debug!("{:<12} -- GET entry::edit {}", "ROUTE", id);
+
+    let entry = EntryBmc::get_details(&mut mm, id).await?;
+    ensure_visible(&mut mm, Some(&editor.0), &entry).await?;
+    let form = EntryEditForm {
+        id: entry.id,
+        title: entry.title,
+        entry_type: entry.entry_type,
+        info_json: format!("{:#}", entry.entry_info.0),
+    };
+    Ok(maybe_document(hx_req, props, entry_edit_page(&form)).into_response())
+}
+
+#[derive(Deserialize, Validate)]
+pub(super) struct EditEntryPayload {
+    #[validate(length(min = 1, max = 512))]
+    title: String,
+    entry_type: EntryType,
+    #[validate(custom(function = validate_entry_info))]
+    entry_info: String,
+    #[validate(length(max = 500))]
+    #[serde(default)]
+    comment: String,
+}
+
+/// Handle the entry edit form: replace the content of the entry and record it as a new revision
+/// authored by the logged in user.
+///
+/// # Returns
+///
+/// - `Ok(Response)` — an HTMX redirect to the history of the entry, also when nothing changed.
+/// - `Err(Error::Auth(auth::Error::MissingRole(Role::Editor)))` — when the user is not an editor.
+/// - `Err(Error::Model(model::Error::EntityNotFound { .. }))` — when there is no entry with this
+///   id.
+///
+/// # Examples
+///
+/// ```no_run
+/// // POST /entry/<id>/edit with `title`, `entry_type`, `entry_info` and `comment`
+/// // -> HX-Redirect: /entry/<id>/history
+/// ```
+pub(super) async fn edit_submit(
+    editor: RequireRole<Editor>,
+    State(mut mm): State<ModelManager>,
+    audit: AuditContext,
+    Path(id): Path<String>,
+    WRVForm(EditEntryPayload {
+        title,
+        entry_type,
+        entry_info,
+        comment,
+    }): WRVForm<EditEntryPayload>,
+) -> Result<Response> {
+    tracing::debug!("{:<12} -- POST entry::edit {}", "ROUTE", id);
+
+    // already validated as a JSON object
+    let entry_info =
+        serde_json::from_str(&entry_info).map_err(|err| Error::Unexpected(err.into()))?;
+
+    let mut tx = mm.tx().await?;
+    let entry = EntryBmc::get_details(&mut tx, id.clone())
+        .always_send()
+        .await?;
+    ensure_visible(&mut mm, Some(&editor.0), &entry).await?;
+    let revision = EntryBmc::update_entry(
+        &mut tx,
+        &audit,
+        id.clone(),
+        EntryForUpdate {
+            title,
+            entry_type,
+            entry_info: Json(entry_info),
+        },
+        comment.trim(),
+    )
+    .always_send()
+    .await?;
+    tx.commit().await?;
+
+    if let Some(revision) = revision {
+        tracing::info!(
+            "{:<12} -- Entry {} edited by {} (revision {})",
+            "ENTRY",
+            id,
+            editor.0.user_id,
+            revision
+        );
+    }
+
+    Ok(history_redirect(&id))
+}
+
+#[derive(Deserialize)]
+pub(super) struct RollbackPayload {
+    revision: i32,
+}
+
+/// Restore the content of an entry from one of its earlier revisions, as a new revision.
+///
+/// Only the submitter of the entry and the moderators may roll it back.
+///
+/// # Returns
+///
+/// - `Ok(Response)` — an HTMX redirect to the history of the entry.
+/// - `Err(Error::Auth(auth::Error::MissingRole(Role::Editor)))` — when the user is not an editor.
+/// - `Err(Error::Auth(auth::Error::NotAllowed))` — when the user may not roll back the entry.
+/// - `Err(Error::Model(model::Error::EntityNotFound { .. }))` — when there is no such entry or
+///   revision.
+pub(super) async fn rollback(
+    editor: RequireRole<Editor>,
+    State(mut mm): State<ModelManager>,
+    audit: AuditContext,
+    Path(id): Path<String>,
+    WRForm(RollbackPayload { revision }): WRForm<RollbackPayload>,
+) -> Result<Response> {
+    tracing::debug!(
+        "{:<12} -- POST entry::rollback {} {}",
+        "ROUTE",
+        id,
+        revision
+    );
+
+    let mut tx = mm.tx().await?;
+    let entry = EntryBmc::get_details(&mut tx, id.clone())
+        .always_send()
+        .await?;
+    ensure_visible(&mut mm, Some(&editor.0), &entry).await?;
+    if !can_rollback(&mut mm, &editor.0, &entry).await? {
+        return Err(Error::Auth(auth::Error::NotAllowed));
+    }
+    EntryBmc::restore_revision(&mut tx, &audit, id.clone(), revision)
+        .always_send()
+        .await?;
+    tx.commit().await?;
+
+    tracing::info!(
+        "{:<12} -- Entry {} rolled back to revision {} by {}",
+        "ENTRY",
+        id,
+        revision,
+        editor.0.user_id
+    );
+
+    Ok(history_redirect(&id))
+}
@@ -0,0 +1,120 @@
+use always_send::FutureExt;
+use axum::{
+    extract::State,
+    response::{IntoResponse, Response},
+};
+use axum_htmx::{HxRedirect, HxRequest};
+use nrs_webapp_core::data::entry::types::idtype::EntryType;
+use nrs_webapp_frontend::{maybe_document, views::pages::entry::submit::entry_submit_page};
+use serde::Deserialize;
+use sqlx::types::Json;
+use validator::Validate;
+
+use crate::{
+    Error, Result,
+    auth::session::Session,
+    extract::{doc_props::DocProps, with_rejection::WRVForm},
+    model::{
+        self, ModelManager,
+        audit_event::AuditContext,
+        entry::{EntryBmc, EntryForCreate, EntryStatus, alias::EntryAliasBmc},
+        user_role::Role,
+    },
+    validate::entry::{ENTRY_ID_REGEX, validate_entry_info},
+};
+
+/// Render the form submitting a new entry.
+pub(super) async fn submit_page(
+    hx_req: HxRequest,
+    DocProps(props): DocProps,
+    _session: Session,
+) -> Result<Response> {
+    tracing::debug!("{:<12} -- GET entry::submit", "ROUTE");
+    Ok(maybe_document(hx_req, props, entry_submit_page()).into_response())
+}
+
+#[derive(Deserialize, Validate)]
+pub(super) struct SubmitEntryPayload {
+    #[validate(regex(path = *ENTRY_ID_REGEX))]
+    id: String,
+    #[validate(length(min = 1, max = 512))]
+    title: String,
+    entry_type: EntryType,
+    #[validate(custom(function = validate_entry_info))]
+    entry_info: String,
+}
+
+/// Handle the entry submission form.
+///
+/// The entries submitted by moderators are approved right away; the others wait in the
+/// moderation queue, only visible to their submitter and the moderators.
+///
+/// # Returns
+///
+/// - `Ok(Response)` — an HTMX redirect to the new entry.
+/// - `Err(Error::Model(model::Error::EntryAlreadyExists))` — when the ID is taken by an entry or
+///   one of its aliases.
+///
+/// # Examples
+///
+/// ```no_run
+/// // POST /entry/new with `id`, `title`, `entry_type` and `entry_info`
+/// // -> HX-Redirect: /entry/<id>
+/// ```
+pub(super) async fn submit(
+    session: Session,
+    State(mut mm): State<ModelManager>,
+    audit: AuditContext,
+    WRVForm(SubmitEntryPayload {
+        id,
+        title,
+        entry_type,
+        entry_info,
+    }): WRVForm<SubmitEntryPayload>,
+) -> Result<Response> {
+    tracing::debug!("{:<12} -- POST entry::submit {}", "ROUTE", id);
+
+    // already validated as a JSON object
+    let entry_info =
+        serde_json::from_str(&entry_info).map_err(|err| Error::Unexpected(err.into()))?;
+    let status = if session.has_role(&mut mm, Role::Moderator).await? {
+        EntryStatus::Approved
+    } else {
+        EntryStatus::Pending
+    };
+
+    let mut tx = mm.tx().await?;
+    // an alias would shadow the new entry
+    if EntryAliasBmc::get_new_id(&mut tx, id.clone())
+        .always_send()
+        .await?
+        .is_some()
+    {
+        return Err(model::Error::EntryAlreadyExists.into());
+    }
+    EntryBmc::create_entry(
+        &mut tx,
+        &audit,
+        EntryForCreate {
+            id: id.clone(),
+            title,
+            entry_type,
+            entry_info: Json(entry_info),
+            added_by: session.user_id,
+            status,
+        },
+    )
+    .always_send()
+    .await?;
+    tx.commit().await?;
+
+    tracing::info!(
+        "{:<12} -- Entry {} submitted by {} ({})",
+        "ENTRY",
+        id,
+        session.user_id,
+        <&'static str>::from(status)
+    );
+
+    Ok((HxRedirect(format!("/entry/{id}")), ()).into_response())
+}
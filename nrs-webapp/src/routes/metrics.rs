@@ -0,0 +1,68 @@
+use axum::{
+    Router,
+    extract::State,
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use axum_extra::{
+    TypedHeader,
+    headers::{Authorization, authorization::Bearer},
+};
+use subtle::ConstantTimeEq;
+
+use crate::{model::ModelManager, monitoring};
+
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// Creates the router exposing the Prometheus metrics to the scrapers holding `token`.
+///
+/// # Examples
+///
+/// ```
+/// let _router = nrs_webapp::routes::metrics::router("secret".into());
+/// ```
+pub fn router(token: String) -> Router<ModelManager> {
+    Router::new().route(
+        "/",
+        get(
+            |State(mm): State<ModelManager>, auth: Option<TypedHeader<Authorization<Bearer>>>| async move {
+                metrics(&mm, &token, auth)
+            },
+        ),
+    )
+}
+
+/// Renders the metrics, if the request carries `token` as a bearer token.
+///
+/// # Examples
+///
+/// ```no_run
+/// // GET /metrics with `Authorization: Bearer <SERVICE_METRICS_TOKEN>`
+/// // -> 200 with the metrics in the Prometheus text format
+/// ```
+fn metrics(
+    mm: &ModelManager,
+    token: &str,
+    auth: Option<TypedHeader<Authorization<Bearer>>>,
+) -> Response {
+    tracing::debug!("{:<12} -- GET metrics", "ROUTE");
+
+    let authorized = auth.is_some_and(|TypedHeader(Authorization(bearer))| {
+        bool::from(bearer.token().as_bytes().ct_eq(token.as_bytes()))
+    });
+    if !authorized {
+        return (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+        )
+            .into_response();
+    }
+
+    match monitoring::render(mm) {
+        Some(metrics) => {
+            ([(header::CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE)], metrics).into_response()
+        }
+        None => StatusCode::SERVICE_UNAVAILABLE.into_response(),
+    }
+}
@@ -1,20 +1,32 @@
 #[cfg(debug_assertions)]
 mod dev;
 
+mod account;
+mod admin;
 mod auth;
+mod csp_report;
 mod entry;
 mod fallback;
+pub mod metrics;
+mod moderation;
 mod static_serve;
 
 use axum::{Router, response::IntoResponse, routing::get};
 use axum_htmx::HxRequest;
 use nrs_webapp_frontend::{maybe_document, views};
+use time::OffsetDateTime;
 
 use crate::{
     config::AppConfig,
     extract::doc_props::DocProps,
     middleware::{
-        mw_req_session::mw_req_session, mw_req_stamp::mw_req_stamp, mw_res_map::mw_res_mapper,
+        mw_csrf::{mw_csrf_check, mw_csrf_token},
+        mw_metrics::mw_metrics,
+        mw_rate_limit::mw_rate_limit,
+        mw_req_session::mw_req_session,
+        mw_req_stamp::mw_req_stamp,
+        mw_res_map::mw_res_mapper,
+        mw_sec_headers::{CSP_REPORT_PATH, mw_sec_headers},
     },
     model::ModelManager,
     routes::fallback::{fallback_handler, method_not_allowed_fallback_handler},
@@ -23,8 +35,10 @@ use crate::{
 /// Builds and returns the application's HTTP router with routes, middleware, and static services configured.
 ///
 /// The returned router mounts the root home handler at `/`, nests the authentication router under `/auth` (using
-/// the provided `ModelManager`), serves static assets under `/static`, and applies response mapping and request
-/// middleware. In debug builds an additional dev-only router is nested at `/__dev_only`. A fallback handler and a
+/// the provided `ModelManager`), the account management router under `/account`, the admin pages under `/admin` and the moderation queue under `/moderation`, serves static assets under
+/// `/static`, receives CSP violation reports at `/csp-report`, and applies CSRF protection, rate limiting, response
+/// mapping, request metrics and request middleware. The Prometheus metrics are served at `/metrics` when
+/// `SERVICE_METRICS_TOKEN` is set, to the scrapers sending it as a bearer token. Security headers are added to every response, static assets included. In debug builds an additional dev-only router is nested at `/__dev_only`. A fallback handler and a
 /// method-not-allowed handler are also registered.
 ///
 /// # Parameters
@@ -50,14 +64,32 @@ pub fn router(mm: ModelManager) -> Router<ModelManager> {
     let mut router = Router::<ModelManager>::new()
         .route("/", get(home))
         .nest("/auth", auth::router())
+        .nest("/account", account::router())
+        .nest("/admin", admin::router())
         .nest("/entry", entry::router())
+        .nest("/moderation", moderation::router())
+        .nest(CSP_REPORT_PATH, csp_report::router())
         .fallback(fallback_handler)
         .method_not_allowed_fallback(method_not_allowed_fallback_handler)
+        .layer(axum::middleware::from_fn_with_state(
+            mm.clone(),
+            mw_rate_limit,
+        ))
+        .layer(axum::middleware::from_fn(mw_csrf_check))
         .layer(axum::middleware::map_response(mw_res_mapper))
+        .layer(axum::middleware::from_fn(mw_metrics))
+        .layer(axum::middleware::from_fn_with_state(
+            mm.clone(),
+            mw_csrf_token,
+        ))
         .layer(axum::middleware::from_fn_with_state(mm, mw_req_session))
         .layer(axum::middleware::from_fn(mw_req_stamp))
         .layer(AppConfig::get().IP_SOURCE.clone().into_extension())
         .nest_service("/static", static_serve::service());
+    if let Some(token) = AppConfig::get().SERVICE_METRICS_TOKEN.clone() {
+        router = router.nest("/metrics", metrics::router(token));
+    }
+    router = router.layer(axum::middleware::from_fn(mw_sec_headers));
     #[cfg(debug_assertions)]
     {
         router = router.nest("/__dev_only", dev::dev_router());
@@ -87,3 +119,8 @@ async fn home(hx_req: HxRequest, DocProps(doc_props): DocProps) -> impl IntoResp
     tracing::debug!("{:<12} -- GET home", "ROUTE");
     maybe_document(hx_req, doc_props, views::pages::home::home())
 }
+
+/// Formats `timestamp` as shown on the pages, such as `2025-01-01 12:00:00.0`.
+fn format_timestamp(timestamp: OffsetDateTime) -> String {
+    format!("{} {}", timestamp.date(), timestamp.time())
+}
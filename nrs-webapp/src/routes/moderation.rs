@@ -0,0 +1,212 @@
+use always_send::FutureExt;
+use axum::{
+    Router,
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use axum_htmx::{HxRedirect, HxRequest};
+use nrs_webapp_frontend::{
+    maybe_document,
+    views::{
+        email::Locale,
+        pages::moderation::{PendingEntry, moderation_queue_page},
+    },
+};
+use serde::{Deserialize, Deserializer};
+use sqlbindable::{FieldNames, Fields};
+use sqlx::FromRow;
+use validator::Validate;
+
+use crate::{
+    Result,
+    auth::session::{Moderator, RequireRole},
+    extract::{doc_props::DocProps, with_rejection::WRVForm},
+    mail::{outbox, queue_entry_reviewed_mail},
+    model::{
+        ModelManager,
+        audit_event::AuditContext,
+        entity::DbBmcWithPkey,
+        entry::{EntryBmc, EntryReview},
+        user::UserBmc,
+    },
+    routes::format_timestamp,
+};
+
+/// Constructs the Router of the moderation queue of the submitted entries.
+///
+/// Every handler requires the moderator role, through `RequireRole<Moderator>`.
+pub fn router() -> Router<ModelManager> {
+    Router::new()
+        .route("/", get(queue))
+        .route("/{id}/approve", post(approve))
+        .route("/{id}/reject", post(reject))
+}
+
+#[derive(FromRow, FieldNames, Fields)]
+struct Submitter {
+    username: String,
+    email: String,
+}
+
+fn queue_redirect() -> Response {
+    (HxRedirect("/moderation".to_string()), ()).into_response()
+}
+
+/// Render the entries waiting for a moderator, the oldest submission first.
+///
+/// # Examples
+///
+/// ```no_run
+/// // GET /moderation
+/// ```
+async fn queue(
+    hx_req: HxRequest,
+    DocProps(props): DocProps,
+    _moderator: RequireRole<Moderator>,
+    State(mut mm): State<ModelManager>,
+) -> Result<Response> {
+    tracing::debug!("{:<12} -- GET moderation::queue", "ROUTE");
+
+    let entries = EntryBmc::list_pending(&mut mm)
+        .await?
+        .into_iter()
+        .map(|entry| PendingEntry {
+            id: entry.id,
+            title: entry.title,
+            entry_type: entry.entry_type,
+            submitted_by: entry.added_by.username,
+            submitted_at: format_timestamp(entry.submitted_at),
+        })
+        .collect::<Vec<_>>();
+
+    Ok(maybe_document(hx_req, props, moderation_queue_page(&entries)).into_response())
+}
+
+/// Record the review of a pending entry and notify its submitter, unless they deleted their
+/// account.
+async fn review(
+    mm: ModelManager,
+    audit: AuditContext,
+    id: String,
+    review: EntryReview,
+) -> Result<()> {
+    let mut tx = mm.tx().await?;
+    EntryBmc::review(&mut tx, &audit, id.clone(), review.clone())
+        .always_send()
+        .await?;
+    let entry = EntryBmc::get_details(&mut tx, id.clone())
+        .always_send()
+        .await?;
+    if let Some(user_id) = entry.added_by.id {
+        let submitter: Submitter = UserBmc::get(&mut tx, user_id).always_send().await?;
+        let rejection_reason = match &review {
+            EntryReview::Approve => None,
+            EntryReview::Reject { reason } => Some(reason.as_str()),
+        };
+        // the language of the submitter is unknown outside of their requests
+        queue_entry_reviewed_mail(
+            &mut tx,
+            &submitter.email,
+            &submitter.username,
+            &entry.id,
+            &entry.title,
+            rejection_reason,
+            Locale::default(),
+        )
+        .always_send()
+        .await?;
+    }
+    tx.commit().await?;
+    outbox::wake_worker();
+    Ok(())
+}
+
+/// Approve a pending entry, which lists it.
+///
+/// # Returns
+///
+/// - `Ok(Response)` — an HTMX redirect to the moderation queue.
+/// - `Err(Error::Model(model::Error::EntityNotFound { .. }))` — when there is no such pending
+///   entry.
+async fn approve(
+    moderator: RequireRole<Moderator>,
+    State(mm): State<ModelManager>,
+    audit: AuditContext,
+    Path(id): Path<String>,
+) -> Result<Response> {
+    tracing::debug!("{:<12} -- POST moderation::approve {}", "ROUTE", id);
+
+    review(mm, audit, id.clone(), EntryReview::Approve).await?;
+
+    tracing::info!(
+        "{:<12} -- Entry {} approved by {}",
+        "MODERATION",
+        id,
+        moderator.0.user_id
+    );
+
+    Ok(queue_redirect())
+}
+
+#[derive(Deserialize, Validate)]
+struct RejectPayload {
+    // trimmed before the validation, so that a blank reason is rejected
+    #[serde(deserialize_with = "deserialize_trimmed")]
+    #[validate(length(min = 1, max = 500))]
+    reason: String,
+}
+
+fn deserialize_trimmed<'de, D>(deserializer: D) -> core::result::Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = String::deserialize(deserializer)?;
+    Ok(value.trim().to_string())
+}
+
+/// Reject a pending entry for the given `reason`, which is sent to its submitter.
+///
+/// # Returns
+///
+/// - `Ok(Response)` — an HTMX redirect to the moderation queue.
+/// - `Err(Error::Model(model::Error::EntityNotFound { .. }))` — when there is no such pending
+///   entry.
+async fn reject(
+    moderator: RequireRole<Moderator>,
+    State(mm): State<ModelManager>,
+    audit: AuditContext,
+    Path(id): Path<String>,
+    WRVForm(RejectPayload { reason }): WRVForm<RejectPayload>,
+) -> Result<Response> {
+    tracing::debug!("{:<12} -- POST moderation::reject {}", "ROUTE", id);
+
+    review(mm, audit, id.clone(), EntryReview::Reject { reason }).await?;
+
+    tracing::info!(
+        "{:<12} -- Entry {} rejected by {}",
+        "MODERATION",
+        id,
+        moderator.0.user_id
+    );
+
+    Ok(queue_redirect())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn reject_reason_is_trimmed_before_validation() {
+        let blank: RejectPayload = serde_json::from_value(json!({ "reason": "   " })).unwrap();
+        assert!(blank.validate().is_err());
+
+        let padded: RejectPayload =
+            serde_json::from_value(json!({ "reason": "  Duplicate entry \n" })).unwrap();
+        assert!(padded.validate().is_ok());
+        assert_eq!(padded.reason, "Duplicate entry");
+    }
+}
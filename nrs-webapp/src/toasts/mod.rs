@@ -7,6 +7,11 @@ pub enum ConstToast {
     LoginAgainAfterEmailVerification,
     LoginAgainAfterEmailVerificationOAuth,
     LoginAgainAfterPasswordReset,
+    AccountUnlocked,
+    AccountDeleted,
+    OAuthAccountLinked,
+    OAuthAccountUnlinked,
+    PasswordResetLinkSent,
 }
 
 impl From<ConstToast> for Toast {
@@ -41,6 +46,33 @@ impl From<ConstToast> for Toast {
                 description:
                     rsx! {"Your password has been reset. Please log in again to continue."}.render(),
             },
+            ConstToast::AccountUnlocked => Toast {
+                kind: ToastKind::Success,
+                title: "Account Unlocked".to_string(),
+                description: rsx! {"You can log in with your password again."}.render(),
+            },
+            ConstToast::AccountDeleted => Toast {
+                kind: ToastKind::Info,
+                title: "Account Deleted".to_string(),
+                description: rsx! {"Your account and its personal data have been deleted."}
+                    .render(),
+            },
+            ConstToast::OAuthAccountLinked => Toast {
+                kind: ToastKind::Success,
+                title: "Account Linked".to_string(),
+                description: rsx! {"You can now log in with the linked provider."}.render(),
+            },
+            ConstToast::OAuthAccountUnlinked => Toast {
+                kind: ToastKind::Success,
+                title: "Account Unlinked".to_string(),
+                description: rsx! {"The provider can no longer be used to log in."}.render(),
+            },
+            ConstToast::PasswordResetLinkSent => Toast {
+                kind: ToastKind::Success,
+                title: "Password Reset Link Sent".to_string(),
+                description: rsx! {"The user has been emailed a link to reset their password."}
+                    .render(),
+            },
         }
     }
 }
@@ -0,0 +1,25 @@
+use regex_macro::{LazyRegex, lazy_regex};
+use validator::ValidationError;
+
+pub static ENTRY_ID_REGEX: LazyRegex = lazy_regex!(r"^[A-Za-z0-9_\-]{1,50}$");
+
+/// Validates that the info of an entry is a JSON object.
+///
+/// # Examples
+///
+/// ```
+/// use nrs_webapp::validate::entry::validate_entry_info;
+///
+/// assert!(validate_entry_info(r#"{"year": 2020}"#).is_ok());
+/// assert!(validate_entry_info("[1, 2]").is_err());
+/// assert!(validate_entry_info("{").is_err());
+/// ```
+pub fn validate_entry_info(entry_info: &str) -> Result<(), ValidationError> {
+    match serde_json::from_str::<serde_json::Value>(entry_info) {
+        Ok(serde_json::Value::Object(_)) => Ok(()),
+        Ok(_) => Err(ValidationError::new("entry_info_not_object")
+            .with_message("Entry info must be a JSON object".into())),
+        Err(_) => Err(ValidationError::new("entry_info_invalid_json")
+            .with_message("Entry info must be valid JSON".into())),
+    }
+}
@@ -25,7 +25,39 @@ pub fn derives_field_names(input: TokenStream) -> TokenStream {
     // -- Collect Elements
     let props = utils::get_props(fields);
 
-    let props_all_names: Vec<&String> = props.iter().map(|p| &p.name).collect();
+    let props_all_names: Vec<&String> = props
+        .iter()
+        .filter(|p| p.join.is_none())
+        .map(|p| &p.name)
+        .collect();
+
+    // -- The joined structs, only overriding the default when there are some
+    let joins: Vec<_> = props
+        .iter()
+        .filter_map(|p| {
+            let join = p.join.as_ref()?;
+            let (name, ty) = (&p.name, p.ty);
+            let (table, on, references) = (&join.table, &join.on, &join.references);
+            Some(quote! {
+                sqlbindable::Join {
+                    name: #name,
+                    table: #table,
+                    on: #on,
+                    references: #references,
+                    field_names: <#ty as sqlbindable::HasFieldNames>::field_names(),
+                },
+            })
+        })
+        .collect();
+    let joins_fn = if joins.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            fn joins() -> Vec<sqlbindable::Join> {
+                vec![#(#joins)*]
+            }
+        }
+    };
 
     // -- Compose the final code
     let output = quote! {
@@ -35,6 +67,8 @@ pub fn derives_field_names(input: TokenStream) -> TokenStream {
                 #props_all_names,
                 )*])
             }
+
+            #joins_fn
         }
     };
 
@@ -57,8 +91,9 @@ pub fn derives_fields(input: TokenStream) -> TokenStream {
         panic!("Only support Struct")
     };
 
-    // -- Collect Elements
+    // -- Collect Elements, the joined structs not being columns
     let props = utils::get_props(fields);
+    let props: Vec<_> = props.into_iter().filter(|p| p.join.is_none()).collect();
 
     let props_all_idents: Vec<&Option<Ident>> = props.iter().map(|p| p.ident).collect();
     let props_all_names: Vec<&String> = props.iter().map(|p| &p.name).collect();
@@ -130,3 +165,64 @@ pub fn derives_fields(input: TokenStream) -> TokenStream {
 
     output.into()
 }
+
+#[proc_macro_derive(Filter, attributes(filter))]
+pub fn derives_filter(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    let struct_name = ast.ident;
+
+    // -- get the fields
+    let fields = if let syn::Data::Struct(syn::DataStruct {
+        fields: syn::Fields::Named(ref fields),
+        ..
+    }) = ast.data
+    {
+        fields
+    } else {
+        panic!("Only support Struct")
+    };
+
+    // -- Collect Elements
+    let (table, props) = match (
+        utils::get_filter_table(&ast.attrs),
+        utils::get_filter_props(fields),
+    ) {
+        (Ok(table), Ok(props)) => (table, props),
+        (Err(err), _) | (_, Err(err)) => return err.to_compile_error().into(),
+    };
+    let table = match table {
+        Some(table) => quote! { Some(#table) },
+        None => quote! { None },
+    };
+
+    // -- One builder call per field, skipped when an option is None
+    let conditions = props.iter().map(|p| {
+        let ident = p.ident;
+        let column = &p.column;
+        let op = Ident::new(&p.op, proc_macro2::Span::call_site());
+        if p.is_option {
+            quote! {
+                if let Some(val) = self.#ident {
+                    builder.#op(#column, val)?;
+                }
+            }
+        } else {
+            quote! {
+                builder.#op(#column, self.#ident)?;
+            }
+        }
+    });
+
+    // -- Compose the final code
+    let output = quote! {
+        impl sqlbindable::Filter for #struct_name {
+            fn into_condition(self) -> core::result::Result<sqlbindable::filter::Condition, sqlbindable::TryIntoExprError> {
+                let mut builder = sqlbindable::FilterBuilder::new(#table);
+                #(#conditions)*
+                Ok(builder.build())
+            }
+        }
+    };
+
+    output.into()
+}
@@ -1,13 +1,22 @@
 use proc_macro2::Ident;
 use quote::ToTokens;
 use syn::punctuated::Punctuated;
-use syn::{Attribute, Expr, Field, FieldsNamed, Lit, Meta, Token};
+use syn::{Attribute, Expr, Field, FieldsNamed, Lit, Meta, Token, Type};
 
 // region:    --- Prop (i.e., sqlb Field)
 pub struct Prop<'a> {
     pub name: String,
     pub is_option: bool,
     pub ident: &'a Option<Ident>,
+    pub ty: &'a Type,
+    /// Set for a nested struct loaded from a joined table, which is not a column.
+    pub join: Option<PropJoin>,
+}
+
+pub struct PropJoin {
+    pub table: String,
+    pub on: String,
+    pub references: String,
 }
 
 pub fn get_props(fields: &FieldsNamed) -> Vec<Prop<'_>> {
@@ -47,6 +56,8 @@ pub fn get_props(fields: &FieldsNamed) -> Vec<Prop<'_>> {
             name,
             is_option,
             ident,
+            ty: &field.ty,
+            join: field_attr.join,
         })
     }
 
@@ -58,15 +69,20 @@ pub fn get_props(fields: &FieldsNamed) -> Vec<Prop<'_>> {
 pub struct PropAttr {
     pub skip: bool,
     pub name: Option<String>,
+    pub join: Option<PropJoin>,
 }
 
 // #[field(skip, name = "new_name")]
 // #[field(name = "new_name")]
+// #[field(join = "app_user", on = "added_by", references = "id")]
 pub fn get_prop_attr(field: &Field) -> Result<PropAttr, syn::Error> {
     let attribute = get_attribute(field, "field");
 
     let mut skip = false;
     let mut name: Option<String> = None;
+    let mut join_table: Option<String> = None;
+    let mut join_on: Option<String> = None;
+    let mut join_references: Option<String> = None;
 
     if let Some(attribute) = attribute {
         let nested = attribute.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
@@ -87,6 +103,21 @@ pub fn get_prop_attr(field: &Field) -> Result<PropAttr, syn::Error> {
                     }
                 }
 
+                // #[field(join=table)]
+                Meta::NameValue(nv) if nv.path.is_ident("join") => {
+                    join_table = Some(get_lit_str(&nv.value)?);
+                }
+
+                // #[field(on=column)]
+                Meta::NameValue(nv) if nv.path.is_ident("on") => {
+                    join_on = Some(get_lit_str(&nv.value)?);
+                }
+
+                // #[field(references=column)]
+                Meta::NameValue(nv) if nv.path.is_ident("references") => {
+                    join_references = Some(get_lit_str(&nv.value)?);
+                }
+
                 /* ... */
                 _ => {
                     return Err(syn::Error::new_spanned(meta, "unrecognized field"));
@@ -95,10 +126,179 @@ pub fn get_prop_attr(field: &Field) -> Result<PropAttr, syn::Error> {
         }
     }
 
-    Ok(PropAttr { skip, name })
+    let join = match (join_table, join_on) {
+        (Some(table), Some(on)) => Some(PropJoin {
+            table,
+            on,
+            references: join_references.unwrap_or_else(|| "id".to_string()),
+        }),
+        (None, None) if join_references.is_none() => None,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                field,
+                "a joined field needs both `join` and `on`",
+            ));
+        }
+    };
+
+    Ok(PropAttr { skip, name, join })
 }
 
 fn get_attribute<'a>(field: &'a Field, name: &str) -> Option<&'a Attribute> {
     field.attrs.iter().find(|a| a.path().is_ident(name))
 }
 // endregion: --- Attribute
+
+// region:    --- FilterProp (i.e., a condition of a Filter)
+pub struct FilterProp<'a> {
+    pub column: String,
+    /// The name of the `FilterBuilder` method of the operator.
+    pub op: String,
+    pub is_option: bool,
+    pub ident: &'a Option<Ident>,
+}
+
+/// The operators of the field name suffixes, the first match winning, along with what replaces
+/// the suffix in the column name: `created_after` compares the `created_at` column.
+const FILTER_SUFFIXES: &[(&str, &str, &str)] = &[
+    ("_contains", "contains", ""),
+    ("_is_null", "is_null", ""),
+    ("_after", "gt", "_at"),
+    ("_before", "lt", "_at"),
+    ("_gte", "gte", ""),
+    ("_lte", "lte", ""),
+    ("_gt", "gt", ""),
+    ("_lt", "lt", ""),
+    ("_ne", "ne", ""),
+    ("_eq", "eq", ""),
+    ("_in", "is_in", ""),
+];
+
+const FILTER_OPS: &[&str] = &[
+    "eq", "ne", "gt", "gte", "lt", "lte", "is_in", "contains", "is_null",
+];
+
+pub fn get_filter_props(fields: &FieldsNamed) -> Result<Vec<FilterProp<'_>>, syn::Error> {
+    let mut props = Vec::new();
+
+    for field in fields.named.iter() {
+        let attr = get_filter_prop_attr(field)?;
+        if attr.skip {
+            continue;
+        }
+
+        let ident = &field.ident;
+        let type_name = format!("{}", &field.ty.to_token_stream());
+        let is_option = type_name.starts_with("Option ");
+
+        let name = ident.as_ref().map(|i| i.to_string()).unwrap();
+        let (column, op) = match FILTER_SUFFIXES
+            .iter()
+            .find(|(suffix, _, _)| name.len() > suffix.len() && name.ends_with(suffix))
+        {
+            Some((suffix, op, column_suffix)) => (
+                format!("{}{column_suffix}", &name[..name.len() - suffix.len()]),
+                *op,
+            ),
+            None => (name.clone(), "eq"),
+        };
+
+        let op = attr.op.unwrap_or_else(|| op.to_string());
+        // a plain `bool` would always apply, its default selecting the rows with a value
+        if op == "is_null" && !is_option {
+            return Err(syn::Error::new_spanned(
+                &field.ty,
+                "is_null filters must be an `Option<bool>`",
+            ));
+        }
+
+        props.push(FilterProp {
+            column: attr.column.unwrap_or(column),
+            op,
+            is_option,
+            ident,
+        })
+    }
+
+    Ok(props)
+}
+
+pub struct FilterPropAttr {
+    pub skip: bool,
+    pub column: Option<String>,
+    pub op: Option<String>,
+}
+
+// #[filter(skip)]
+// #[filter(column = "created_at", op = "gte")]
+pub fn get_filter_prop_attr(field: &Field) -> Result<FilterPropAttr, syn::Error> {
+    let mut attr = FilterPropAttr {
+        skip: false,
+        column: None,
+        op: None,
+    };
+
+    if let Some(attribute) = get_attribute(field, "filter") {
+        let nested = attribute.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+
+        for meta in nested {
+            match meta {
+                Meta::Path(path) if path.is_ident("skip") => {
+                    attr.skip = true;
+                }
+
+                Meta::NameValue(nv) if nv.path.is_ident("column") => {
+                    attr.column = Some(get_lit_str(&nv.value)?);
+                }
+
+                Meta::NameValue(nv) if nv.path.is_ident("op") => {
+                    let op = get_lit_str(&nv.value)?;
+                    if !FILTER_OPS.contains(&op.as_str()) {
+                        return Err(syn::Error::new_spanned(
+                            nv.value,
+                            format!("unknown filter op, expected one of {FILTER_OPS:?}"),
+                        ));
+                    }
+                    attr.op = Some(op);
+                }
+
+                _ => {
+                    return Err(syn::Error::new_spanned(meta, "unrecognized filter"));
+                }
+            }
+        }
+    }
+
+    Ok(attr)
+}
+
+// #[filter(table = "entry")] on the struct
+pub fn get_filter_table(attrs: &[Attribute]) -> Result<Option<String>, syn::Error> {
+    let Some(attribute) = attrs.iter().find(|a| a.path().is_ident("filter")) else {
+        return Ok(None);
+    };
+    let nested = attribute.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)?;
+
+    let mut table = None;
+    for meta in nested {
+        match meta {
+            Meta::NameValue(nv) if nv.path.is_ident("table") => {
+                table = Some(get_lit_str(&nv.value)?);
+            }
+            _ => {
+                return Err(syn::Error::new_spanned(meta, "unrecognized filter"));
+            }
+        }
+    }
+    Ok(table)
+}
+
+fn get_lit_str(expr: &Expr) -> Result<String, syn::Error> {
+    if let Expr::Lit(exp_lit) = expr
+        && let Lit::Str(lit_str) = &exp_lit.lit
+    {
+        return Ok(lit_str.value());
+    }
+    Err(syn::Error::new_spanned(expr, "expected a string literal"))
+}
+// endregion: --- FilterProp (i.e., a condition of a Filter)
@@ -0,0 +1,163 @@
+pub use sea_query::Condition;
+use sea_query::{Expr, ExprTrait, Func, LikeExpr};
+
+use crate::{TryIntoExpr, TryIntoExprError};
+
+/// A set of conditions on the rows of a table, usually derived with
+/// [`Filter`](sqlbindable_macros::Filter).
+pub trait Filter {
+    /// The conditions of the filter, all of which the rows must meet.
+    fn into_condition(self) -> Result<Condition, TryIntoExprError>;
+}
+
+/// No filter: every row matches.
+impl Filter for () {
+    fn into_condition(self) -> Result<Condition, TryIntoExprError> {
+        Ok(Condition::all())
+    }
+}
+
+/// Composes the conditions of a filter, on the columns of an optional table.
+///
+/// This is what the code derived by [`Filter`](sqlbindable_macros::Filter) builds the conditions
+/// with, one method per operator.
+pub struct FilterBuilder {
+    table: Option<&'static str>,
+    condition: Condition,
+}
+
+impl FilterBuilder {
+    pub fn new(table: Option<&'static str>) -> Self {
+        Self {
+            table,
+            condition: Condition::all(),
+        }
+    }
+
+    fn col(&self, column: &'static str) -> Expr {
+        match self.table {
+            Some(table) => Expr::col((table, column)),
+            None => Expr::col(column),
+        }
+    }
+
+    fn add(&mut self, expr: Expr) -> &mut Self {
+        self.condition = std::mem::replace(&mut self.condition, Condition::all()).add(expr);
+        self
+    }
+
+    pub fn eq(
+        &mut self,
+        column: &'static str,
+        value: impl TryIntoExpr,
+    ) -> Result<&mut Self, TryIntoExprError> {
+        let expr = self.col(column).eq(value.into_expr()?);
+        Ok(self.add(expr))
+    }
+
+    pub fn ne(
+        &mut self,
+        column: &'static str,
+        value: impl TryIntoExpr,
+    ) -> Result<&mut Self, TryIntoExprError> {
+        let expr = self.col(column).ne(value.into_expr()?);
+        Ok(self.add(expr))
+    }
+
+    pub fn gt(
+        &mut self,
+        column: &'static str,
+        value: impl TryIntoExpr,
+    ) -> Result<&mut Self, TryIntoExprError> {
+        let expr = self.col(column).gt(value.into_expr()?);
+        Ok(self.add(expr))
+    }
+
+    pub fn gte(
+        &mut self,
+        column: &'static str,
+        value: impl TryIntoExpr,
+    ) -> Result<&mut Self, TryIntoExprError> {
+        let expr = self.col(column).gte(value.into_expr()?);
+        Ok(self.add(expr))
+    }
+
+    pub fn lt(
+        &mut self,
+        column: &'static str,
+        value: impl TryIntoExpr,
+    ) -> Result<&mut Self, TryIntoExprError> {
+        let expr = self.col(column).lt(value.into_expr()?);
+        Ok(self.add(expr))
+    }
+
+    pub fn lte(
+        &mut self,
+        column: &'static str,
+        value: impl TryIntoExpr,
+    ) -> Result<&mut Self, TryIntoExprError> {
+        let expr = self.col(column).lte(value.into_expr()?);
+        Ok(self.add(expr))
+    }
+
+    /// The column is one of `values`. An empty list is ignored rather than matching no row, as
+    /// it is what an empty query parameter deserializes to.
+    pub fn is_in<T: TryIntoExpr>(
+        &mut self,
+        column: &'static str,
+        values: Vec<T>,
+    ) -> Result<&mut Self, TryIntoExprError> {
+        if values.is_empty() {
+            return Ok(self);
+        }
+        let values = values
+            .into_iter()
+            .map(TryIntoExpr::into_expr)
+            .collect::<Result<Vec<_>, _>>()?;
+        let expr = self.col(column).is_in(values);
+        Ok(self.add(expr))
+    }
+
+    /// The column contains `value`, ignoring the case.
+    ///
+    /// The `LIKE` it compiles to is not supported on columns with a nondeterministic collation.
+    pub fn contains(
+        &mut self,
+        column: &'static str,
+        value: impl AsRef<str>,
+    ) -> Result<&mut Self, TryIntoExprError> {
+        let pattern = format!("%{}%", escape_like(&value.as_ref().to_lowercase()));
+        let expr = Func::lower(self.col(column)).like(LikeExpr::new(pattern).escape('\\'));
+        Ok(self.add(expr))
+    }
+
+    /// The column is `NULL` if `is_null`, and is not otherwise.
+    pub fn is_null(
+        &mut self,
+        column: &'static str,
+        is_null: bool,
+    ) -> Result<&mut Self, TryIntoExprError> {
+        let expr = if is_null {
+            self.col(column).is_null()
+        } else {
+            self.col(column).is_not_null()
+        };
+        Ok(self.add(expr))
+    }
+
+    pub fn build(self) -> Condition {
+        self.condition
+    }
+}
+
+/// Escapes the wildcards of `LIKE` in `value`, with `\` as the escape character.
+fn escape_like(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
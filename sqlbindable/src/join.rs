@@ -0,0 +1,58 @@
+use sea_query::{Expr, ExprTrait, JoinType, SelectExpr, SelectStatement};
+
+use crate::{FieldNameVec, HasFieldNames};
+
+/// A struct nested in another one, loaded from a table joined on a column of the other's table.
+///
+/// Declared with `#[field(join = "<table>", on = "<column>")]` on the nested field, whose name
+/// aliases both the joined table and its selected columns: the column `id` of a field `added_by`
+/// is selected as `"added_by.id"`, to be read with `#[sqlx(flatten)]`.
+pub struct Join {
+    /// The name of the nested field.
+    pub name: &'static str,
+    pub table: &'static str,
+    /// The column of the outer table referencing the joined one.
+    pub on: &'static str,
+    /// The referenced column of the joined table, `id` by default.
+    pub references: &'static str,
+    pub field_names: FieldNameVec,
+}
+
+pub trait SelectContext {
+    /// Select the fields of `E` from `table`, along with the fields of the structs it joins.
+    ///
+    /// The joins are left joins, so the fields of a nested struct are `NULL` when no row
+    /// matches. Nested structs cannot join further tables.
+    fn select_fields<E: HasFieldNames>(&mut self, table: &'static str) -> &mut Self;
+}
+
+impl SelectContext for SelectStatement {
+    fn select_fields<E: HasFieldNames>(&mut self, table: &'static str) -> &mut Self {
+        self.exprs(E::field_names().iter_copied().map(|col| SelectExpr {
+            expr: Expr::col((table, col)),
+            alias: Some(col.into()),
+            window: None,
+        }));
+
+        for join in E::joins() {
+            let aliases = join.field_names.add_prefix(join.name);
+            self.exprs(
+                join.field_names
+                    .iter_copied()
+                    .zip(aliases)
+                    .map(|(col, alias)| SelectExpr {
+                        expr: Expr::col((join.name, col)),
+                        alias: Some(alias.into()),
+                        window: None,
+                    }),
+            )
+            .join_as(
+                JoinType::LeftJoin,
+                join.table,
+                join.name,
+                Expr::col((table, join.on)).equals((join.name, join.references)),
+            );
+        }
+        self
+    }
+}
@@ -4,9 +4,20 @@ use thiserror::Error;
 use crate as sqlbindable;
 pub use sqlbindable_macros::FieldNames;
 pub use sqlbindable_macros::Fields;
+pub use sqlbindable_macros::Filter;
+
+pub mod filter;
+pub mod join;
+pub use filter::{Filter, FilterBuilder};
+pub use join::{Join, SelectContext};
 
 pub trait HasFieldNames {
     fn field_names() -> FieldNameVec;
+
+    /// The nested structs loaded from joined tables, which are not among the field names.
+    fn joins() -> Vec<Join> {
+        Vec::new()
+    }
 }
 
 pub trait HasFields: HasFieldNames {
@@ -253,6 +264,40 @@ impl BindContext for InsertStatement {
     }
 }
 
+/// Binds several rows at once, into a multi-row `VALUES`.
+pub trait BindRowsContext {
+    fn bind_rows(&mut self, rows: impl IntoIterator<Item = FieldVec>) -> &mut Self;
+}
+
+impl BindRowsContext for InsertStatement {
+    /// The columns are those of the first row.
+    ///
+    /// # Panics
+    ///
+    /// If the rows do not all have the same fields, in the same order, as with
+    /// [`HasFields::all_fields`].
+    fn bind_rows(&mut self, rows: impl IntoIterator<Item = FieldVec>) -> &mut Self {
+        let mut columns: Option<Vec<&'static str>> = None;
+        for row in rows {
+            let (names, values): (Vec<_>, Vec<SimpleExpr>) = row
+                .0
+                .into_iter()
+                .map(|field| (field.name, field.value))
+                .unzip();
+            match &columns {
+                Some(columns) => assert_eq!(columns, &names, "the rows have different fields"),
+                None => {
+                    self.columns(names.clone());
+                    columns = Some(names);
+                }
+            }
+            self.values(values)
+                .expect("names.len() == values.len(), this should not panic");
+        }
+        self
+    }
+}
+
 impl BindContext for UpdateStatement {
     fn bind(&mut self, fields: FieldVec) -> &mut Self {
         let fields: Vec<_> = fields
@@ -268,7 +313,10 @@ impl BindContext for UpdateStatement {
 mod tests {
     use std::marker::PhantomData;
 
-    use crate::{self as sqlbindable, BindContext, HasFieldNames, HasFields};
+    use crate::{
+        self as sqlbindable, BindContext, BindRowsContext, Filter, HasFieldNames, HasFields,
+        SelectContext,
+    };
     use sea_query::PostgresQueryBuilder;
     use sqlbindable_macros::{FieldNames, Fields};
 
@@ -314,6 +362,20 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_bind_rows() {
+        let rows =
+            [(1, Some(2)), (3, None)].map(|(x, y)| Test { x, y, z: None }.all_fields().unwrap());
+        let query = sea_query::Query::insert()
+            .into_table("test")
+            .bind_rows(rows)
+            .to_owned();
+        assert_eq!(
+            query.to_string(PostgresQueryBuilder),
+            r#"INSERT INTO "test" ("x", "y", "z") VALUES (1, 2, NULL), (3, NULL, NULL)"#
+        );
+    }
+
     #[test]
     fn test_field_names() {
         let field_names = TestNameOnly::field_names();
@@ -324,4 +386,98 @@ mod tests {
         let prefixed_names: Vec<String> = prefixed_field_names.iter_cloned().collect();
         assert_eq!(prefixed_names, vec!["prefix.x", "prefix.y", "prefix.z"]);
     }
+
+    #[derive(FieldNames)]
+    #[allow(dead_code)]
+    pub struct TestAuthor {
+        id: Option<i32>,
+        name: Option<String>,
+    }
+
+    #[derive(FieldNames, Fields)]
+    #[allow(dead_code)]
+    pub struct TestPost {
+        id: i32,
+        title: String,
+        #[field(join = "author", on = "author_id")]
+        author: TestAuthor,
+        #[field(join = "author", on = "editor_name", references = "name")]
+        editor: TestAuthor,
+    }
+
+    #[derive(Default, Filter)]
+    #[filter(table = "test")]
+    pub struct TestFilter {
+        name_contains: Option<String>,
+        kind_in: Option<Vec<i32>>,
+        created_after: Option<i64>,
+        #[filter(column = "created_at", op = "lte")]
+        until: Option<i64>,
+        deleted_is_null: Option<bool>,
+        #[filter(skip)]
+        #[allow(dead_code)]
+        page: u32,
+    }
+
+    #[test]
+    fn test_joins() {
+        let names: Vec<&'static str> = TestPost::field_names().iter_copied().collect();
+        assert_eq!(names, vec!["id", "title"]);
+
+        let query = sea_query::Query::select()
+            .from("post")
+            .select_fields::<TestPost>("post")
+            .to_owned();
+        assert_eq!(
+            query.to_string(PostgresQueryBuilder),
+            [
+                r#"SELECT "post"."id" AS "id", "post"."title" AS "title","#,
+                r#""author"."id" AS "author.id", "author"."name" AS "author.name","#,
+                r#""editor"."id" AS "editor.id", "editor"."name" AS "editor.name""#,
+                r#"FROM "post""#,
+                r#"LEFT JOIN "author" AS "author" ON "post"."author_id" = "author"."id""#,
+                r#"LEFT JOIN "author" AS "editor" ON "post"."editor_name" = "editor"."name""#,
+            ]
+            .join(" ")
+        );
+    }
+
+    #[test]
+    fn test_filter() {
+        let query = |filter: TestFilter| {
+            sea_query::Query::select()
+                .column("x")
+                .from("test")
+                .cond_where(filter.into_condition().unwrap())
+                .to_string(PostgresQueryBuilder)
+        };
+
+        assert_eq!(
+            query(TestFilter::default()),
+            r#"SELECT "x" FROM "test" WHERE TRUE"#
+        );
+
+        let filter = TestFilter {
+            name_contains: Some("50%_Off".to_string()),
+            kind_in: Some(vec![1, 2]),
+            created_after: Some(3),
+            until: Some(4),
+            deleted_is_null: Some(true),
+            page: 5,
+        };
+        assert_eq!(
+            query(filter),
+            r#"SELECT "x" FROM "test" WHERE LOWER("test"."name") LIKE E'%50\\%\\_off%' ESCAPE E'\\' AND "test"."kind" IN (1, 2) AND "test"."created_at" > 3 AND "test"."created_at" <= 4 AND "test"."deleted" IS NULL"#
+        );
+
+        let filter = TestFilter {
+            kind_in: Some(vec![]),
+            deleted_is_null: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(
+            query(filter),
+            r#"SELECT "x" FROM "test" WHERE "test"."deleted" IS NOT NULL"#
+        );
+    }
 }